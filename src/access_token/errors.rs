@@ -0,0 +1,36 @@
+use thiserror::Error;
+
+/// Self-contained access token encoding and decoding errors.
+#[derive(Error, Debug)]
+pub enum AccessTokenError {
+    /// Failed to read or write a field value. The token is likely truncated.
+    #[error("Failed to read or write field value.")]
+    ReadWriteFailure(#[from] std::io::Error),
+    /// The long-term key length doesn't match with the AEAD algorithm.
+    #[error("Invalid AEAD key length: {actual}, expected: {expected}.")]
+    InvalidKeyLength {
+        /// Key length required by the AEAD algorithm
+        expected: usize,
+        /// Provided key length
+        actual: usize,
+    },
+    /// The nonce length is not supported by the AEAD algorithm.
+    #[error("Invalid AEAD nonce length: {0}.")]
+    InvalidNonceLength(usize),
+    /// The MAC key doesn't fit into the 16-bit key length field.
+    #[error("MAC key is too long: {0} bytes.")]
+    MacKeyTooLong(usize),
+    /// The authentication tag of the encrypted block doesn't match.
+    /// Either the long-term key or the STUN server name is incorrect, or the token has been tampered with.
+    #[error("Failed to authenticate the encrypted block.")]
+    AuthenticationFailure(),
+    /// The MAC key is neither 20 bytes (HMAC-SHA1) nor 32 bytes (HMAC-SHA256) long.
+    #[error("Invalid MAC key length: {0}.")]
+    InvalidMacKeyLength(usize),
+    /// The lifetime of the token has elapsed.
+    #[error("The access token has expired.")]
+    Expired(),
+    /// The decrypted block contains data after the lifetime field.
+    #[error("Unexpected trailing data in the encrypted block.")]
+    TrailingData(),
+}
@@ -0,0 +1,5 @@
+mod errors;
+mod token;
+
+pub use errors::AccessTokenError;
+pub use token::{AccessToken, AccessTokenAlgorithm};
@@ -0,0 +1,267 @@
+use byteorder::{NetworkEndian, ReadBytesExt, WriteBytesExt};
+use crypto::aead::{AeadDecryptor, AeadEncryptor};
+use crypto::aes::KeySize;
+use crypto::aes_gcm::AesGcm;
+use rand::Rng;
+use std::io::{Cursor, Read};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::errors::AccessTokenError;
+
+/// Nonce length of the AES-GCM algorithms
+const AEAD_NONCE_SIZE: usize = 12;
+/// Authentication tag length of the AES-GCM algorithms
+const AEAD_TAG_SIZE: usize = 16;
+
+/// AEAD algorithm protecting the self-contained token, as per [RFC7635 Section 6.2](https://tools.ietf.org/html/rfc7635#section-6.2)
+///
+/// The algorithm and the long-term key are shared between the authorization server and the STUN server.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum AccessTokenAlgorithm {
+    /// AEAD_AES_128_GCM as defined in [RFC5116](https://tools.ietf.org/html/rfc5116#section-5.1)
+    Aes128Gcm,
+    /// AEAD_AES_256_GCM as defined in [RFC5116](https://tools.ietf.org/html/rfc5116#section-5.2)
+    Aes256Gcm,
+}
+
+impl AccessTokenAlgorithm {
+    /// Returns the length of the long-term key used by the algorithm
+    pub fn key_len(self) -> usize {
+        match self {
+            Self::Aes128Gcm => 16,
+            Self::Aes256Gcm => 32,
+        }
+    }
+
+    // Makes sure the long-term key matches the algorithm and returns the corresponding AES key size
+    fn key_size(self, key: &[u8]) -> Result<KeySize, AccessTokenError> {
+        if key.len() != self.key_len() {
+            return Err(AccessTokenError::InvalidKeyLength {
+                expected: self.key_len(),
+                actual: key.len(),
+            });
+        }
+
+        Ok(match self {
+            Self::Aes128Gcm => KeySize::KeySize128,
+            Self::Aes256Gcm => KeySize::KeySize256,
+        })
+    }
+}
+
+/// [RFC7635: Self-contained token](https://tools.ietf.org/html/rfc7635#section-6.2)
+///
+/// The token is issued by the authorization server and conveyed by the client
+/// to the STUN server within the ACCESS-TOKEN attribute. Its encrypted block
+/// carries the key the client and the STUN server use for MESSAGE-INTEGRITY,
+/// replacing the long-term credential key derived from a password.
+///
+///```text
+///      struct {
+///          uint16_t nonce_length;
+///          opaque nonce[nonce_length];
+///          opaque {
+///              uint16_t key_length;
+///              opaque mac_key[key_length];
+///              uint64_t timestamp;
+///              uint32_t lifetime;
+///          } encrypted_block;
+///      } token;
+///```
+///
+/// An example of issuing a token and verifying a request with it:
+/// ```
+/// # use std::time::SystemTime;
+/// # use stun_coder::{AccessToken, AccessTokenAlgorithm, StunAttribute, StunMessage, StunMessageRef};
+/// // Long-term key shared by the authorization server and the STUN server
+/// let long_term_key = [0x42; 16];
+///
+/// // The authorization server issues a token to the client
+/// let token = AccessToken::new(vec![0x11; 20], SystemTime::now(), 3600);
+/// let encrypted_token = token
+///     .encrypt(AccessTokenAlgorithm::Aes128Gcm, &long_term_key, "turn.example.org")
+///     .unwrap();
+///
+/// // The client authenticates its request with the MAC key of the token
+/// let request = StunMessage::create_request()
+///     .add_attribute(StunAttribute::Username {
+///         value: String::from("key-id"),
+///     })
+///     .add_attribute(StunAttribute::AccessToken {
+///         value: encrypted_token,
+///     })
+///     .add_message_integrity()
+///     .encode_with_key(Some(&token.mac_key))
+///     .unwrap();
+///
+/// // The STUN server decrypts the token and verifies the request
+/// let (decoded, received_token) =
+///     StunMessageRef::decode_with_access_token(&request, "turn.example.org", SystemTime::now(), |kid| {
+///         assert_eq!(kid, "key-id");
+///         Some((AccessTokenAlgorithm::Aes128Gcm, long_term_key.to_vec()))
+///     })
+///     .unwrap();
+///
+/// assert_eq!(received_token.mac_key, token.mac_key);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct AccessToken {
+    /// AEAD nonce used for encrypting the token
+    pub nonce: [u8; AEAD_NONCE_SIZE],
+    /// Key used by the client and the STUN server for MESSAGE-INTEGRITY.
+    /// It is 20 bytes long for HMAC-SHA-1 and 32 bytes long for HMAC-SHA-256-128.
+    pub mac_key: Vec<u8>,
+    /// Time the token has been issued at. The first 48 bits contain the seconds since the Unix epoch,
+    /// the remaining 16 bits contain the 1/64000 fractions of a second.
+    pub timestamp: u64,
+    /// Token lifetime in seconds
+    pub lifetime: u32,
+}
+
+impl AccessToken {
+    /// Creates a new token with a randomly generated nonce
+    ///
+    /// Arguments:
+    ///
+    /// * `mac_key`: Key used for MESSAGE-INTEGRITY
+    /// * `issued_at`: Issue time of the token
+    /// * `lifetime`: Token lifetime in seconds
+    pub fn new(mac_key: Vec<u8>, issued_at: SystemTime, lifetime: u32) -> Self {
+        Self {
+            nonce: rand::thread_rng().gen(),
+            mac_key,
+            timestamp: Self::encode_timestamp(issued_at),
+            lifetime,
+        }
+    }
+
+    /// Returns the time the token has been issued at
+    pub fn get_issued_at(&self) -> SystemTime {
+        let secs = self.timestamp >> 16;
+        let nanos = (self.timestamp & 0xFFFF) * 1_000_000_000 / 64000;
+
+        UNIX_EPOCH + Duration::from_secs(secs) + Duration::from_nanos(nanos)
+    }
+
+    /// Checks whether the lifetime of the token has elapsed at the provided time
+    pub fn is_expired(&self, now: SystemTime) -> bool {
+        now > self.get_issued_at() + Duration::from_secs(self.lifetime as u64)
+    }
+
+    /// Encodes and encrypts the token
+    ///
+    /// Arguments:
+    ///
+    /// * `algorithm`: AEAD algorithm
+    /// * `key`: Long-term key shared by the authorization server and the STUN server
+    /// * `server_name`: STUN server name, used as AEAD associated data
+    pub fn encrypt(
+        &self,
+        algorithm: AccessTokenAlgorithm,
+        key: &[u8],
+        server_name: &str,
+    ) -> Result<Vec<u8>, AccessTokenError> {
+        let key_size = algorithm.key_size(key)?;
+
+        if self.mac_key.len() > u16::MAX as usize {
+            return Err(AccessTokenError::MacKeyTooLong(self.mac_key.len()));
+        }
+
+        // Encode the block that is going to be encrypted
+        let mut block = Vec::with_capacity(2 + self.mac_key.len() + 8 + 4);
+        block.write_u16::<NetworkEndian>(self.mac_key.len() as u16)?;
+        block.extend_from_slice(&self.mac_key);
+        block.write_u64::<NetworkEndian>(self.timestamp)?;
+        block.write_u32::<NetworkEndian>(self.lifetime)?;
+
+        let mut token = Vec::with_capacity(2 + AEAD_NONCE_SIZE + block.len() + AEAD_TAG_SIZE);
+        token.write_u16::<NetworkEndian>(AEAD_NONCE_SIZE as u16)?;
+        token.extend_from_slice(&self.nonce);
+
+        let mut encrypted_block = vec![0; block.len()];
+        let mut tag = [0; AEAD_TAG_SIZE];
+        AesGcm::new(key_size, key, &self.nonce, server_name.as_bytes()).encrypt(
+            &block,
+            &mut encrypted_block,
+            &mut tag,
+        );
+
+        token.extend_from_slice(&encrypted_block);
+        token.extend_from_slice(&tag);
+
+        Ok(token)
+    }
+
+    /// Decrypts and decodes the token
+    ///
+    /// Arguments:
+    ///
+    /// * `token`: Value of the ACCESS-TOKEN attribute
+    /// * `algorithm`: AEAD algorithm
+    /// * `key`: Long-term key shared by the authorization server and the STUN server
+    /// * `server_name`: STUN server name, used as AEAD associated data
+    pub fn decrypt(
+        token: &[u8],
+        algorithm: AccessTokenAlgorithm,
+        key: &[u8],
+        server_name: &str,
+    ) -> Result<Self, AccessTokenError> {
+        let key_size = algorithm.key_size(key)?;
+        let mut cursor = Cursor::new(token);
+
+        let nonce_len = cursor.read_u16::<NetworkEndian>()? as usize;
+        if nonce_len != AEAD_NONCE_SIZE {
+            return Err(AccessTokenError::InvalidNonceLength(nonce_len));
+        }
+
+        let mut nonce = [0; AEAD_NONCE_SIZE];
+        cursor.read_exact(&mut nonce)?;
+
+        // The encrypted block is followed by the authentication tag
+        let encrypted = &token[cursor.position() as usize..];
+        if encrypted.len() < AEAD_TAG_SIZE {
+            return Err(AccessTokenError::ReadWriteFailure(
+                std::io::ErrorKind::UnexpectedEof.into(),
+            ));
+        }
+        let (encrypted_block, tag) = encrypted.split_at(encrypted.len() - AEAD_TAG_SIZE);
+
+        let mut block = vec![0; encrypted_block.len()];
+        if !AesGcm::new(key_size, key, &nonce, server_name.as_bytes()).decrypt(
+            encrypted_block,
+            &mut block,
+            tag,
+        ) {
+            return Err(AccessTokenError::AuthenticationFailure());
+        }
+
+        // Decode the decrypted block
+        let mut cursor = Cursor::new(&block[..]);
+
+        let mac_key_len = cursor.read_u16::<NetworkEndian>()? as usize;
+        let mut mac_key = vec![0; mac_key_len];
+        cursor.read_exact(&mut mac_key)?;
+
+        let timestamp = cursor.read_u64::<NetworkEndian>()?;
+        let lifetime = cursor.read_u32::<NetworkEndian>()?;
+
+        if cursor.position() != block.len() as u64 {
+            return Err(AccessTokenError::TrailingData());
+        }
+
+        Ok(Self {
+            nonce,
+            mac_key,
+            timestamp,
+            lifetime,
+        })
+    }
+
+    // Converts the time into the 48.16 fixed-point timestamp format
+    fn encode_timestamp(time: SystemTime) -> u64 {
+        let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+        let fraction = since_epoch.subsec_nanos() as u64 * 64000 / 1_000_000_000;
+
+        (since_epoch.as_secs() << 16) | fraction
+    }
+}
@@ -0,0 +1,49 @@
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+/// [TURN address family](https://tools.ietf.org/html/rfc8656#section-18.10)
+///
+/// Identifies the address family of a relayed transport address
+/// in the REQUESTED-ADDRESS-FAMILY, ADDITIONAL-ADDRESS-FAMILY and ADDRESS-ERROR-CODE attributes.
+///
+/// The address families are:
+///
+/// 0x01: IPv4
+/// 0x02: IPv6
+///
+/// Any other value is represented by the `Other` variant.
+///
+///```
+/// use stun_coder::StunAddressFamily;
+///
+/// assert_eq!(StunAddressFamily::from(0x02), StunAddressFamily::Ipv6);
+/// assert_eq!(u8::from(StunAddressFamily::Ipv4), 0x01);
+///```
+pub enum StunAddressFamily {
+    /// IPv4 address family
+    Ipv4,
+    /// IPv6 address family
+    Ipv6,
+    /// Address family not known to this implementation
+    Other(u8),
+}
+
+impl From<u8> for StunAddressFamily {
+    /// Converts an address family number into an address family
+    fn from(family: u8) -> Self {
+        match family {
+            0x01 => Self::Ipv4,
+            0x02 => Self::Ipv6,
+            other => Self::Other(other),
+        }
+    }
+}
+
+impl From<StunAddressFamily> for u8 {
+    /// Converts an address family into its number
+    fn from(family: StunAddressFamily) -> Self {
+        match family {
+            StunAddressFamily::Ipv4 => 0x01,
+            StunAddressFamily::Ipv6 => 0x02,
+            StunAddressFamily::Other(other) => other,
+        }
+    }
+}
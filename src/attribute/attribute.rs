@@ -1,5 +1,7 @@
 use std::net::SocketAddr;
 
+use super::{StunAddressFamily, StunPasswordAlgorithm};
+
 /// [STUN message attribute](https://tools.ietf.org/html/rfc5389#section-15)
 ///
 ///   After the STUN header are zero or more attributes.  Each attribute
@@ -34,7 +36,7 @@ use std::net::SocketAddr;
 ///   The rest of this section describes the format of the various
 ///   attributes defined in this specification.
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum StunAttribute {
     /// [RFC5389: MAPPED-ADDRESS](https://tools.ietf.org/html/rfc5389#section-15.1)
     ///
@@ -299,4 +301,590 @@ pub enum StunAttribute {
         /// Tiebreaker value used for ICE role conflict resolution defined in [Section 7.3.1.1 of RFC8445](https://tools.ietf.org/html/rfc8445#section-7.3.1.1)
         tie_breaker: u64,
     },
+    /// [RFC8656: CHANNEL-NUMBER](https://tools.ietf.org/html/rfc8656#section-18.1)
+    ///
+    /// The CHANNEL-NUMBER attribute contains the number of the channel.  The
+    /// value portion of this attribute is 4 bytes long and consists of a
+    /// 16-bit unsigned integer followed by a two-octet RFFU (Reserved For
+    /// Future Use) field, which MUST be set to 0 on transmission and MUST be
+    /// ignored on reception.
+    ChannelNumber {
+        /// Number of the channel.
+        number: u16,
+    },
+    /// [RFC8656: LIFETIME](https://tools.ietf.org/html/rfc8656#section-18.2)
+    ///
+    /// The LIFETIME attribute represents the duration for which the server
+    /// will maintain an allocation in the absence of a refresh.  The TURN
+    /// client can include the LIFETIME attribute with the desired lifetime
+    /// in Allocate and Refresh requests.  The value portion of this attribute
+    /// is 4 bytes long and consists of a 32-bit unsigned integral value
+    /// representing the number of seconds remaining until expiration.
+    Lifetime {
+        /// Number of seconds remaining until expiration.
+        seconds: u32,
+    },
+    /// [RFC8656: XOR-PEER-ADDRESS](https://tools.ietf.org/html/rfc8656#section-18.3)
+    ///
+    /// The XOR-PEER-ADDRESS specifies the address and port of the peer as
+    /// seen from the TURN server.  (For example, the peer's server-reflexive
+    /// transport address if the peer is behind a NAT.)  It is encoded in the
+    /// same way as XOR-MAPPED-ADDRESS.
+    XorPeerAddress {
+        /// Transport address of the peer as seen from the TURN server.
+        socket_addr: SocketAddr,
+    },
+    /// [RFC8656: DATA](https://tools.ietf.org/html/rfc8656#section-18.4)
+    ///
+    /// The DATA attribute is present in all Send indications.  If the
+    /// ICMP attribute is not present in a Data indication, it contains a
+    /// DATA attribute.  The value portion of this attribute is variable
+    /// length and consists of the application data (that is, the data that
+    /// would immediately follow the UDP header if the data was sent directly
+    /// between the client and the peer).
+    Data {
+        /// Application data.
+        value: Vec<u8>,
+    },
+    /// [RFC8656: XOR-RELAYED-ADDRESS](https://tools.ietf.org/html/rfc8656#section-18.5)
+    ///
+    /// The XOR-RELAYED-ADDRESS is present in Allocate responses.  It
+    /// specifies the address and port that the server allocated to the
+    /// client.  It is encoded in the same way as XOR-MAPPED-ADDRESS.
+    XorRelayedAddress {
+        /// Relayed transport address allocated to the client.
+        socket_addr: SocketAddr,
+    },
+    /// [RFC8656: EVEN-PORT](https://tools.ietf.org/html/rfc8656#section-18.6)
+    ///
+    /// This attribute allows the client to request that the port in the
+    /// relayed transport address be even and (optionally) that the server
+    /// reserve the next-higher port number.  The value portion of this
+    /// attribute is 1 byte long.
+    ///
+    /// The R bit is set to 1 if the server is requested to reserve the
+    /// next-higher port number (on the same IP address) for a subsequent
+    /// allocation.  The remaining 7 RFFU bits MUST be set to 0 on
+    /// transmission and MUST be ignored on reception.
+    EvenPort {
+        /// The R bit. Requests the server to reserve the next-higher port number.
+        reserve_next: bool,
+    },
+    /// [RFC8656: REQUESTED-TRANSPORT](https://tools.ietf.org/html/rfc8656#section-18.7)
+    ///
+    /// This attribute is used by the client to request a specific transport
+    /// protocol for the allocated transport address.  The Protocol field
+    /// specifies the desired protocol.  The codepoints used in this field
+    /// are taken from those allowed in the Protocol field in the IPv4
+    /// header and the NextHeader field in the IPv6 header [Protocol-Numbers](https://www.iana.org/assignments/protocol-numbers).
+    /// This specification only allows the use of codepoint 17 (User Datagram Protocol).
+    ///
+    /// The RFFU field MUST be set to zero on transmission and MUST be
+    /// ignored on reception.
+    RequestedTransport {
+        /// Desired transport protocol number.
+        protocol: u8,
+    },
+    /// [RFC8656: DONT-FRAGMENT](https://tools.ietf.org/html/rfc8656#section-18.8)
+    ///
+    /// This attribute is used by the client to request that the server set
+    /// the DF (Don't Fragment) bit in the IP header when relaying the
+    /// application data onward to the peer and for determining the server
+    /// capability in Allocate requests.  This attribute has no value part,
+    /// and thus, the attribute length field is 0.
+    DontFragment,
+    /// [RFC8656: RESERVATION-TOKEN](https://tools.ietf.org/html/rfc8656#section-18.9)
+    ///
+    /// The RESERVATION-TOKEN attribute contains a token that uniquely
+    /// identifies a relayed transport address being held in reserve by the
+    /// server.  The server includes this attribute in a success response to
+    /// tell the client about the token, and the client includes this
+    /// attribute in a subsequent Allocate request to request the server use
+    /// that relayed transport address for the allocation.
+    ///
+    /// The attribute value is 8 bytes and contains the token value.
+    ReservationToken {
+        /// Token identifying the reserved relayed transport address.
+        token: u64,
+    },
+    /// Attribute of a type that is not known to this implementation
+    ///
+    /// Comprehension-optional attributes that aren't recognized by the decoder are preserved as raw TLVs,
+    /// so that re-encoding a decoded message reproduces their type and value byte for byte.
+    /// The padding isn't preserved: as with every other attribute, it is written back as NULL bytes.
+    Unknown {
+        /// Attribute type value
+        attr_type: u16,
+        /// Raw attribute value without padding
+        value: Vec<u8>,
+    },
+    /// [RFC8489: MESSAGE-INTEGRITY-SHA256](https://tools.ietf.org/html/rfc8489#section-14.6)
+    ///
+    /// The MESSAGE-INTEGRITY-SHA256 attribute contains an HMAC-SHA256
+    /// [RFC2104](https://tools.ietf.org/html/rfc2104) of the STUN message.  The MESSAGE-INTEGRITY-SHA256
+    /// attribute can be present in any STUN message type.  The MESSAGE-
+    /// INTEGRITY-SHA256 attribute contains an initial portion of the HMAC-
+    /// SHA-256 [RFC2104](https://tools.ietf.org/html/rfc2104) of the STUN message.  The value will be at most
+    /// 32 bytes, but it MUST be at least 16 bytes and MUST be a multiple of
+    /// 4 bytes.  The value must be the full 32 bytes unless the STUN Usage
+    /// explicitly specifies that truncation is allowed.
+    ///
+    /// The text used as input to HMAC is the STUN message, up to and
+    /// including the attribute preceding the MESSAGE-INTEGRITY-SHA256
+    /// attribute.  The Length field of the STUN message header is adjusted
+    /// to point to the end of the MESSAGE-INTEGRITY-SHA256 attribute.
+    ///
+    /// When encoding, an empty or zero-filled value is treated as a placeholder that is replaced
+    /// with the computed HMAC truncated to the placeholder length (32 bytes for an empty value).
+    MessageIntegritySha256 {
+        /// Initial portion of the HMAC-SHA256 ([RFC2104](https://tools.ietf.org/html/rfc2104)) of the STUN message.
+        key: Vec<u8>,
+    },
+    /// [RFC8489: PASSWORD-ALGORITHM](https://tools.ietf.org/html/rfc8489#section-14.12)
+    ///
+    /// The PASSWORD-ALGORITHM attribute is present only in requests.  It
+    /// contains the algorithm that the server must use to derive a key from
+    /// the long-term password.
+    PasswordAlgorithm {
+        /// Algorithm used for the long-term credential key derivation
+        algorithm: StunPasswordAlgorithm,
+        /// Algorithm specific parameters. Empty for MD5 and SHA-256.
+        parameters: Vec<u8>,
+    },
+    /// [RFC8489: PASSWORD-ALGORITHMS](https://tools.ietf.org/html/rfc8489#section-14.11)
+    ///
+    /// The PASSWORD-ALGORITHMS attribute may be present in requests and
+    /// responses.  It contains the list of algorithms that the server can
+    /// use to derive the long-term password.
+    PasswordAlgorithms {
+        /// Supported algorithms along with their parameters, in order of preference
+        algorithms: Vec<(StunPasswordAlgorithm, Vec<u8>)>,
+    },
+    /// [RFC8489: USERHASH](https://tools.ietf.org/html/rfc8489#section-14.4)
+    ///
+    /// The USERHASH attribute is used as a replacement for the USERNAME
+    /// attribute when username anonymity is supported.
+    ///
+    /// The value of USERHASH has a fixed length of 32 bytes.  The username
+    /// MUST have been processed using the OpaqueString profile [RFC8265](https://tools.ietf.org/html/rfc8265),
+    /// and the realm MUST have been processed using the OpaqueString profile
+    /// [RFC8265](https://tools.ietf.org/html/rfc8265) before hashing.
+    ///
+    /// The following is the operation that the client will perform to hash
+    /// the username:
+    ///
+    /// userhash = SHA-256(OpaqueString(username) ":" OpaqueString(realm))
+    Userhash {
+        /// SHA-256 hash of the username and realm
+        value: [u8; 32],
+    },
+    /// [RFC5780: CHANGE-REQUEST](https://tools.ietf.org/html/rfc5780#section-7.2)
+    ///
+    /// The CHANGE-REQUEST attribute contains two flags to control the IP
+    /// address and port that the server uses to send the response.  These
+    /// flags are called the "change IP" and "change port" flags.  The
+    /// CHANGE-REQUEST attribute is allowed only in the Binding Request.  The
+    /// "change IP" and "change port" flags are useful for determining the
+    /// current filtering behavior of a NAT.  They instruct the server to
+    /// send the Binding Responses from the alternate source IP address
+    /// and/or alternate port.  The CHANGE-REQUEST attribute is optional in
+    /// the Binding Request.
+    ///
+    /// The attribute is 32 bits long, although only two bits (A and B) are
+    /// used.
+    ChangeRequest {
+        /// A: This is the "change IP" flag.  If true, it requests the server to send the Binding Response with a different IP address than the one the Binding Request was received on.
+        change_ip: bool,
+        /// B: This is the "change port" flag.  If true, it requests the server to send the Binding Response with a different port than the one the Binding Request was received on.
+        change_port: bool,
+    },
+    /// [RFC5780: RESPONSE-PORT](https://tools.ietf.org/html/rfc5780#section-7.5)
+    ///
+    /// The RESPONSE-PORT attribute contains a port.  The RESPONSE-PORT
+    /// attribute can be present in the Binding Request and indicates which
+    /// port the Binding Response will be sent to.  For servers which support
+    /// the RESPONSE-PORT attribute, the Binding Response MUST be transmitted
+    /// to the source IP address of the Binding Request and the port
+    /// contained in RESPONSE-PORT.  It is used in tests such as Section 4.6.
+    /// When not present, the server sends the Binding Response to the source
+    /// IP address and port of the Binding Request.  The server MUST NOT
+    /// process RESPONSE-PORT on other requests.
+    ResponsePort {
+        /// Port the Binding Response will be sent to
+        port: u16,
+    },
+    /// [RFC5780: PADDING](https://tools.ietf.org/html/rfc5780#section-7.6)
+    ///
+    /// The PADDING attribute allows for the entire message to be padded to
+    /// force the STUN message to be divided into IP fragments.  PADDING
+    /// consists entirely of a freeform string, the value of which does not
+    /// matter.  PADDING can be used in either Binding Requests or Binding
+    /// Responses.
+    ///
+    /// PADDING MUST NOT be longer than the length that brings the total IP
+    /// datagram size to 64K.  It SHOULD be equal in length to the MTU of the
+    /// outgoing interface, rounded up to an even multiple of four bytes.
+    Padding {
+        /// Freeform padding bytes
+        value: Vec<u8>,
+    },
+    /// [RFC5780: RESPONSE-ORIGIN](https://tools.ietf.org/html/rfc5780#section-7.3)
+    ///
+    /// The RESPONSE-ORIGIN attribute is inserted by the server and indicates
+    /// the source IP address and port the response was sent from.  It is
+    /// useful for detecting double NAT configurations.  It is only present
+    /// in Binding Responses.
+    ResponseOrigin {
+        /// Source transport address of the response
+        socket_addr: SocketAddr,
+    },
+    /// [RFC5780: OTHER-ADDRESS](https://tools.ietf.org/html/rfc5780#section-7.4)
+    ///
+    /// The OTHER-ADDRESS attribute is used in Binding Responses.  It informs
+    /// the client of the source IP address and port that would be used if
+    /// the client requested the "change IP" and "change port" behavior.
+    /// OTHER-ADDRESS MUST NOT be inserted into a Binding Response unless the
+    /// server has a second IP address.
+    OtherAddress {
+        /// Alternate transport address of the server
+        socket_addr: SocketAddr,
+    },
+    /// [RFC3489: RESPONSE-ADDRESS](https://tools.ietf.org/html/rfc3489#section-11.2.2)
+    ///
+    /// The RESPONSE-ADDRESS attribute indicates where the response to a
+    /// Binding Request should be sent.  Its syntax is identical to MAPPED-
+    /// ADDRESS.
+    ResponseAddress {
+        /// Transport address the response should be sent to
+        socket_addr: SocketAddr,
+    },
+    /// [RFC3489: SOURCE-ADDRESS](https://tools.ietf.org/html/rfc3489#section-11.2.5)
+    ///
+    /// The SOURCE-ADDRESS attribute is present in Binding Responses.  It
+    /// indicates the source IP address and port that the server is sending
+    /// the response from.  Its syntax is identical to that of MAPPED-
+    /// ADDRESS.
+    SourceAddress {
+        /// Source transport address of the response
+        socket_addr: SocketAddr,
+    },
+    /// [RFC3489: CHANGED-ADDRESS](https://tools.ietf.org/html/rfc3489#section-11.2.3)
+    ///
+    /// The CHANGED-ADDRESS attribute indicates the IP address and port where
+    /// responses would have been sent from if the "change IP" and "change
+    /// port" flags had been set in the CHANGE-REQUEST attribute of the
+    /// Binding Request.  The attribute is always present in a Binding
+    /// Response, independent of the value of the flags.  Its syntax is
+    /// identical to MAPPED-ADDRESS.
+    ChangedAddress {
+        /// Alternate transport address of the server
+        socket_addr: SocketAddr,
+    },
+    /// [RFC3489: REFLECTED-FROM](https://tools.ietf.org/html/rfc3489#section-11.2.11)
+    ///
+    /// The REFLECTED-FROM attribute is present only in Binding Responses,
+    /// when the Binding Request contained a RESPONSE-ADDRESS attribute.  The
+    /// attribute contains the identity (in terms of IP address) of the
+    /// source where the request came from.  Its purpose is to provide
+    /// traceability, so that a STUN server cannot be used as a reflector for
+    /// denial-of-service attacks.
+    ///
+    /// Its syntax is identical to the MAPPED-ADDRESS attribute.
+    ReflectedFrom {
+        /// Source transport address of the request
+        socket_addr: SocketAddr,
+    },
+    /// [RFC7635: THIRD-PARTY-AUTHORIZATION](https://tools.ietf.org/html/rfc7635#section-6.1)
+    ///
+    /// This attribute is used by the STUN server to inform the client that
+    /// it supports third-party authorization.  This attribute value contains
+    /// the STUN server name.  The authorization server may have tie ups with
+    /// multiple STUN servers and vice versa, so the client MUST provide the
+    /// STUN server name to the authorization server so that it can select
+    /// the appropriate keying material to generate the self-contained token.
+    /// If the authorization server does not have tie up with the STUN
+    /// server, then it returns an error to the client.  If the client does
+    /// not support or is not capable of doing third-party authorization,
+    /// then it defaults to first-party authentication.  The
+    /// THIRD-PARTY-AUTHORIZATION attribute is a comprehension-optional
+    /// attribute.
+    ThirdPartyAuthorization {
+        /// STUN server name
+        server_name: String,
+    },
+    /// [RFC7635: ACCESS-TOKEN](https://tools.ietf.org/html/rfc7635#section-6.2)
+    ///
+    /// The access token is issued by the authorization server.  OAuth 2.0
+    /// does not impose any limitation on the length of the access token but
+    /// if path MTU is unknown, then STUN messages over IPv4 would need to be
+    /// less than 548 bytes.  The access token length needs to be restricted
+    /// to fit within the maximum STUN message size.
+    ///
+    /// The value is the encrypted self-contained token, see [`AccessToken`](crate::AccessToken).
+    AccessToken {
+        /// Encrypted self-contained token
+        value: Vec<u8>,
+    },
+    /// [RFC6062: CONNECTION-ID](https://tools.ietf.org/html/rfc6062#section-6.2.1)
+    ///
+    /// This attribute specifies the connection ID.  The attribute is 32 bits
+    /// long and contains the connection ID.
+    ///
+    /// The connection ID uniquely identifies a peer data connection.  It is
+    /// returned by the server in the Connect success response and in the
+    /// ConnectionAttempt indication, and used by the client in the
+    /// ConnectionBind request to associate a new client data connection
+    /// with the peer data connection.
+    ConnectionId {
+        /// Connection ID
+        value: u32,
+    },
+    /// [RFC8656: REQUESTED-ADDRESS-FAMILY](https://tools.ietf.org/html/rfc8656#section-18.10)
+    ///
+    /// This attribute is used in Allocate and Refresh requests to specify
+    /// the address type requested by the client.  The value of this
+    /// attribute is 4 bytes with the following format:
+    ///```text
+    ///    0                   1                   2                   3
+    ///    0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+    ///   +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    ///   |    Family     |            Reserved                           |
+    ///   +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    ///```
+    RequestedAddressFamily {
+        /// Requested address family
+        family: StunAddressFamily,
+    },
+    /// [RFC8656: ADDITIONAL-ADDRESS-FAMILY](https://tools.ietf.org/html/rfc8656#section-18.11)
+    ///
+    /// This attribute is used by clients to request the allocation of an
+    /// IPv4 and IPv6 address type from a server.  It is encoded in the same
+    /// way as the REQUESTED-ADDRESS-FAMILY attribute.  The
+    /// ADDITIONAL-ADDRESS-FAMILY attribute MAY be present in the Allocate
+    /// request.  The attribute value of 0x02 (IPv6 address) is the only
+    /// valid value in Allocate request.
+    AdditionalAddressFamily {
+        /// Additionally requested address family
+        family: StunAddressFamily,
+    },
+    /// [RFC8656: ADDRESS-ERROR-CODE](https://tools.ietf.org/html/rfc8656#section-18.12)
+    ///
+    /// This attribute is used by servers to signal the reason for not
+    /// allocating the requested address family.  The value portion of this
+    /// attribute is variable length with the following format:
+    ///```text
+    ///    0                   1                   2                   3
+    ///    0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+    ///   +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    ///   |  Family       |    Reserved             |Class|     Number    |
+    ///   +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    ///   |      Reason Phrase (variable)                                ..
+    ///   +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    ///```
+    /// The class, number and reason phrase are encoded as in the ERROR-CODE attribute.
+    AddressErrorCode {
+        /// Address family the error refers to
+        family: StunAddressFamily,
+        /// Error class
+        class: u8,
+        /// Error number
+        number: u8,
+        /// Reason phrase
+        reason: String,
+    },
+    /// [NOMINATION](https://tools.ietf.org/html/draft-thatcher-ice-renomination-01#section-3)
+    ///
+    /// Comprehension-optional attribute used for ICE renomination.  The
+    /// controlling agent sends the nomination value in connectivity checks
+    /// and the controlled agent selects the candidate pair with the highest
+    /// nomination value received so far.
+    Nomination {
+        /// Nomination value
+        value: u32,
+    },
+    /// [GOOG-NETWORK-INFO](https://webrtc.googlesource.com/src/+/refs/heads/main/api/transport/stun.h)
+    ///
+    /// Comprehension-optional attribute sent by WebRTC implementations in ICE
+    /// connectivity checks.  It describes the network the check has been sent
+    /// over, so that the remote agent can prefer less costly networks.  The
+    /// network ID occupies the 16 most significant bits of the 32-bit value,
+    /// the network cost the 16 least significant ones.
+    GoogNetworkInfo {
+        /// Network ID
+        network_id: u16,
+        /// Network cost
+        network_cost: u16,
+    },
+    /// [GOOG-LAST-ICE-CHECK-RECEIVED](https://webrtc.googlesource.com/src/+/refs/heads/main/api/transport/stun.h)
+    ///
+    /// Comprehension-optional attribute sent by WebRTC implementations in ICE
+    /// connectivity check responses.  It contains the transaction ID of the
+    /// last connectivity check received on the candidate pair.
+    GoogLastIceCheckReceived {
+        /// Transaction ID of the last received connectivity check
+        value: Vec<u8>,
+    },
+    /// [GOOG-MISC-INFO](https://webrtc.googlesource.com/src/+/refs/heads/main/api/transport/stun.h)
+    ///
+    /// Comprehension-optional attribute sent by WebRTC implementations,
+    /// carrying a list of 16-bit values.  The meaning of each value is
+    /// defined by its index in the list, e.g. the first value announces
+    /// the supported GOOG-PING version.
+    GoogMiscInfo {
+        /// Miscellaneous 16-bit values
+        values: Vec<u16>,
+    },
+    /// [GOOG-DELTA](https://webrtc.googlesource.com/src/+/refs/heads/main/api/transport/stun.h)
+    ///
+    /// Comprehension-optional attribute sent by WebRTC implementations in ICE
+    /// connectivity checks.  It carries an opaque delta of the ICE state the
+    /// remote agent is asked to apply.
+    GoogDelta {
+        /// Opaque state delta
+        value: Vec<u8>,
+    },
+    /// [GOOG-DELTA-ACK](https://webrtc.googlesource.com/src/+/refs/heads/main/api/transport/stun.h)
+    ///
+    /// Comprehension-optional attribute sent by WebRTC implementations in ICE
+    /// connectivity check responses, acknowledging a received GOOG-DELTA.
+    GoogDeltaAck {
+        /// Acknowledgement value
+        value: u64,
+    },
+    /// [MS-TURN: MS-Version](https://docs.microsoft.com/en-us/openspecs/office_protocols/ms-turn/)
+    ///
+    /// The MS-Version attribute is used to identify the version of the TURN
+    /// protocol extensions supported by the client or the server.  The value
+    /// portion of this attribute is 4 bytes long and contains the version
+    /// number as an unsigned integer.
+    MsVersion {
+        /// Protocol version
+        version: u32,
+    },
+    /// [MS-TURN: MS-Sequence-Number](https://docs.microsoft.com/en-us/openspecs/office_protocols/ms-turn/)
+    ///
+    /// The MS-Sequence-Number attribute is used to detect reordered and
+    /// replayed messages on a TURN connection.  The value portion of this
+    /// attribute is 24 bytes long and consists of a 20-byte connection ID
+    /// followed by a 32-bit sequence number.
+    MsSequenceNumber {
+        /// Connection ID
+        connection_id: [u8; 20],
+        /// Sequence number, increased with each message sent on the connection
+        sequence_number: u32,
+    },
+    /// [MS-TURN: MS-Service-Quality](https://docs.microsoft.com/en-us/openspecs/office_protocols/ms-turn/)
+    ///
+    /// The MS-Service-Quality attribute is used to specify the type of the
+    /// media stream and the desired service quality for it.  The value
+    /// portion of this attribute is 4 bytes long and consists of a 16-bit
+    /// stream type followed by a 16-bit service quality.
+    MsServiceQuality {
+        /// Stream type: 0x0001 audio, 0x0002 video, 0x0003 supplemental video, 0x0004 data
+        stream_type: u16,
+        /// Service quality: 0x0000 best effort delivery, 0x0001 reliable delivery
+        service_quality: u16,
+    },
+    /// [MS-TURNBWM: Bandwidth Admission Control Message](https://docs.microsoft.com/en-us/openspecs/office_protocols/ms-turnbwm/)
+    ///
+    /// The Bandwidth Admission Control Message attribute specifies the type
+    /// of the bandwidth management message.  The value portion of this
+    /// attribute is 4 bytes long and consists of 16 reserved bits followed
+    /// by a 16-bit message type.
+    BandwidthAdmissionControl {
+        /// Message type: 0x0000 reservation check, 0x0001 reservation commit, 0x0002 reservation update
+        message_type: u16,
+    },
+    /// [MS-TURN: MS-Alternate-Host-Name](https://docs.microsoft.com/en-us/openspecs/office_protocols/ms-turn/)
+    ///
+    /// The MS-Alternate-Host-Name attribute contains the fully qualified
+    /// domain name of the alternate server the client is redirected to.  It
+    /// accompanies the ALTERNATE-SERVER attribute, so that the client can
+    /// validate the certificate of the alternate server.
+    MsAlternateHostName {
+        /// Host name of the alternate server
+        host_name: String,
+    },
+    /// [MS-ICE2: Candidate-Identifier](https://docs.microsoft.com/en-us/openspecs/office_protocols/ms-ice2/)
+    ///
+    /// The Candidate-Identifier attribute identifies the candidate a
+    /// connectivity check has been sent from.  The value portion of this
+    /// attribute is 4 bytes long and contains the foundation of the
+    /// candidate.
+    CandidateIdentifier {
+        /// Candidate foundation
+        foundation: [u8; 4],
+    },
+    /// [MS-ICE2: MS-Implementation-Version](https://docs.microsoft.com/en-us/openspecs/office_protocols/ms-ice2/)
+    ///
+    /// The MS-Implementation-Version attribute announces the version of the
+    /// ICE implementation of the endpoint, so that the remote endpoint can
+    /// adjust to its behavior.  The value portion of this attribute is 4
+    /// bytes long and contains the version number as an unsigned integer.
+    MsImplementationVersion {
+        /// Implementation version
+        version: u32,
+    },
+    /// [RFC7982: TRANSACTION_TRANSMIT_COUNTER](https://tools.ietf.org/html/rfc7982#section-3.1)
+    ///
+    /// The TRANSACTION_TRANSMIT_COUNTER attribute is a comprehension-
+    /// optional attribute.  The format is:
+    ///```text
+    ///    0                   1                   2                   3
+    ///    0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+    ///   +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    ///   |          Reserved (Padding)   |     Req       |     Resp      |
+    ///   +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    ///```
+    /// The client includes the attribute in every request transmission,
+    /// and the server echoes the request counter of the request it is
+    /// responding to, so that the client can tell which transmission
+    /// produced the response.
+    TransactionTransmitCounter {
+        /// Req: Number of times the request has been transmitted with the same transaction ID
+        request: u8,
+        /// Resp: Number of times a response has been transmitted with the same transaction ID. Set to 0 in requests.
+        response: u8,
+    },
+    /// [RFC8016: MOBILITY-TICKET](https://tools.ietf.org/html/rfc8016#section-3.1)
+    ///
+    /// This attribute is used to retain an allocation on the TURN server.
+    /// It is exchanged between the client and server to aid mobility.  The
+    /// value of the MOBILITY-TICKET is encrypted and is of variable length.
+    MobilityTicket {
+        /// Opaque ticket issued by the server
+        value: Vec<u8>,
+    },
+    /// [RFC6679: ECN-CHECK](https://tools.ietf.org/html/rfc6679#section-7.2.2)
+    ///
+    /// The ECN-CHECK attribute is used to verify that ECN-marked packets
+    /// traverse the path between the endpoints.  The attribute is 32 bits
+    /// long:
+    ///```text
+    ///    0                   1                   2                   3
+    ///    0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+    ///   +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    ///   |            Unused                                       |ECF|V|
+    ///   +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    ///```
+    /// The Valid (V) bit indicates whether the ECN Field (ECF) contains the
+    /// ECN field value of the IP header of the received request.
+    EcnCheck {
+        /// ECF: ECN field value. Only the 2 least significant bits are encoded.
+        ecn_field: u8,
+        /// V: Whether the ECN field value is valid
+        valid: bool,
+    },
+    /// [ORIGIN](https://tools.ietf.org/html/draft-ietf-tram-stun-origin-06#section-3)
+    ///
+    /// The ORIGIN attribute contains the origin of the web application
+    /// that initiated the STUN or TURN transaction, as defined by
+    /// [RFC6454](https://tools.ietf.org/html/rfc6454).  It allows servers to
+    /// select the realm or the credentials of the tenant the request
+    /// belongs to.  The attribute is comprehension-optional and can be
+    /// present multiple times.
+    Origin {
+        /// Serialized origin
+        value: String,
+    },
 }
@@ -0,0 +1,107 @@
+use super::StunAttribute;
+
+/// Borrowed view of a [STUN message attribute](https://tools.ietf.org/html/rfc5389#section-15)
+///
+/// The USERNAME, MESSAGE-INTEGRITY, MESSAGE-INTEGRITY-SHA256, ERROR-CODE, REALM, NONCE, SOFTWARE and DATA attributes,
+/// as well as unknown attributes, refer directly to the buffer the message was decoded from.
+/// All the other attributes are decoded by value and stored in the `Other` variant,
+/// which allocates for the variable-length ones, such as UNKNOWN-ATTRIBUTES or ACCESS-TOKEN.
+///
+/// Use `into_owned` to convert it into a `StunAttribute`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StunAttributeRef<'a> {
+    /// [RFC5389: USERNAME](https://tools.ietf.org/html/rfc5389#section-15.3)
+    Username {
+        /// The username and password combination used in the message-integrity check.
+        value: &'a str,
+    },
+    /// [RFC5389: MESSAGE-INTEGRITY](https://tools.ietf.org/html/rfc5389#section-15.4)
+    MessageIntegrity {
+        /// HMAC-SHA1 ([RFC2104](https://tools.ietf.org/html/rfc2104)) of the STUN message.
+        key: &'a [u8],
+    },
+    /// [RFC8489: MESSAGE-INTEGRITY-SHA256](https://tools.ietf.org/html/rfc8489#section-14.6)
+    MessageIntegritySha256 {
+        /// Initial portion of the HMAC-SHA256 ([RFC2104](https://tools.ietf.org/html/rfc2104)) of the STUN message.
+        key: &'a [u8],
+    },
+    /// [RFC5389: ERROR-CODE](https://tools.ietf.org/html/rfc5389#section-15.6)
+    ErrorCode {
+        /// Error class
+        class: u8,
+        /// Error number
+        number: u8,
+        /// Reason phrase
+        reason: &'a str,
+    },
+    /// [RFC5389: REALM](https://tools.ietf.org/html/rfc5389#section-15.7)
+    Realm {
+        /// Text that meets the grammar for "realm-value" as described in [RFC 3261](https://tools.ietf.org/html/rfc3261) but without the double quotes and their surrounding whitespace.
+        value: &'a str,
+    },
+    /// [RFC5389: NONCE](https://tools.ietf.org/html/rfc5389#section-15.8)
+    Nonce {
+        /// Sequence of qdtext or quoted-pair, which are defined in [RFC 3261](https://tools.ietf.org/html/rfc3261).
+        value: &'a str,
+    },
+    /// [RFC5389: SOFTWARE](https://tools.ietf.org/html/rfc5389#section-15.10)
+    Software {
+        /// Textual description of the software being used by the agent sending the message.
+        description: &'a str,
+    },
+    /// [RFC8656: DATA](https://tools.ietf.org/html/rfc8656#section-18.4)
+    Data {
+        /// Application data.
+        value: &'a [u8],
+    },
+    /// Attribute of a type that is not known to this implementation
+    Unknown {
+        /// Attribute type value
+        attr_type: u16,
+        /// Raw attribute value without padding
+        value: &'a [u8],
+    },
+    /// Any other attribute, decoded by value
+    Other(StunAttribute),
+}
+
+impl<'a> StunAttributeRef<'a> {
+    /// Converts the borrowed attribute into an owned one
+    pub fn into_owned(self) -> StunAttribute {
+        match self {
+            Self::Username { value } => StunAttribute::Username {
+                value: value.to_string(),
+            },
+            Self::MessageIntegrity { key } => StunAttribute::MessageIntegrity { key: key.to_vec() },
+            Self::MessageIntegritySha256 { key } => {
+                StunAttribute::MessageIntegritySha256 { key: key.to_vec() }
+            }
+            Self::ErrorCode {
+                class,
+                number,
+                reason,
+            } => StunAttribute::ErrorCode {
+                class,
+                number,
+                reason: reason.to_string(),
+            },
+            Self::Realm { value } => StunAttribute::Realm {
+                value: value.to_string(),
+            },
+            Self::Nonce { value } => StunAttribute::Nonce {
+                value: value.to_string(),
+            },
+            Self::Software { description } => StunAttribute::Software {
+                description: description.to_string(),
+            },
+            Self::Data { value } => StunAttribute::Data {
+                value: value.to_vec(),
+            },
+            Self::Unknown { attr_type, value } => StunAttribute::Unknown {
+                attr_type,
+                value: value.to_vec(),
+            },
+            Self::Other(attr) => attr,
+        }
+    }
+}
@@ -1,32 +1,32 @@
 use byteorder::{ByteOrder, NetworkEndian, ReadBytesExt};
 use num_traits::FromPrimitive;
-use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::io::Cursor;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 
 use super::errors::AttributeDecodeError;
 use super::types::StunAttributeType;
-use super::utils::xor_byte_range;
-use super::StunAttribute;
+use super::utils::{is_valid_integrity_sha256_len, read_tlv, xor_byte_range};
+use super::{StunAddressFamily, StunAttribute, StunAttributeRef, StunPasswordAlgorithm};
 
-use crate::definitions::{StunTransactionId, STUN_MAGIC_COOKIE};
+use crate::definitions::{StunTransactionId, STUN_INTEGRITY_ATTR_SIZE, STUN_MAGIC_COOKIE};
 
 impl StunAttribute {
-    #[allow(clippy::many_single_char_names)]
     // Decodes MappedAddress/XorMappedAddress/AlternateServer attributes.
-    fn decode_address(
+    pub(super) fn decode_address(
         bytes: &[u8],
         is_xored: bool,
         transaction_id: StunTransactionId,
     ) -> Result<SocketAddr, AttributeDecodeError> {
-        // Separate IP address family
-        let ip_family = (&bytes[1..2]).read_u8()?;
-
         if bytes.len() < 8 {
             return Err(AttributeDecodeError::InsufficientData());
         }
 
-        let mut port_bytes = bytes[2..4].to_vec();
-        let mut addr_bytes = bytes[4..].to_vec();
+        // Separate IP address family
+        let ip_family = bytes[1];
+
+        let mut port_bytes = [0u8; 2];
+        port_bytes.copy_from_slice(&bytes[2..4]);
+        let addr_bytes = &bytes[4..];
 
         // XOR the port number bytes if the attribute type is XorMappedAddress
         if is_xored {
@@ -42,43 +42,31 @@ impl StunAttribute {
                     return Err(AttributeDecodeError::InsufficientData());
                 }
 
+                let mut ip_data = [0u8; 4];
+                ip_data.copy_from_slice(&addr_bytes[0..4]);
+
                 // XOR the ip address bytes if the attribute type is XorMappedAddress
                 if is_xored {
-                    xor_byte_range(&mut addr_bytes, &STUN_MAGIC_COOKIE);
+                    xor_byte_range(&mut ip_data, &STUN_MAGIC_COOKIE);
                 }
 
-                let mut cursor = Cursor::new(addr_bytes);
-
-                let a = cursor.read_u8()?;
-                let b = cursor.read_u8()?;
-                let c = cursor.read_u8()?;
-                let d = cursor.read_u8()?;
-
-                Ok(IpAddr::V4(Ipv4Addr::new(a, b, c, d)))
+                Ok(IpAddr::V4(Ipv4Addr::from(ip_data)))
             }
             0x02 => {
                 if addr_bytes.len() < 16 {
                     return Err(AttributeDecodeError::InsufficientData());
                 }
 
+                let mut ip_data = [0u8; 16];
+                ip_data.copy_from_slice(&addr_bytes[0..16]);
+
                 // XOR the ip address bytes if the attribute type is XorMappedAddress
                 if is_xored {
-                    xor_byte_range(&mut addr_bytes[0..4], &STUN_MAGIC_COOKIE);
-                    xor_byte_range(&mut addr_bytes[4..16], &transaction_id);
+                    xor_byte_range(&mut ip_data[0..4], &STUN_MAGIC_COOKIE);
+                    xor_byte_range(&mut ip_data[4..16], &transaction_id);
                 }
 
-                let mut cursor = Cursor::new(addr_bytes);
-
-                let a = cursor.read_u16::<NetworkEndian>()?;
-                let b = cursor.read_u16::<NetworkEndian>()?;
-                let c = cursor.read_u16::<NetworkEndian>()?;
-                let d = cursor.read_u16::<NetworkEndian>()?;
-                let e = cursor.read_u16::<NetworkEndian>()?;
-                let f = cursor.read_u16::<NetworkEndian>()?;
-                let g = cursor.read_u16::<NetworkEndian>()?;
-                let h = cursor.read_u16::<NetworkEndian>()?;
-
-                Ok(IpAddr::V6(Ipv6Addr::new(a, b, c, d, e, f, g, h)))
+                Ok(IpAddr::V6(Ipv6Addr::from(ip_data)))
             }
             _ => Err(AttributeDecodeError::InvalidValue(ip_family as u128)),
         };
@@ -111,8 +99,41 @@ impl StunAttribute {
         Ok(NetworkEndian::read_u64(bytes))
     }
 
-    // Decodes the ErrorCode attribute.
-    fn decode_error_code(bytes: &[u8]) -> Result<Self, AttributeDecodeError> {
+    // Decodes attributes containing a WORD value followed by a 2 byte RFFU field.
+    fn decode_u16_val(bytes: &[u8]) -> Result<u16, AttributeDecodeError> {
+        // Prevent NetworkEndian::read_u16 from panicking if we don't have enough data to read from.
+        if bytes.len() < 2 {
+            return Err(AttributeDecodeError::InsufficientData());
+        }
+
+        Ok(NetworkEndian::read_u16(bytes))
+    }
+
+    // Decodes the EvenPort attribute.
+    fn decode_even_port(bytes: &[u8]) -> Result<Self, AttributeDecodeError> {
+        if bytes.is_empty() {
+            return Err(AttributeDecodeError::InsufficientData());
+        }
+
+        // Only the R bit is meaningful, the rest are RFFU
+        let reserve_next = bytes[0] & 0b1000_0000 != 0;
+
+        Ok(Self::EvenPort { reserve_next })
+    }
+
+    // Decodes the RequestedTransport attribute.
+    fn decode_requested_transport(bytes: &[u8]) -> Result<Self, AttributeDecodeError> {
+        if bytes.len() < 4 {
+            return Err(AttributeDecodeError::InsufficientData());
+        }
+
+        Ok(Self::RequestedTransport { protocol: bytes[0] })
+    }
+
+    // Decodes the class, number and reason phrase of the ErrorCode attribute.
+    pub(super) fn decode_error_code_fields(
+        bytes: &[u8],
+    ) -> Result<(u8, u8, &str), AttributeDecodeError> {
         // Prevent NetworkEndian::read_u32 from panicking if we don't have enough data to read from.
         if bytes.len() < 4 {
             return Err(AttributeDecodeError::InsufficientData());
@@ -120,120 +141,511 @@ impl StunAttribute {
 
         let class = bytes[2];
         let number = bytes[3];
-        let reason = String::from_utf8(bytes[4..].to_vec())?;
+        let reason = std::str::from_utf8(&bytes[4..])?;
+
+        Ok((class, number, reason))
+    }
+
+    // Decodes the ErrorCode attribute.
+    fn decode_error_code(bytes: &[u8]) -> Result<Self, AttributeDecodeError> {
+        let (class, number, reason) = Self::decode_error_code_fields(bytes)?;
 
         Ok(Self::ErrorCode {
             class,
             number,
-            reason,
+            reason: reason.to_string(),
         })
     }
 
-    // Decodes the UnknownAttributes attribute.
-    fn decode_unknown_attributes(bytes: &[u8]) -> Result<Self, AttributeDecodeError> {
-        let mut types = Vec::new();
+    // Decodes the address family of the RequestedAddressFamily/AdditionalAddressFamily attributes.
+    fn decode_address_family(bytes: &[u8]) -> Result<StunAddressFamily, AttributeDecodeError> {
+        if bytes.len() < 4 {
+            return Err(AttributeDecodeError::InsufficientData());
+        }
+
+        Ok(StunAddressFamily::from(bytes[0]))
+    }
+
+    // Decodes the AddressErrorCode attribute.
+    fn decode_address_error_code(bytes: &[u8]) -> Result<Self, AttributeDecodeError> {
+        // The family occupies the leading byte of the reserved ErrorCode bits
+        let (class, number, reason) = Self::decode_error_code_fields(bytes)?;
+
+        Ok(Self::AddressErrorCode {
+            family: StunAddressFamily::from(bytes[0]),
+            class,
+            number,
+            reason: reason.to_string(),
+        })
+    }
+
+    // Decodes attributes containing a list of WORD values, such as the UnknownAttributes attribute.
+    fn decode_u16_list(bytes: &[u8]) -> Result<Vec<u16>, AttributeDecodeError> {
+        let mut values = Vec::new();
 
         let mut cursor = Cursor::new(bytes);
 
         while cursor.position() < bytes.len() as u64 {
-            types.push(cursor.read_u16::<NetworkEndian>()?);
+            values.push(cursor.read_u16::<NetworkEndian>()?);
         }
 
-        Ok(Self::UnknownAttributes { types })
+        Ok(values)
     }
 
-    /// Decodes bytes passed via cursor into a STUN attribute.
-    /// On each invocation only one attribute is decoded and the cursor position is advanced.
-    pub(crate) fn decode(
+    // Checks the MessageIntegrity value length. HMAC-SHA1 values are never truncated.
+    pub(super) fn decode_integrity(bytes: &[u8]) -> Result<&[u8], AttributeDecodeError> {
+        if bytes.len() != STUN_INTEGRITY_ATTR_SIZE - 4 {
+            return Err(AttributeDecodeError::InvalidIntegrityLength(bytes.len()));
+        }
+
+        Ok(bytes)
+    }
+
+    // Checks the MessageIntegritySha256 value length.
+    pub(super) fn decode_integrity_sha256(bytes: &[u8]) -> Result<&[u8], AttributeDecodeError> {
+        if !is_valid_integrity_sha256_len(bytes.len()) {
+            return Err(AttributeDecodeError::InvalidIntegrityLength(bytes.len()));
+        }
+
+        Ok(bytes)
+    }
+
+    // Decodes the ChangeRequest attribute.
+    fn decode_change_request(bytes: &[u8]) -> Result<Self, AttributeDecodeError> {
+        let flags = Self::decode_u32_val(bytes)?;
+
+        Ok(Self::ChangeRequest {
+            change_ip: flags & 0b100 != 0,
+            change_port: flags & 0b010 != 0,
+        })
+    }
+
+    // Decodes the Userhash attribute.
+    fn decode_userhash(bytes: &[u8]) -> Result<Self, AttributeDecodeError> {
+        if bytes.len() != 32 {
+            return Err(AttributeDecodeError::InvalidValue(bytes.len() as u128));
+        }
+
+        let mut value = [0u8; 32];
+        value.copy_from_slice(bytes);
+
+        Ok(Self::Userhash { value })
+    }
+
+    // Decodes attributes containing two WORD values.
+    fn decode_u16_pair(bytes: &[u8]) -> Result<(u16, u16), AttributeDecodeError> {
+        if bytes.len() < 4 {
+            return Err(AttributeDecodeError::InsufficientData());
+        }
+
+        Ok((
+            NetworkEndian::read_u16(&bytes[0..2]),
+            NetworkEndian::read_u16(&bytes[2..4]),
+        ))
+    }
+
+    // Decodes the MsSequenceNumber attribute.
+    fn decode_ms_sequence_number(bytes: &[u8]) -> Result<Self, AttributeDecodeError> {
+        if bytes.len() < 24 {
+            return Err(AttributeDecodeError::InsufficientData());
+        }
+
+        let mut connection_id = [0u8; 20];
+        connection_id.copy_from_slice(&bytes[0..20]);
+
+        Ok(Self::MsSequenceNumber {
+            connection_id,
+            sequence_number: NetworkEndian::read_u32(&bytes[20..24]),
+        })
+    }
+
+    // Decodes the CandidateIdentifier attribute.
+    fn decode_candidate_identifier(bytes: &[u8]) -> Result<Self, AttributeDecodeError> {
+        if bytes.len() < 4 {
+            return Err(AttributeDecodeError::InsufficientData());
+        }
+
+        let mut foundation = [0u8; 4];
+        foundation.copy_from_slice(&bytes[0..4]);
+
+        Ok(Self::CandidateIdentifier { foundation })
+    }
+
+    // Decodes the TransactionTransmitCounter attribute.
+    fn decode_transaction_transmit_counter(bytes: &[u8]) -> Result<Self, AttributeDecodeError> {
+        if bytes.len() < 4 {
+            return Err(AttributeDecodeError::InsufficientData());
+        }
+
+        // The counters follow 2 reserved bytes
+        Ok(Self::TransactionTransmitCounter {
+            request: bytes[2],
+            response: bytes[3],
+        })
+    }
+
+    // Decodes the EcnCheck attribute.
+    fn decode_ecn_check(bytes: &[u8]) -> Result<Self, AttributeDecodeError> {
+        let raw_val = Self::decode_u32_val(bytes)?;
+
+        // Only the 3 least significant bits are used
+        Ok(Self::EcnCheck {
+            ecn_field: ((raw_val >> 1) & 0b11) as u8,
+            valid: raw_val & 0b1 != 0,
+        })
+    }
+
+    // Decodes a single PasswordAlgorithm entry and advances the cursor past its (optional) padding.
+    fn decode_password_algorithm_entry(
         cursor: &mut Cursor<&[u8]>,
-        transaction_id: StunTransactionId,
-    ) -> Result<Self, AttributeDecodeError> {
-        // Read attribute type
-        let encoded_attr_type = cursor.read_u16::<NetworkEndian>()?;
-        // Read attribute data length
-        let attr_len = cursor.read_u16::<NetworkEndian>()?;
+    ) -> Result<(StunPasswordAlgorithm, Vec<u8>), AttributeDecodeError> {
+        let algorithm = cursor.read_u16::<NetworkEndian>()?;
+        let params_len = cursor.read_u16::<NetworkEndian>()? as usize;
 
-        // Read attribute data
-        let mut attr_data = vec![0u8; attr_len as usize];
-        cursor.read_exact(&mut attr_data)?;
+        let bytes = cursor.get_ref();
+        let start = cursor.position() as usize;
+        let end = start + params_len;
 
-        // Calculate the padding and advance the cursor
-        let padding = 4 - attr_len % 4;
-        if padding != 4 {
-            cursor.seek(SeekFrom::Current(padding as i64))?;
+        if end > bytes.len() {
+            return Err(AttributeDecodeError::InsufficientData());
         }
 
-        let attr_type = FromPrimitive::from_u16(encoded_attr_type).ok_or(
-            AttributeDecodeError::UnrecognizedAttributeType {
-                attr_type: encoded_attr_type,
-            },
-        )?;
+        let parameters = bytes[start..end].to_vec();
+
+        // Skip the parameters padding
+        let padded_end = (end + 3) & !3;
+        cursor.set_position(padded_end.min(bytes.len()) as u64);
+
+        Ok((algorithm.into(), parameters))
+    }
+
+    // Decodes the PasswordAlgorithm attribute.
+    fn decode_password_algorithm(bytes: &[u8]) -> Result<Self, AttributeDecodeError> {
+        let (algorithm, parameters) =
+            Self::decode_password_algorithm_entry(&mut Cursor::new(bytes))?;
+
+        Ok(Self::PasswordAlgorithm {
+            algorithm,
+            parameters,
+        })
+    }
+
+    // Decodes the PasswordAlgorithms attribute.
+    fn decode_password_algorithms(bytes: &[u8]) -> Result<Self, AttributeDecodeError> {
+        let mut algorithms = Vec::new();
+
+        let mut cursor = Cursor::new(bytes);
+
+        while cursor.position() < bytes.len() as u64 {
+            algorithms.push(Self::decode_password_algorithm_entry(&mut cursor)?);
+        }
+
+        Ok(Self::PasswordAlgorithms { algorithms })
+    }
+
+    /// Decodes the value of a STUN attribute into an owned attribute variant.
+    ///
+    /// Arguments:
+    ///
+    /// * `encoded_attr_type`: Attribute type value read from the TLV
+    /// * `attr_data`: Attribute value without padding
+    /// * `transaction_id`: Transaction id of the message used for XOR-ed addresses
+    pub(crate) fn decode_value(
+        encoded_attr_type: u16,
+        attr_data: &[u8],
+        transaction_id: StunTransactionId,
+    ) -> Result<Self, AttributeDecodeError> {
+        // Preserve the attributes of unknown types as raw TLVs
+        let attr_type = match FromPrimitive::from_u16(encoded_attr_type) {
+            Some(attr_type) => attr_type,
+            None => {
+                return Ok(Self::Unknown {
+                    attr_type: encoded_attr_type,
+                    value: attr_data.to_vec(),
+                })
+            }
+        };
 
         // Decode and return the appropriate variant based on the attribute type.
         match attr_type {
             StunAttributeType::XorMappedAddress => {
-                let socket_addr = Self::decode_address(&attr_data, true, transaction_id)?;
+                let socket_addr = Self::decode_address(attr_data, true, transaction_id)?;
 
                 Ok(Self::XorMappedAddress { socket_addr })
             }
             StunAttributeType::MappedAddress => {
-                let socket_addr = Self::decode_address(&attr_data, false, transaction_id)?;
+                let socket_addr = Self::decode_address(attr_data, false, transaction_id)?;
 
                 Ok(Self::MappedAddress { socket_addr })
             }
             StunAttributeType::Username => {
-                let raw_val = Self::decode_utf8_val(&attr_data)?;
+                let raw_val = Self::decode_utf8_val(attr_data)?;
 
                 Ok(Self::Username { value: raw_val })
             }
-            StunAttributeType::MessageIntegrity => Ok(Self::MessageIntegrity { key: attr_data }),
+            StunAttributeType::MessageIntegrity => Ok(Self::MessageIntegrity {
+                key: Self::decode_integrity(attr_data)?.to_vec(),
+            }),
             StunAttributeType::Software => {
-                let raw_val = Self::decode_utf8_val(&attr_data)?;
+                let raw_val = Self::decode_utf8_val(attr_data)?;
 
                 Ok(Self::Software {
                     description: raw_val,
                 })
             }
             StunAttributeType::AlternateServer => {
-                let socket_addr = Self::decode_address(&attr_data, false, transaction_id)?;
+                let socket_addr = Self::decode_address(attr_data, false, transaction_id)?;
 
                 Ok(Self::AlternateServer { socket_addr })
             }
             StunAttributeType::Realm => {
-                let raw_val = Self::decode_utf8_val(&attr_data)?;
+                let raw_val = Self::decode_utf8_val(attr_data)?;
 
                 Ok(Self::Realm { value: raw_val })
             }
             StunAttributeType::Nonce => {
-                let raw_val = Self::decode_utf8_val(&attr_data)?;
+                let raw_val = Self::decode_utf8_val(attr_data)?;
 
                 Ok(Self::Nonce { value: raw_val })
             }
             StunAttributeType::Fingerprint => Ok(Self::Fingerprint {
-                value: Self::decode_u32_val(&attr_data)?,
+                value: Self::decode_u32_val(attr_data)?,
             }),
             StunAttributeType::IceControlled => {
-                let raw_val = Self::decode_u64_val(&attr_data)?;
+                let raw_val = Self::decode_u64_val(attr_data)?;
 
                 Ok(Self::IceControlled {
                     tie_breaker: raw_val,
                 })
             }
             StunAttributeType::IceControlling => {
-                let raw_val = Self::decode_u64_val(&attr_data)?;
+                let raw_val = Self::decode_u64_val(attr_data)?;
 
                 Ok(Self::IceControlling {
                     tie_breaker: raw_val,
                 })
             }
             StunAttributeType::Priority => {
-                let raw_val = Self::decode_u32_val(&attr_data)?;
+                let raw_val = Self::decode_u32_val(attr_data)?;
 
                 Ok(Self::Priority { value: raw_val })
             }
-            StunAttributeType::ErrorCode => Self::decode_error_code(&attr_data),
-            StunAttributeType::UnknownAttributes => Self::decode_unknown_attributes(&attr_data),
+            StunAttributeType::ErrorCode => Self::decode_error_code(attr_data),
+            StunAttributeType::UnknownAttributes => Ok(Self::UnknownAttributes {
+                types: Self::decode_u16_list(attr_data)?,
+            }),
             StunAttributeType::UseCandidate => Ok(Self::UseCandidate),
+            StunAttributeType::ChannelNumber => {
+                let raw_val = Self::decode_u16_val(attr_data)?;
+
+                Ok(Self::ChannelNumber { number: raw_val })
+            }
+            StunAttributeType::Lifetime => {
+                let raw_val = Self::decode_u32_val(attr_data)?;
+
+                Ok(Self::Lifetime { seconds: raw_val })
+            }
+            StunAttributeType::XorPeerAddress => {
+                let socket_addr = Self::decode_address(attr_data, true, transaction_id)?;
+
+                Ok(Self::XorPeerAddress { socket_addr })
+            }
+            StunAttributeType::Data => Ok(Self::Data {
+                value: attr_data.to_vec(),
+            }),
+            StunAttributeType::XorRelayedAddress => {
+                let socket_addr = Self::decode_address(attr_data, true, transaction_id)?;
+
+                Ok(Self::XorRelayedAddress { socket_addr })
+            }
+            StunAttributeType::EvenPort => Self::decode_even_port(attr_data),
+            StunAttributeType::RequestedTransport => Self::decode_requested_transport(attr_data),
+            StunAttributeType::DontFragment => Ok(Self::DontFragment),
+            StunAttributeType::ReservationToken => {
+                let raw_val = Self::decode_u64_val(attr_data)?;
+
+                Ok(Self::ReservationToken { token: raw_val })
+            }
+            StunAttributeType::MessageIntegritySha256 => Ok(Self::MessageIntegritySha256 {
+                key: Self::decode_integrity_sha256(attr_data)?.to_vec(),
+            }),
+            StunAttributeType::PasswordAlgorithm => Self::decode_password_algorithm(attr_data),
+            StunAttributeType::PasswordAlgorithms => Self::decode_password_algorithms(attr_data),
+            StunAttributeType::Userhash => Self::decode_userhash(attr_data),
+            StunAttributeType::ChangeRequest => Self::decode_change_request(attr_data),
+            StunAttributeType::ResponsePort => {
+                let raw_val = Self::decode_u16_val(attr_data)?;
+
+                Ok(Self::ResponsePort { port: raw_val })
+            }
+            StunAttributeType::Padding => Ok(Self::Padding {
+                value: attr_data.to_vec(),
+            }),
+            StunAttributeType::ResponseOrigin => {
+                let socket_addr = Self::decode_address(attr_data, false, transaction_id)?;
+
+                Ok(Self::ResponseOrigin { socket_addr })
+            }
+            StunAttributeType::OtherAddress => {
+                let socket_addr = Self::decode_address(attr_data, false, transaction_id)?;
+
+                Ok(Self::OtherAddress { socket_addr })
+            }
+            StunAttributeType::ResponseAddress => {
+                let socket_addr = Self::decode_address(attr_data, false, transaction_id)?;
+
+                Ok(Self::ResponseAddress { socket_addr })
+            }
+            StunAttributeType::SourceAddress => {
+                let socket_addr = Self::decode_address(attr_data, false, transaction_id)?;
+
+                Ok(Self::SourceAddress { socket_addr })
+            }
+            StunAttributeType::ChangedAddress => {
+                let socket_addr = Self::decode_address(attr_data, false, transaction_id)?;
+
+                Ok(Self::ChangedAddress { socket_addr })
+            }
+            StunAttributeType::ReflectedFrom => {
+                let socket_addr = Self::decode_address(attr_data, false, transaction_id)?;
+
+                Ok(Self::ReflectedFrom { socket_addr })
+            }
+            StunAttributeType::ThirdPartyAuthorization => {
+                let raw_val = Self::decode_utf8_val(attr_data)?;
+
+                Ok(Self::ThirdPartyAuthorization {
+                    server_name: raw_val,
+                })
+            }
+            StunAttributeType::AccessToken => Ok(Self::AccessToken {
+                value: attr_data.to_vec(),
+            }),
+            StunAttributeType::ConnectionId => Ok(Self::ConnectionId {
+                value: Self::decode_u32_val(attr_data)?,
+            }),
+            StunAttributeType::RequestedAddressFamily => Ok(Self::RequestedAddressFamily {
+                family: Self::decode_address_family(attr_data)?,
+            }),
+            StunAttributeType::AdditionalAddressFamily => Ok(Self::AdditionalAddressFamily {
+                family: Self::decode_address_family(attr_data)?,
+            }),
+            StunAttributeType::AddressErrorCode => Self::decode_address_error_code(attr_data),
+            StunAttributeType::Nomination => Ok(Self::Nomination {
+                value: Self::decode_u32_val(attr_data)?,
+            }),
+            StunAttributeType::GoogNetworkInfo => {
+                let raw_val = Self::decode_u32_val(attr_data)?;
+
+                Ok(Self::GoogNetworkInfo {
+                    network_id: (raw_val >> 16) as u16,
+                    network_cost: raw_val as u16,
+                })
+            }
+            StunAttributeType::GoogLastIceCheckReceived => Ok(Self::GoogLastIceCheckReceived {
+                value: attr_data.to_vec(),
+            }),
+            StunAttributeType::GoogMiscInfo => Ok(Self::GoogMiscInfo {
+                values: Self::decode_u16_list(attr_data)?,
+            }),
+            StunAttributeType::GoogDelta => Ok(Self::GoogDelta {
+                value: attr_data.to_vec(),
+            }),
+            StunAttributeType::GoogDeltaAck => Ok(Self::GoogDeltaAck {
+                value: Self::decode_u64_val(attr_data)?,
+            }),
+            StunAttributeType::MsVersion => Ok(Self::MsVersion {
+                version: Self::decode_u32_val(attr_data)?,
+            }),
+            StunAttributeType::MsSequenceNumber => Self::decode_ms_sequence_number(attr_data),
+            StunAttributeType::MsServiceQuality => {
+                let (stream_type, service_quality) = Self::decode_u16_pair(attr_data)?;
+
+                Ok(Self::MsServiceQuality {
+                    stream_type,
+                    service_quality,
+                })
+            }
+            StunAttributeType::BandwidthAdmissionControl => {
+                // The message type follows 16 reserved bits
+                let (_, message_type) = Self::decode_u16_pair(attr_data)?;
+
+                Ok(Self::BandwidthAdmissionControl { message_type })
+            }
+            StunAttributeType::MsAlternateHostName => {
+                let raw_val = Self::decode_utf8_val(attr_data)?;
+
+                Ok(Self::MsAlternateHostName { host_name: raw_val })
+            }
+            StunAttributeType::CandidateIdentifier => Self::decode_candidate_identifier(attr_data),
+            StunAttributeType::MsImplementationVersion => Ok(Self::MsImplementationVersion {
+                version: Self::decode_u32_val(attr_data)?,
+            }),
+            StunAttributeType::TransactionTransmitCounter => {
+                Self::decode_transaction_transmit_counter(attr_data)
+            }
+            StunAttributeType::MobilityTicket => Ok(Self::MobilityTicket {
+                value: attr_data.to_vec(),
+            }),
+            StunAttributeType::EcnCheck => Self::decode_ecn_check(attr_data),
+            StunAttributeType::Origin => {
+                let raw_val = Self::decode_utf8_val(attr_data)?;
+
+                Ok(Self::Origin { value: raw_val })
+            }
+        }
+    }
+}
+
+impl<'a> StunAttributeRef<'a> {
+    /// Decodes bytes passed via cursor into a borrowed STUN attribute.
+    /// On each invocation only one attribute is decoded and the cursor position is advanced.
+    pub(crate) fn decode(
+        cursor: &mut Cursor<&'a [u8]>,
+        transaction_id: StunTransactionId,
+    ) -> Result<Self, AttributeDecodeError> {
+        let (encoded_attr_type, attr_data) = read_tlv(cursor)?;
+
+        // Borrow the variable-length values and decode everything else by value
+        match FromPrimitive::from_u16(encoded_attr_type) {
+            Some(StunAttributeType::Username) => Ok(Self::Username {
+                value: std::str::from_utf8(attr_data)?,
+            }),
+            Some(StunAttributeType::MessageIntegrity) => Ok(Self::MessageIntegrity {
+                key: StunAttribute::decode_integrity(attr_data)?,
+            }),
+            Some(StunAttributeType::MessageIntegritySha256) => Ok(Self::MessageIntegritySha256 {
+                key: StunAttribute::decode_integrity_sha256(attr_data)?,
+            }),
+            Some(StunAttributeType::ErrorCode) => {
+                let (class, number, reason) = StunAttribute::decode_error_code_fields(attr_data)?;
+
+                Ok(Self::ErrorCode {
+                    class,
+                    number,
+                    reason,
+                })
+            }
+            Some(StunAttributeType::Realm) => Ok(Self::Realm {
+                value: std::str::from_utf8(attr_data)?,
+            }),
+            Some(StunAttributeType::Nonce) => Ok(Self::Nonce {
+                value: std::str::from_utf8(attr_data)?,
+            }),
+            Some(StunAttributeType::Software) => Ok(Self::Software {
+                description: std::str::from_utf8(attr_data)?,
+            }),
+            Some(StunAttributeType::Data) => Ok(Self::Data { value: attr_data }),
+            // Preserve the attributes of unknown types as raw TLVs
+            None => Ok(Self::Unknown {
+                attr_type: encoded_attr_type,
+                value: attr_data,
+            }),
+            Some(_) => Ok(Self::Other(StunAttribute::decode_value(
+                encoded_attr_type,
+                attr_data,
+                transaction_id,
+            )?)),
         }
     }
 }
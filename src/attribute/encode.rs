@@ -1,48 +1,37 @@
 use byteorder::{ByteOrder, NetworkEndian, WriteBytesExt};
-use std::io::{Cursor, Write};
+use std::io::{self, Write};
 use std::net::SocketAddr;
 
 use super::errors::AttributeEncodeError;
 use super::types::StunAttributeType;
-use super::utils::xor_byte_range;
-use super::StunAttribute;
+use super::utils::{is_valid_integrity_sha256_len, xor_byte_range};
+use super::{StunAddressFamily, StunAttribute, StunPasswordAlgorithm};
 
 use crate::definitions::{StunTransactionId, STUN_MAGIC_COOKIE};
 
-impl StunAttribute {
-    // Wraps the encoded attribute data into TLV
-    fn wrap_in_tlv(
-        attr_type: StunAttributeType,
-        attr_bytes: &[u8],
-    ) -> Result<Vec<u8>, AttributeEncodeError> {
-        let mut cursor = Cursor::new(Vec::new());
-
-        let data_len = attr_bytes.len();
-
-        // Write the attribute type
-        cursor.write_u16::<NetworkEndian>(attr_type as u16)?;
-        // Write the attribute data length
-        cursor.write_u16::<NetworkEndian>(data_len as u16)?;
-        // Write attribute data
-        cursor.write_all(attr_bytes)?;
+/// Writer that only counts the bytes passed to it. Used for measuring attribute values without encoding them into a buffer.
+#[derive(Default)]
+struct ByteCounter(usize);
 
-        // Calculate and add attribute padding
-        // NOTE: As per [RFC5389 Section 15](https://tools.ietf.org/html/rfc5389#section-15) padding bytes may take any value.
-        // In this implementation NULL bytes are used
-        let padding = 4 - data_len % 4;
-        if padding != 4 {
-            cursor.write_all(&vec![0u8; padding])?;
-        }
+impl Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
 
-        Ok(cursor.get_ref().to_vec())
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
     }
+}
 
+impl StunAttribute {
     // Encodes MappedAddress/XorMappedAddress/AlternateServer attributes.
-    fn encode_address(
+    fn encode_address<W: Write>(
+        writer: &mut W,
         addr: &SocketAddr,
         is_xored: bool,
         transaction_id: StunTransactionId,
-    ) -> Result<Vec<u8>, AttributeEncodeError> {
+    ) -> Result<(), AttributeEncodeError> {
         let family = match addr {
             SocketAddr::V4(_) => 0x01,
             SocketAddr::V6(_) => 0x02,
@@ -50,14 +39,22 @@ impl StunAttribute {
 
         // Process the port number
         let port = addr.port();
-        let mut port_bytes = [0u8, 2];
+        let mut port_bytes = [0u8; 2];
         NetworkEndian::write_u16(&mut port_bytes, port);
         // XOR the port number bytes if the attribute type is XorMappedAddress
         if is_xored {
             xor_byte_range(&mut port_bytes, &STUN_MAGIC_COOKIE);
         }
 
-        let ip_addr_bytes = match addr {
+        // Write leading zeroes
+        writer.write_u8(0)?;
+        // Write ip address family
+        writer.write_u8(family)?;
+        // Write socket port number
+        writer.write_all(&port_bytes)?;
+
+        // Write (XORed) ip address bytes
+        match addr {
             SocketAddr::V4(addr_v4) => {
                 let mut ip_data = addr_v4.ip().octets();
 
@@ -66,176 +63,565 @@ impl StunAttribute {
                     xor_byte_range(&mut ip_data, &STUN_MAGIC_COOKIE)
                 }
 
-                ip_data.to_vec()
+                writer.write_all(&ip_data)?;
             }
             SocketAddr::V6(addr_v6) => {
-                let segments = addr_v6.ip().segments();
-
-                let mut ip_cursor = Cursor::new(Vec::new());
-                for segment in segments.iter() {
-                    ip_cursor.write_u16::<NetworkEndian>(*segment)?;
-                }
-
-                let ip_addr_bytes = ip_cursor.get_mut();
+                let mut ip_data = addr_v6.ip().octets();
 
                 // XOR the ip address bytes if the attribute type is XorMappedAddress
                 if is_xored {
-                    xor_byte_range(&mut ip_addr_bytes[0..4], &STUN_MAGIC_COOKIE);
-                    xor_byte_range(&mut ip_addr_bytes[4..16], &transaction_id);
+                    xor_byte_range(&mut ip_data[0..4], &STUN_MAGIC_COOKIE);
+                    xor_byte_range(&mut ip_data[4..16], &transaction_id);
                 }
 
-                ip_addr_bytes.to_vec()
+                writer.write_all(&ip_data)?;
             }
         };
 
-        let mut cursor = Cursor::new(Vec::new());
-
-        // Write leading zeroes
-        cursor.write_u8(0)?;
-        // Write ip address family
-        cursor.write_u8(family)?;
-        // Write socket port number
-        cursor.write_all(&port_bytes)?;
-        // Write (XORed) ip address bytes
-        cursor.write_all(&ip_addr_bytes)?;
-
-        Ok(cursor.get_ref().to_vec())
+        Ok(())
     }
 
     // Encodes attributes containing Unicode values
-    fn encode_utf8_val(data: &str, limit: Option<usize>) -> Result<Vec<u8>, AttributeEncodeError> {
-        let encoded_val = data.as_bytes().to_vec();
-
+    fn encode_utf8_val<W: Write>(
+        writer: &mut W,
+        data: &str,
+        limit: Option<usize>,
+    ) -> Result<(), AttributeEncodeError> {
         // Make sure we don't cross the size limit
-        match limit {
-            None => Ok(encoded_val),
-            Some(size_limit) => {
-                if encoded_val.len() > size_limit {
-                    Err(AttributeEncodeError::Utf8ValueTooBig {
-                        limit: size_limit,
-                        length: encoded_val.len(),
-                    })
-                } else {
-                    Ok(encoded_val)
-                }
+        if let Some(size_limit) = limit {
+            if data.len() > size_limit {
+                return Err(AttributeEncodeError::Utf8ValueTooBig {
+                    limit: size_limit,
+                    length: data.len(),
+                });
             }
         }
+
+        writer.write_all(data.as_bytes())?;
+
+        Ok(())
     }
 
     // Encodes attributes containing DWORD values.
-    fn encode_u32_val(value: u32) -> Result<Vec<u8>, AttributeEncodeError> {
-        let mut buf: Vec<u8> = vec![0u8; 4];
-        NetworkEndian::write_u32(&mut buf, value);
+    fn encode_u32_val<W: Write>(writer: &mut W, value: u32) -> Result<(), AttributeEncodeError> {
+        writer.write_u32::<NetworkEndian>(value)?;
 
-        Ok(buf)
+        Ok(())
     }
 
     // Encodes attributes containing QWORD values.
-    fn encode_u64_val(value: u64) -> Result<Vec<u8>, AttributeEncodeError> {
-        let mut buf: Vec<u8> = vec![0u8; 8];
-        NetworkEndian::write_u64(&mut buf, value);
+    fn encode_u64_val<W: Write>(writer: &mut W, value: u64) -> Result<(), AttributeEncodeError> {
+        writer.write_u64::<NetworkEndian>(value)?;
 
-        Ok(buf)
+        Ok(())
     }
 
-    // Encodes the ErrorCode attribute.
-    fn encode_error_code(
+    // Encodes the ErrorCode/AddressErrorCode attributes.
+    // The address family is only present in the AddressErrorCode attribute, in place of the leading zeroes.
+    fn encode_error_code<W: Write>(
+        writer: &mut W,
+        family: Option<StunAddressFamily>,
         class: u8,
         number: u8,
         reason: &str,
-    ) -> Result<Vec<u8>, AttributeEncodeError> {
-        let mut cursor = Cursor::new(Vec::new());
-        // Write leading zeroes
-        cursor.write_u16::<NetworkEndian>(0)?;
+    ) -> Result<(), AttributeEncodeError> {
+        // Write the address family or leading zeroes
+        writer.write_u8(family.map(u8::from).unwrap_or(0))?;
+        writer.write_u8(0)?;
         // Write error class
-        cursor.write_u8(class)?;
+        writer.write_u8(class)?;
         // Write error number
-        cursor.write_u8(number)?;
+        writer.write_u8(number)?;
         // Write readable error reason
-        cursor.write_all(&Self::encode_utf8_val(reason, Some(763))?)?;
+        Self::encode_utf8_val(writer, reason, Some(763))
+    }
+
+    // Encodes attributes containing a WORD value followed by a 2 byte RFFU field.
+    fn encode_u16_val<W: Write>(writer: &mut W, value: u16) -> Result<(), AttributeEncodeError> {
+        writer.write_u16::<NetworkEndian>(value)?;
+        writer.write_u16::<NetworkEndian>(0)?;
+
+        Ok(())
+    }
+
+    // Encodes attributes containing two WORD values.
+    fn encode_u16_pair<W: Write>(
+        writer: &mut W,
+        first: u16,
+        second: u16,
+    ) -> Result<(), AttributeEncodeError> {
+        writer.write_u16::<NetworkEndian>(first)?;
+        writer.write_u16::<NetworkEndian>(second)?;
+
+        Ok(())
+    }
+
+    // Encodes the EcnCheck attribute.
+    fn encode_ecn_check<W: Write>(
+        writer: &mut W,
+        ecn_field: u8,
+        valid: bool,
+    ) -> Result<(), AttributeEncodeError> {
+        // Only the 3 least significant bits are used
+        let value = ((ecn_field & 0b11) as u32) << 1 | valid as u32;
 
-        Ok(cursor.get_ref().to_vec())
+        Self::encode_u32_val(writer, value)
     }
 
-    // Encodes the UnknownAttributes attribute.
-    fn encode_unknown_attributes(unknown_attrs: Vec<u16>) -> Result<Vec<u8>, AttributeEncodeError> {
-        let mut cursor = Cursor::new(Vec::new());
+    // Encodes the EvenPort attribute.
+    fn encode_even_port<W: Write>(
+        writer: &mut W,
+        reserve_next: bool,
+    ) -> Result<(), AttributeEncodeError> {
+        // The R bit is the most significant one, the rest are RFFU
+        let flags = if reserve_next { 0b1000_0000 } else { 0 };
+        writer.write_u8(flags)?;
 
-        // Write each attribute type into the list
-        for attr in unknown_attrs.iter() {
-            cursor.write_u16::<NetworkEndian>(*attr)?;
+        Ok(())
+    }
+
+    // Encodes the RequestedAddressFamily/AdditionalAddressFamily attributes.
+    fn encode_address_family<W: Write>(
+        writer: &mut W,
+        family: StunAddressFamily,
+    ) -> Result<(), AttributeEncodeError> {
+        // The family is followed by 3 reserved bytes
+        writer.write_u8(family.into())?;
+        writer.write_all(&[0; 3])?;
+
+        Ok(())
+    }
+
+    // Encodes the RequestedTransport attribute.
+    fn encode_requested_transport<W: Write>(
+        writer: &mut W,
+        protocol: u8,
+    ) -> Result<(), AttributeEncodeError> {
+        // Protocol number followed by 3 RFFU bytes
+        writer.write_all(&[protocol, 0, 0, 0])?;
+
+        Ok(())
+    }
+
+    // Encodes attributes containing a list of WORD values, such as the UnknownAttributes attribute.
+    fn encode_u16_list<W: Write>(
+        writer: &mut W,
+        values: &[u16],
+    ) -> Result<(), AttributeEncodeError> {
+        // Write each value into the list
+        for value in values.iter() {
+            writer.write_u16::<NetworkEndian>(*value)?;
+        }
+
+        Ok(())
+    }
+
+    // Encodes the ChangeRequest attribute.
+    fn encode_change_request<W: Write>(
+        writer: &mut W,
+        change_ip: bool,
+        change_port: bool,
+    ) -> Result<(), AttributeEncodeError> {
+        let mut flags = 0u32;
+        if change_ip {
+            flags |= 0b100;
+        }
+        if change_port {
+            flags |= 0b010;
+        }
+
+        Self::encode_u32_val(writer, flags)
+    }
+
+    // Encodes a single PasswordAlgorithm entry.
+    // Entries of the PasswordAlgorithms list have their parameters padded to a 4 byte boundary.
+    fn encode_password_algorithm<W: Write>(
+        writer: &mut W,
+        algorithm: StunPasswordAlgorithm,
+        parameters: &[u8],
+        padded: bool,
+    ) -> Result<(), AttributeEncodeError> {
+        if parameters.len() > u16::MAX as usize {
+            return Err(AttributeEncodeError::ValueTooBig(parameters.len()));
+        }
+
+        writer.write_u16::<NetworkEndian>(algorithm.into())?;
+        writer.write_u16::<NetworkEndian>(parameters.len() as u16)?;
+        writer.write_all(parameters)?;
+
+        if padded {
+            writer.write_all(&[0u8; 3][..Self::padding_len(parameters.len())])?;
         }
 
-        Ok(cursor.get_ref().to_vec())
+        Ok(())
     }
 
-    /// Encodes StunAttribute into bytes
-    pub(crate) fn encode(
+    /// Writes the attribute value (without the TLV header and padding) and returns the attribute type
+    fn encode_value<W: Write>(
         &self,
+        writer: &mut W,
         transaction_id: StunTransactionId,
-    ) -> Result<Vec<u8>, AttributeEncodeError> {
-        let (attr_type, encoded_attr) = match self {
-            StunAttribute::XorMappedAddress { socket_addr } => (
-                StunAttributeType::XorMappedAddress,
-                Self::encode_address(socket_addr, true, transaction_id),
-            ),
-            StunAttribute::MappedAddress { socket_addr } => (
-                StunAttributeType::MappedAddress,
-                Self::encode_address(socket_addr, false, transaction_id),
-            ),
-            StunAttribute::Username { value } => (
-                StunAttributeType::Username,
-                Self::encode_utf8_val(value, Some(513)),
-            ),
+    ) -> Result<u16, AttributeEncodeError> {
+        let attr_type = match self {
+            StunAttribute::XorMappedAddress { socket_addr } => {
+                Self::encode_address(writer, socket_addr, true, transaction_id)?;
+                StunAttributeType::XorMappedAddress
+            }
+            StunAttribute::MappedAddress { socket_addr } => {
+                Self::encode_address(writer, socket_addr, false, transaction_id)?;
+                StunAttributeType::MappedAddress
+            }
+            StunAttribute::Username { value } => {
+                Self::encode_utf8_val(writer, value, Some(513))?;
+                StunAttributeType::Username
+            }
             StunAttribute::MessageIntegrity { key } => {
-                (StunAttributeType::MessageIntegrity, Ok(key.clone()))
-            }
-            StunAttribute::Software { description } => (
-                StunAttributeType::Software,
-                Self::encode_utf8_val(description, Some(763)),
-            ),
-            StunAttribute::AlternateServer { socket_addr } => (
-                StunAttributeType::AlternateServer,
-                Self::encode_address(socket_addr, false, transaction_id),
-            ),
-            StunAttribute::Realm { value } => (
-                StunAttributeType::Realm,
-                Self::encode_utf8_val(value, Some(763)),
-            ),
-            StunAttribute::Nonce { value } => (
-                StunAttributeType::Nonce,
-                Self::encode_utf8_val(value, Some(763)),
-            ),
+                writer.write_all(key)?;
+                StunAttributeType::MessageIntegrity
+            }
+            StunAttribute::Software { description } => {
+                Self::encode_utf8_val(writer, description, Some(763))?;
+                StunAttributeType::Software
+            }
+            StunAttribute::AlternateServer { socket_addr } => {
+                Self::encode_address(writer, socket_addr, false, transaction_id)?;
+                StunAttributeType::AlternateServer
+            }
+            StunAttribute::Realm { value } => {
+                Self::encode_utf8_val(writer, value, Some(763))?;
+                StunAttributeType::Realm
+            }
+            StunAttribute::Nonce { value } => {
+                Self::encode_utf8_val(writer, value, Some(763))?;
+                StunAttributeType::Nonce
+            }
             StunAttribute::Fingerprint { value } => {
-                (StunAttributeType::Fingerprint, Self::encode_u32_val(*value))
-            }
-            StunAttribute::IceControlled { tie_breaker } => (
-                StunAttributeType::IceControlled,
-                Self::encode_u64_val(*tie_breaker),
-            ),
-            StunAttribute::IceControlling { tie_breaker } => (
-                StunAttributeType::IceControlling,
-                Self::encode_u64_val(*tie_breaker),
-            ),
+                Self::encode_u32_val(writer, *value)?;
+                StunAttributeType::Fingerprint
+            }
+            StunAttribute::IceControlled { tie_breaker } => {
+                Self::encode_u64_val(writer, *tie_breaker)?;
+                StunAttributeType::IceControlled
+            }
+            StunAttribute::IceControlling { tie_breaker } => {
+                Self::encode_u64_val(writer, *tie_breaker)?;
+                StunAttributeType::IceControlling
+            }
             StunAttribute::Priority { value } => {
-                (StunAttributeType::Priority, Self::encode_u32_val(*value))
+                Self::encode_u32_val(writer, *value)?;
+                StunAttributeType::Priority
             }
             StunAttribute::ErrorCode {
                 class,
                 number,
                 reason,
-            } => (
-                StunAttributeType::ErrorCode,
-                Self::encode_error_code(*class, *number, reason),
-            ),
-            StunAttribute::UnknownAttributes { types } => (
-                StunAttributeType::UnknownAttributes,
-                Self::encode_unknown_attributes(types.clone()),
-            ),
-            StunAttribute::UseCandidate => (StunAttributeType::UseCandidate, Ok(Vec::new())),
+            } => {
+                Self::encode_error_code(writer, None, *class, *number, reason)?;
+                StunAttributeType::ErrorCode
+            }
+            StunAttribute::UnknownAttributes { types } => {
+                Self::encode_u16_list(writer, types)?;
+                StunAttributeType::UnknownAttributes
+            }
+            StunAttribute::UseCandidate => StunAttributeType::UseCandidate,
+            StunAttribute::ChannelNumber { number } => {
+                Self::encode_u16_val(writer, *number)?;
+                StunAttributeType::ChannelNumber
+            }
+            StunAttribute::Lifetime { seconds } => {
+                Self::encode_u32_val(writer, *seconds)?;
+                StunAttributeType::Lifetime
+            }
+            StunAttribute::XorPeerAddress { socket_addr } => {
+                Self::encode_address(writer, socket_addr, true, transaction_id)?;
+                StunAttributeType::XorPeerAddress
+            }
+            StunAttribute::Data { value } => {
+                writer.write_all(value)?;
+                StunAttributeType::Data
+            }
+            StunAttribute::XorRelayedAddress { socket_addr } => {
+                Self::encode_address(writer, socket_addr, true, transaction_id)?;
+                StunAttributeType::XorRelayedAddress
+            }
+            StunAttribute::EvenPort { reserve_next } => {
+                Self::encode_even_port(writer, *reserve_next)?;
+                StunAttributeType::EvenPort
+            }
+            StunAttribute::RequestedTransport { protocol } => {
+                Self::encode_requested_transport(writer, *protocol)?;
+                StunAttributeType::RequestedTransport
+            }
+            StunAttribute::DontFragment => StunAttributeType::DontFragment,
+            StunAttribute::ReservationToken { token } => {
+                Self::encode_u64_val(writer, *token)?;
+                StunAttributeType::ReservationToken
+            }
+            StunAttribute::MessageIntegritySha256 { key } => {
+                if !is_valid_integrity_sha256_len(key.len()) {
+                    return Err(AttributeEncodeError::InvalidIntegrityLength(key.len()));
+                }
+
+                writer.write_all(key)?;
+                StunAttributeType::MessageIntegritySha256
+            }
+            StunAttribute::PasswordAlgorithm {
+                algorithm,
+                parameters,
+            } => {
+                Self::encode_password_algorithm(writer, *algorithm, parameters, false)?;
+                StunAttributeType::PasswordAlgorithm
+            }
+            StunAttribute::PasswordAlgorithms { algorithms } => {
+                for (algorithm, parameters) in algorithms.iter() {
+                    Self::encode_password_algorithm(writer, *algorithm, parameters, true)?;
+                }
+                StunAttributeType::PasswordAlgorithms
+            }
+            StunAttribute::Userhash { value } => {
+                writer.write_all(value)?;
+                StunAttributeType::Userhash
+            }
+            StunAttribute::ChangeRequest {
+                change_ip,
+                change_port,
+            } => {
+                Self::encode_change_request(writer, *change_ip, *change_port)?;
+                StunAttributeType::ChangeRequest
+            }
+            StunAttribute::ResponsePort { port } => {
+                Self::encode_u16_val(writer, *port)?;
+                StunAttributeType::ResponsePort
+            }
+            StunAttribute::Padding { value } => {
+                writer.write_all(value)?;
+                StunAttributeType::Padding
+            }
+            StunAttribute::ResponseOrigin { socket_addr } => {
+                Self::encode_address(writer, socket_addr, false, transaction_id)?;
+                StunAttributeType::ResponseOrigin
+            }
+            StunAttribute::OtherAddress { socket_addr } => {
+                Self::encode_address(writer, socket_addr, false, transaction_id)?;
+                StunAttributeType::OtherAddress
+            }
+            StunAttribute::ResponseAddress { socket_addr } => {
+                Self::encode_address(writer, socket_addr, false, transaction_id)?;
+                StunAttributeType::ResponseAddress
+            }
+            StunAttribute::SourceAddress { socket_addr } => {
+                Self::encode_address(writer, socket_addr, false, transaction_id)?;
+                StunAttributeType::SourceAddress
+            }
+            StunAttribute::ChangedAddress { socket_addr } => {
+                Self::encode_address(writer, socket_addr, false, transaction_id)?;
+                StunAttributeType::ChangedAddress
+            }
+            StunAttribute::ReflectedFrom { socket_addr } => {
+                Self::encode_address(writer, socket_addr, false, transaction_id)?;
+                StunAttributeType::ReflectedFrom
+            }
+            StunAttribute::ThirdPartyAuthorization { server_name } => {
+                Self::encode_utf8_val(writer, server_name, None)?;
+                StunAttributeType::ThirdPartyAuthorization
+            }
+            StunAttribute::AccessToken { value } => {
+                writer.write_all(value)?;
+                StunAttributeType::AccessToken
+            }
+            StunAttribute::ConnectionId { value } => {
+                Self::encode_u32_val(writer, *value)?;
+                StunAttributeType::ConnectionId
+            }
+            StunAttribute::RequestedAddressFamily { family } => {
+                Self::encode_address_family(writer, *family)?;
+                StunAttributeType::RequestedAddressFamily
+            }
+            StunAttribute::AdditionalAddressFamily { family } => {
+                Self::encode_address_family(writer, *family)?;
+                StunAttributeType::AdditionalAddressFamily
+            }
+            StunAttribute::AddressErrorCode {
+                family,
+                class,
+                number,
+                reason,
+            } => {
+                Self::encode_error_code(writer, Some(*family), *class, *number, reason)?;
+                StunAttributeType::AddressErrorCode
+            }
+            StunAttribute::Nomination { value } => {
+                Self::encode_u32_val(writer, *value)?;
+                StunAttributeType::Nomination
+            }
+            StunAttribute::GoogNetworkInfo {
+                network_id,
+                network_cost,
+            } => {
+                Self::encode_u32_val(writer, (*network_id as u32) << 16 | *network_cost as u32)?;
+                StunAttributeType::GoogNetworkInfo
+            }
+            StunAttribute::GoogLastIceCheckReceived { value } => {
+                writer.write_all(value)?;
+                StunAttributeType::GoogLastIceCheckReceived
+            }
+            StunAttribute::GoogMiscInfo { values } => {
+                Self::encode_u16_list(writer, values)?;
+                StunAttributeType::GoogMiscInfo
+            }
+            StunAttribute::GoogDelta { value } => {
+                writer.write_all(value)?;
+                StunAttributeType::GoogDelta
+            }
+            StunAttribute::GoogDeltaAck { value } => {
+                Self::encode_u64_val(writer, *value)?;
+                StunAttributeType::GoogDeltaAck
+            }
+            StunAttribute::MsVersion { version } => {
+                Self::encode_u32_val(writer, *version)?;
+                StunAttributeType::MsVersion
+            }
+            StunAttribute::MsSequenceNumber {
+                connection_id,
+                sequence_number,
+            } => {
+                writer.write_all(connection_id)?;
+                Self::encode_u32_val(writer, *sequence_number)?;
+                StunAttributeType::MsSequenceNumber
+            }
+            StunAttribute::MsServiceQuality {
+                stream_type,
+                service_quality,
+            } => {
+                Self::encode_u16_pair(writer, *stream_type, *service_quality)?;
+                StunAttributeType::MsServiceQuality
+            }
+            StunAttribute::BandwidthAdmissionControl { message_type } => {
+                // The message type follows 16 reserved bits
+                Self::encode_u16_pair(writer, 0, *message_type)?;
+                StunAttributeType::BandwidthAdmissionControl
+            }
+            StunAttribute::MsAlternateHostName { host_name } => {
+                Self::encode_utf8_val(writer, host_name, None)?;
+                StunAttributeType::MsAlternateHostName
+            }
+            StunAttribute::CandidateIdentifier { foundation } => {
+                writer.write_all(foundation)?;
+                StunAttributeType::CandidateIdentifier
+            }
+            StunAttribute::MsImplementationVersion { version } => {
+                Self::encode_u32_val(writer, *version)?;
+                StunAttributeType::MsImplementationVersion
+            }
+            StunAttribute::TransactionTransmitCounter { request, response } => {
+                // The counters follow 2 reserved bytes
+                writer.write_all(&[0, 0, *request, *response])?;
+                StunAttributeType::TransactionTransmitCounter
+            }
+            StunAttribute::MobilityTicket { value } => {
+                writer.write_all(value)?;
+                StunAttributeType::MobilityTicket
+            }
+            StunAttribute::EcnCheck { ecn_field, valid } => {
+                Self::encode_ecn_check(writer, *ecn_field, *valid)?;
+                StunAttributeType::EcnCheck
+            }
+            StunAttribute::Origin { value } => {
+                Self::encode_utf8_val(writer, value, None)?;
+                StunAttributeType::Origin
+            }
+            // Unknown attributes are written back as is
+            StunAttribute::Unknown { attr_type, value } => {
+                writer.write_all(value)?;
+                return Ok(*attr_type);
+            }
         };
 
-        // Wrap the encoded attribute data into TLV
-        Self::wrap_in_tlv(attr_type, &encoded_attr?)
+        Ok(attr_type as u16)
+    }
+
+    /// Returns the attribute type and the length of its value (without padding)
+    fn measure(
+        &self,
+        transaction_id: StunTransactionId,
+    ) -> Result<(u16, usize), AttributeEncodeError> {
+        let mut counter = ByteCounter::default();
+        let attr_type = self.encode_value(&mut counter, transaction_id)?;
+
+        Ok((attr_type, counter.0))
+    }
+
+    /// Returns the number of bytes the attribute takes when encoded, including the TLV header and padding
+    pub(crate) fn encoded_len(
+        &self,
+        transaction_id: StunTransactionId,
+    ) -> Result<usize, AttributeEncodeError> {
+        let (_, value_len) = self.measure(transaction_id)?;
+
+        Ok(4 + value_len + Self::padding_len(value_len))
+    }
+
+    // Calculates the amount of padding needed to align the value to a 4 byte boundary
+    fn padding_len(value_len: usize) -> usize {
+        (4 - value_len % 4) % 4
+    }
+
+    /// Encodes StunAttribute as a TLV directly into the writer
+    pub(crate) fn encode<W: Write>(
+        &self,
+        writer: &mut W,
+        transaction_id: StunTransactionId,
+    ) -> Result<(), AttributeEncodeError> {
+        let (attr_type, value_len) = self.measure(transaction_id)?;
+
+        if value_len > u16::MAX as usize {
+            return Err(AttributeEncodeError::ValueTooBig(value_len));
+        }
+
+        // Write the attribute type
+        writer.write_u16::<NetworkEndian>(attr_type)?;
+        // Write the attribute data length
+        writer.write_u16::<NetworkEndian>(value_len as u16)?;
+        // Write attribute data
+        self.encode_value(writer, transaction_id)?;
+
+        // Add attribute padding
+        // NOTE: As per [RFC5389 Section 15](https://tools.ietf.org/html/rfc5389#section-15) padding bytes may take any value.
+        // In this implementation NULL bytes are used
+        writer.write_all(&[0u8; 3][..Self::padding_len(value_len)])?;
+
+        Ok(())
+    }
+
+    // Writes a TLV with the provided value
+    fn encode_raw_tlv<W: Write>(
+        writer: &mut W,
+        attr_type: StunAttributeType,
+        value: &[u8],
+    ) -> Result<(), AttributeEncodeError> {
+        writer.write_u16::<NetworkEndian>(attr_type as u16)?;
+        writer.write_u16::<NetworkEndian>(value.len() as u16)?;
+        writer.write_all(value)?;
+        writer.write_all(&[0u8; 3][..Self::padding_len(value.len())])?;
+
+        Ok(())
+    }
+
+    /// Writes a MessageIntegrity attribute carrying the provided HMAC value
+    ///
+    /// Used by the message encoder to emit computed HMAC values without constructing an owned attribute.
+    pub(crate) fn encode_message_integrity<W: Write>(
+        writer: &mut W,
+        hmac: &[u8],
+    ) -> Result<(), AttributeEncodeError> {
+        Self::encode_raw_tlv(writer, StunAttributeType::MessageIntegrity, hmac)
+    }
+
+    /// Writes a MessageIntegritySha256 attribute carrying the provided (truncated) HMAC value
+    pub(crate) fn encode_message_integrity_sha256<W: Write>(
+        writer: &mut W,
+        hmac: &[u8],
+    ) -> Result<(), AttributeEncodeError> {
+        Self::encode_raw_tlv(writer, StunAttributeType::MessageIntegritySha256, hmac)
     }
 }
@@ -9,12 +9,19 @@ pub enum AttributeDecodeError {
     /// Failed to convert byte sequence into a UTF-8 string.
     #[error("Failed to convert byte sequence into a UTF-8 string.")]
     InvalidString(#[from] std::string::FromUtf8Error),
+    /// Failed to borrow a byte sequence as a UTF-8 string slice.
+    #[error("Failed to borrow byte sequence as a UTF-8 string slice.")]
+    InvalidStr(#[from] std::str::Utf8Error),
     /// Not enough data was provided to decode the value.
     #[error("Not enough data.")]
     InsufficientData(),
     /// Unrecognized field value was provided.
     #[error("Invalid field value: {0}.")]
     InvalidValue(u128),
+    /// The MESSAGE-INTEGRITY value must be 20 bytes long.
+    /// The MESSAGE-INTEGRITY-SHA256 value must be 16 to 32 bytes long and a multiple of 4.
+    #[error("Invalid integrity attribute length: {0}.")]
+    InvalidIntegrityLength(usize),
     /// Unrecognized attribute type value was provided.
     #[error("Unrecognized attribute type value: {attr_type:?}.")]
     UnrecognizedAttributeType {
@@ -38,4 +45,10 @@ pub enum AttributeEncodeError {
         /// The current length of the encoded value
         length: usize,
     },
+    /// The encoded attribute value doesn't fit into the 16-bit length field.
+    #[error("Attribute value too big: {0} bytes.")]
+    ValueTooBig(usize),
+    /// The MESSAGE-INTEGRITY-SHA256 value must be 16 to 32 bytes long and a multiple of 4.
+    #[error("Invalid MESSAGE-INTEGRITY-SHA256 length: {0}.")]
+    InvalidIntegrityLength(usize),
 }
@@ -1,9 +1,15 @@
+mod address_family;
 mod attribute;
+mod attribute_ref;
 mod decode;
 mod encode;
 mod errors;
+mod password_algorithm;
 mod types;
 mod utils;
 
+pub use address_family::StunAddressFamily;
 pub use attribute::StunAttribute;
+pub use attribute_ref::StunAttributeRef;
 pub use errors::{AttributeDecodeError, AttributeEncodeError};
+pub use password_algorithm::StunPasswordAlgorithm;
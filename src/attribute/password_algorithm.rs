@@ -0,0 +1,61 @@
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+/// [STUN password algorithm](https://tools.ietf.org/html/rfc8489#section-18.5)
+///
+/// Identifies the algorithm used to derive the long-term credential key
+/// from the username, realm and password.
+///
+/// The initial password algorithms are:
+///
+/// 0x0000: Reserved
+/// 0x0001: MD5
+/// 0x0002: SHA-256
+///
+/// Password algorithms in the range 0x0003 - 0xFFFF are not defined yet and are represented by the `Other` variant.
+///
+///```
+/// use stun_coder::StunPasswordAlgorithm;
+///
+/// assert_eq!(StunPasswordAlgorithm::from(0x0002), StunPasswordAlgorithm::Sha256);
+/// assert_eq!(u16::from(StunPasswordAlgorithm::Md5), 0x0001);
+///```
+pub enum StunPasswordAlgorithm {
+    /// [RFC8489: MD5](https://tools.ietf.org/html/rfc8489#section-18.5.1.1)
+    ///
+    /// The key length is 16 bytes and the parameters value is empty.
+    Md5,
+    /// [RFC8489: SHA-256](https://tools.ietf.org/html/rfc8489#section-18.5.1.2)
+    ///
+    /// The key length is 32 bytes and the parameters value is empty.
+    Sha256,
+    /// Password algorithm not known to this implementation
+    Other(u16),
+}
+
+impl From<u16> for StunPasswordAlgorithm {
+    /// Converts a password algorithm number into a password algorithm
+    fn from(algorithm: u16) -> Self {
+        match algorithm {
+            0x0001 => Self::Md5,
+            0x0002 => Self::Sha256,
+            other => Self::Other(other),
+        }
+    }
+}
+
+impl From<StunPasswordAlgorithm> for u16 {
+    /// Converts a password algorithm into its number
+    fn from(algorithm: StunPasswordAlgorithm) -> Self {
+        match algorithm {
+            StunPasswordAlgorithm::Md5 => 0x0001,
+            StunPasswordAlgorithm::Sha256 => 0x0002,
+            StunPasswordAlgorithm::Other(other) => other,
+        }
+    }
+}
+
+impl Default for StunPasswordAlgorithm {
+    /// MD5 is used when no PASSWORD-ALGORITHM attribute is present, as per [RFC8489 Section 9.2.2](https://tools.ietf.org/html/rfc8489#section-9.2.2)
+    fn default() -> Self {
+        Self::Md5
+    }
+}
@@ -16,4 +16,49 @@ pub enum StunAttributeType {
     Software = 0x8022,
     AlternateServer = 0x8023,
     Fingerprint = 0x8028,
+    ChannelNumber = 0x000C,
+    Lifetime = 0x000D,
+    XorPeerAddress = 0x0012,
+    Data = 0x0013,
+    XorRelayedAddress = 0x0016,
+    EvenPort = 0x0018,
+    RequestedTransport = 0x0019,
+    DontFragment = 0x001A,
+    ReservationToken = 0x0022,
+    MessageIntegritySha256 = 0x001C,
+    PasswordAlgorithm = 0x001D,
+    PasswordAlgorithms = 0x8002,
+    Userhash = 0x001E,
+    ChangeRequest = 0x0003,
+    ResponsePort = 0x0027,
+    Padding = 0x0026,
+    ResponseOrigin = 0x802B,
+    OtherAddress = 0x802C,
+    ResponseAddress = 0x0002,
+    SourceAddress = 0x0004,
+    ChangedAddress = 0x0005,
+    ReflectedFrom = 0x000B,
+    ThirdPartyAuthorization = 0x802E,
+    AccessToken = 0x001B,
+    ConnectionId = 0x002A,
+    RequestedAddressFamily = 0x0017,
+    AdditionalAddressFamily = 0x8000,
+    AddressErrorCode = 0x8001,
+    Nomination = 0xC001,
+    GoogNetworkInfo = 0xC057,
+    GoogLastIceCheckReceived = 0xC058,
+    GoogMiscInfo = 0xC059,
+    GoogDelta = 0xC05C,
+    GoogDeltaAck = 0xC05D,
+    MsVersion = 0x8008,
+    MsSequenceNumber = 0x8050,
+    MsServiceQuality = 0x8055,
+    BandwidthAdmissionControl = 0x8056,
+    MsAlternateHostName = 0x8032,
+    CandidateIdentifier = 0x8054,
+    MsImplementationVersion = 0x8070,
+    TransactionTransmitCounter = 0x8025,
+    MobilityTicket = 0x8030,
+    EcnCheck = 0x802D,
+    Origin = 0x802F,
 }
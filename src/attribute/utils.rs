@@ -1,3 +1,8 @@
+use byteorder::{NetworkEndian, ReadBytesExt};
+use std::io::Cursor;
+
+use super::errors::AttributeDecodeError;
+
 /// XORs a u8 slice
 pub fn xor_byte_range(target: &mut [u8], xor: &[u8]) {
     // Make sure the target slice size is matching or bigger than the xor slice size.
@@ -13,3 +18,33 @@ pub fn xor_byte_range(target: &mut [u8], xor: &[u8]) {
         .zip(xor.iter())
         .for_each(|(x1, x2)| *x1 ^= *x2);
 }
+
+/// Checks the MESSAGE-INTEGRITY-SHA256 value length: 16 to 32 bytes, multiple of 4.
+pub fn is_valid_integrity_sha256_len(len: usize) -> bool {
+    (16..=32).contains(&len) && len & 3 == 0
+}
+
+/// Reads a TLV encoded attribute and returns its type along with the borrowed value.
+/// The cursor position is advanced past the attribute padding.
+pub fn read_tlv<'a>(
+    cursor: &mut Cursor<&'a [u8]>,
+) -> Result<(u16, &'a [u8]), AttributeDecodeError> {
+    // Read attribute type
+    let attr_type = cursor.read_u16::<NetworkEndian>()?;
+    // Read attribute data length
+    let attr_len = cursor.read_u16::<NetworkEndian>()? as usize;
+
+    let bytes: &'a [u8] = cursor.get_ref();
+    let start = cursor.position() as usize;
+    let end = start + attr_len;
+
+    if end > bytes.len() {
+        return Err(AttributeDecodeError::InsufficientData());
+    }
+
+    // Calculate the padding and advance the cursor
+    let padded_len = (attr_len + 3) & !3;
+    cursor.set_position((start + padded_len) as u64);
+
+    Ok((attr_type, &bytes[start..end]))
+}
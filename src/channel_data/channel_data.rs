@@ -0,0 +1,103 @@
+use crate::definitions::{CHANNEL_DATA_HEADER_SIZE, CHANNEL_NUMBER_MAX, CHANNEL_NUMBER_MIN};
+
+/// [TURN ChannelData message](https://tools.ietf.org/html/rfc8656#section-12.4)
+///
+/// An example of encoding and decoding a ChannelData message:
+///```
+/// // Create a ChannelData message for channel 0x4001
+/// let channel_data = stun_coder::ChannelData::new(0x4001, vec![0xde, 0xad, 0xbe, 0xef, 0x01]);
+///
+/// // Encode it with padding, as required over TCP
+/// let encoded = channel_data.encode(true).unwrap();
+///
+/// // Decode it back
+/// let decoded = stun_coder::ChannelData::decode(&encoded).unwrap();
+///
+/// assert_eq!(decoded.get_channel_number(), 0x4001);
+/// assert_eq!(decoded.get_data(), &[0xde, 0xad, 0xbe, 0xef, 0x01]);
+///```
+///
+///   The ChannelData message is used to carry application data between
+///   the client and the server.  It has the following format:
+///```text
+///       0                   1                   2                   3
+///       0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+///      +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+///      |         Channel Number        |            Length             |
+///      +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+///      |                                                               |
+///      /                       Application Data                        /
+///      /                                                               /
+///      |                                                               |
+///      |                               +-------------------------------+
+///      |                               |
+///      +-------------------------------+
+///```
+///   The Channel Number field specifies the number of the channel on which
+///   the data is traveling, and thus, the address of the peer that is
+///   sending or is to receive the data.
+///
+///   The Length field specifies the length in bytes of the application
+///   data field (i.e., it does not include the size of the ChannelData
+///   header).  Note that 0 is a valid length.
+///
+///   The Application Data field carries the data the client is trying to
+///   send to the peer, or that the peer is sending to the client.
+///
+///   Over TCP and TLS-over-TCP, the ChannelData message MUST be padded to
+///   a multiple of four bytes in order to ensure the alignment of
+///   subsequent messages.  The padding is not reflected in the length
+///   field of the ChannelData message, so the actual size of a ChannelData
+///   message (including padding) is (4 + Length) rounded up to the nearest
+///   multiple of 4.  Over UDP, the padding is not required but MAY be
+///   included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelData {
+    /// Number of the channel the data is traveling on
+    pub(super) channel_number: u16,
+    /// Application data
+    pub(super) data: Vec<u8>,
+}
+
+impl ChannelData {
+    /// Creates a new ChannelData message
+    ///
+    /// The channel number is validated when the message is encoded.
+    pub fn new(channel_number: u16, data: Vec<u8>) -> Self {
+        Self {
+            channel_number,
+            data,
+        }
+    }
+
+    /// Returns the channel number
+    pub fn get_channel_number(&self) -> u16 {
+        self.channel_number
+    }
+
+    /// Returns an immutable reference to the application data
+    pub fn get_data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Consumes the message and returns the application data
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+
+    /// Checks whether the channel number is within the 0x4000 - 0x7FFF range allowed by [RFC8656](https://tools.ietf.org/html/rfc8656#section-12)
+    pub fn is_valid_channel_number(channel_number: u16) -> bool {
+        (CHANNEL_NUMBER_MIN..=CHANNEL_NUMBER_MAX).contains(&channel_number)
+    }
+
+    /// Calculates the message size for the provided application data length
+    pub(super) fn frame_len(data_len: usize, padded: bool) -> usize {
+        let len = CHANNEL_DATA_HEADER_SIZE + data_len;
+
+        if padded {
+            (len + 3) & !3
+        } else {
+            len
+        }
+    }
+}
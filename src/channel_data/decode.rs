@@ -0,0 +1,60 @@
+use byteorder::{NetworkEndian, ReadBytesExt};
+
+use super::channel_data::ChannelData;
+use super::errors::ChannelDataDecodeError;
+use crate::definitions::CHANNEL_DATA_HEADER_SIZE;
+
+impl ChannelData {
+    /// Decodes and returns the ChannelData message
+    ///
+    /// Any bytes following the application data, such as padding, are ignored.
+    ///
+    /// Arguments:
+    ///
+    /// * `bytes`: binary encoded message to decode from
+    pub fn decode(bytes: &[u8]) -> Result<Self, ChannelDataDecodeError> {
+        let (channel_number, data_len) = Self::decode_header(bytes)?;
+
+        let available = bytes.len() - CHANNEL_DATA_HEADER_SIZE;
+        if available < data_len {
+            return Err(ChannelDataDecodeError::InsufficientData {
+                expected: data_len,
+                available,
+            });
+        }
+
+        let data = bytes[CHANNEL_DATA_HEADER_SIZE..CHANNEL_DATA_HEADER_SIZE + data_len].to_vec();
+
+        Ok(Self {
+            channel_number,
+            data,
+        })
+    }
+
+    /// Decodes the header and returns the size of the whole message
+    ///
+    /// This function is intended to be used for framing ChannelData messages received over stream transports.
+    /// Only the first 4 bytes are processed.
+    ///
+    /// Arguments:
+    ///
+    /// * `bytes`: binary encoded message header
+    /// * `padded`: Accounts for the padding to a multiple of 4 bytes. Required over TCP and TLS-over-TCP.
+    pub fn decode_message_len(bytes: &[u8], padded: bool) -> Result<usize, ChannelDataDecodeError> {
+        let (_, data_len) = Self::decode_header(bytes)?;
+
+        Ok(Self::frame_len(data_len, padded))
+    }
+
+    /// Decodes the channel number and application data length from the header
+    pub(crate) fn decode_header(mut bytes: &[u8]) -> Result<(u16, usize), ChannelDataDecodeError> {
+        let channel_number = bytes.read_u16::<NetworkEndian>()?;
+        let data_len = bytes.read_u16::<NetworkEndian>()?;
+
+        if !Self::is_valid_channel_number(channel_number) {
+            return Err(ChannelDataDecodeError::InvalidChannelNumber(channel_number));
+        }
+
+        Ok((channel_number, data_len as usize))
+    }
+}
@@ -0,0 +1,52 @@
+use byteorder::{NetworkEndian, WriteBytesExt};
+use std::io::{Cursor, Write};
+
+use super::channel_data::ChannelData;
+use super::errors::ChannelDataEncodeError;
+
+impl ChannelData {
+    /// Encodes the ChannelData message into a binary representation
+    ///
+    /// Arguments:
+    ///
+    /// * `padded`: Pads the message to a multiple of 4 bytes. Required over TCP and TLS-over-TCP, optional over UDP.
+    pub fn encode(&self, padded: bool) -> Result<Vec<u8>, ChannelDataEncodeError> {
+        if !Self::is_valid_channel_number(self.channel_number) {
+            return Err(ChannelDataEncodeError::InvalidChannelNumber(
+                self.channel_number,
+            ));
+        }
+
+        let data_len = self.data.len();
+        if data_len > u16::MAX as usize {
+            return Err(ChannelDataEncodeError::DataTooBig(data_len));
+        }
+
+        let mut cursor = Cursor::new(Vec::with_capacity(self.encoded_len(padded)));
+
+        // Write the channel number
+        cursor.write_u16::<NetworkEndian>(self.channel_number)?;
+        // Write the application data length. Padding is not reflected in it.
+        cursor.write_u16::<NetworkEndian>(data_len as u16)?;
+        // Write the application data
+        cursor.write_all(&self.data)?;
+
+        // Calculate and add padding
+        // NOTE: As with STUN attributes, NULL bytes are used for padding
+        if padded {
+            let padding = (4 - data_len % 4) % 4;
+            cursor.write_all(&[0u8; 3][..padding])?;
+        }
+
+        Ok(cursor.into_inner())
+    }
+
+    /// Returns the size of the encoded message
+    ///
+    /// Arguments:
+    ///
+    /// * `padded`: Accounts for the padding to a multiple of 4 bytes.
+    pub fn encoded_len(&self, padded: bool) -> usize {
+        Self::frame_len(self.data.len(), padded)
+    }
+}
@@ -0,0 +1,35 @@
+use thiserror::Error;
+
+/// ChannelData decoding errors.
+#[derive(Error, Debug)]
+pub enum ChannelDataDecodeError {
+    /// Failed to read field value.
+    #[error("Failed to read field value.")]
+    ReadFailure(#[from] std::io::Error),
+    /// The channel number is outside of the 0x4000 - 0x7FFF range.
+    /// It's possible that the received byte sequence is not a ChannelData message.
+    #[error("Invalid channel number: {0:#X?}.")]
+    InvalidChannelNumber(u16),
+    /// The provided bytes are shorter than the length specified in the ChannelData header.
+    #[error("Not enough data. Expected length: {expected}, available length: {available}.")]
+    InsufficientData {
+        /// Application data length specified in the header
+        expected: usize,
+        /// Application data length available after the header
+        available: usize,
+    },
+}
+
+/// ChannelData encoding errors.
+#[derive(Error, Debug)]
+pub enum ChannelDataEncodeError {
+    /// Failed to write field value.
+    #[error("Failed to write field value.")]
+    WriteFailure(#[from] std::io::Error),
+    /// The channel number is outside of the 0x4000 - 0x7FFF range.
+    #[error("Invalid channel number: {0:#X?}.")]
+    InvalidChannelNumber(u16),
+    /// The application data doesn't fit into the 16-bit length field.
+    #[error("Application data too big. Length: {0}.")]
+    DataTooBig(usize),
+}
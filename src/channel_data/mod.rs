@@ -0,0 +1,7 @@
+mod channel_data;
+mod decode;
+mod encode;
+mod errors;
+
+pub use channel_data::ChannelData;
+pub use errors::{ChannelDataDecodeError, ChannelDataEncodeError};
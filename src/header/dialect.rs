@@ -0,0 +1,29 @@
+/// STUN protocol dialect of a message
+///
+/// [RFC5389](https://tools.ietf.org/html/rfc5389#section-6) replaced the first 32 bits of the
+/// 128-bit transaction ID defined by [RFC3489](https://tools.ietf.org/html/rfc3489#section-11.1)
+/// with the fixed magic cookie value 0x2112A442:
+///
+///   The magic cookie field MUST contain the fixed value 0x2112A442 in
+///   network byte order.  In RFC 3489 \[RFC3489\], this field was part of
+///   the transaction ID; placing the magic cookie in this location allows
+///   a server to detect if the client will understand certain attributes
+///   that were added in this revised specification.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum StunDialect {
+    /// [RFC5389](https://tools.ietf.org/html/rfc5389) and [RFC8489](https://tools.ietf.org/html/rfc8489) message carrying the magic cookie
+    Rfc5389,
+    /// Classic [RFC3489](https://tools.ietf.org/html/rfc3489) message with a 128-bit transaction ID
+    Rfc3489 {
+        /// The first 32 bits of the transaction ID, occupying the magic cookie field.
+        /// The remaining 96 bits are kept in the `transaction_id` field of the header.
+        transaction_id_prefix: [u8; 4],
+    },
+}
+
+impl Default for StunDialect {
+    /// Messages are encoded according to [RFC5389](https://tools.ietf.org/html/rfc5389) unless a 128-bit transaction ID is set
+    fn default() -> Self {
+        Self::Rfc5389
+    }
+}
@@ -10,9 +10,10 @@ pub enum HeaderDecodeError {
     /// It's possible that the received byte sequence is not a STUN message.
     #[error("Magic cookie mismatch.")]
     MagicCookieMismatch(),
-    /// Unrecognized message method type value.
-    #[error("Unrecognized message method type value: {0}.")]
-    UnrecognizedMessageMethod(u16),
+    /// The most significant 2 bits of the message type field are not zeroes.
+    /// It's possible that the received byte sequence is not a STUN message.
+    #[error("Invalid message type value: {0:#X?}.")]
+    InvalidMessageType(u16),
     /// Unrecognized message class type value.
     #[error("Unrecognized message class type value: {0}.")]
     UnrecognizedMessageClass(u16),
@@ -24,4 +25,8 @@ pub enum HeaderEncodeError {
     /// Failed to write field value.
     #[error("Failed to write field value.")]
     WriteFailure(#[from] std::io::Error),
+    /// The method value of `StunMessageMethod::Other` is out of the 12-bit range
+    /// or belongs to one of the known methods.
+    #[error("Invalid message method value: {0:#X?}.")]
+    InvalidMessageMethod(u16),
 }
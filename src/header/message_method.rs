@@ -1,16 +1,16 @@
-#[derive(Debug, Copy, Clone, FromPrimitive, Ord, PartialOrd, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 /// [STUN message method](https://tools.ietf.org/html/rfc5389#section-18.1)
 ///
 /// A STUN method is a hex number in the range 0x000 - 0xFFF.  The
 /// encoding of STUN method into a STUN message is described in
 /// Section 6.
-
+///
 /// The initial STUN methods are:
-
+///
 /// 0x000: (Reserved)
 /// 0x001: Binding
 /// 0x002: (Reserved; was SharedSecret)
-
+///
 /// STUN methods in the range 0x000 - 0x7FF are assigned by IETF Review
 /// [RFC5226](https://tools.ietf.org/html/rfc5226).  STUN methods in the range 0x800 - 0xFFF are assigned by
 /// Designated Expert [RFC5226](https://tools.ietf.org/html/rfc5226).  The responsibility of the expert is to
@@ -18,7 +18,98 @@
 /// request is not for an abnormally large number of codepoints.
 /// Technical review of the extension itself is outside the scope of the
 /// designated expert responsibility.
+///
+/// Methods that are not known to this implementation are represented by the `Other` variant,
+/// so that vendor specific and future methods can be passed through without any loss.
+///
+/// Conversion from and to the 12-bit method value is done via the `From` trait:
+///```
+/// use stun_coder::StunMessageMethod;
+///
+/// assert_eq!(StunMessageMethod::from(0x003), StunMessageMethod::Allocate);
+/// assert_eq!(StunMessageMethod::from(0x801), StunMessageMethod::Other(0x801));
+/// assert_eq!(u16::from(StunMessageMethod::Other(0x801)), 0x801);
+///```
 pub enum StunMessageMethod {
     /// STUN binding request method
-    BindingRequest = 0b0000_0000_0000_0001,
+    BindingRequest,
+    /// [TURN Allocate method](https://tools.ietf.org/html/rfc8656#section-17)
+    ///
+    /// Only request/response semantics defined.
+    Allocate,
+    /// [TURN Refresh method](https://tools.ietf.org/html/rfc8656#section-17)
+    ///
+    /// Only request/response semantics defined.
+    Refresh,
+    /// [TURN Send method](https://tools.ietf.org/html/rfc8656#section-17)
+    ///
+    /// Only indication semantics defined.
+    Send,
+    /// [TURN Data method](https://tools.ietf.org/html/rfc8656#section-17)
+    ///
+    /// Only indication semantics defined.
+    Data,
+    /// [TURN CreatePermission method](https://tools.ietf.org/html/rfc8656#section-17)
+    ///
+    /// Only request/response semantics defined.
+    CreatePermission,
+    /// [TURN ChannelBind method](https://tools.ietf.org/html/rfc8656#section-17)
+    ///
+    /// Only request/response semantics defined.
+    ChannelBind,
+    /// [TURN-TCP Connect method](https://tools.ietf.org/html/rfc6062#section-6.1)
+    ///
+    /// Only request/response semantics defined.
+    Connect,
+    /// [TURN-TCP ConnectionBind method](https://tools.ietf.org/html/rfc6062#section-6.1)
+    ///
+    /// Only request/response semantics defined.
+    ConnectionBind,
+    /// [TURN-TCP ConnectionAttempt method](https://tools.ietf.org/html/rfc6062#section-6.1)
+    ///
+    /// Only indication semantics defined.
+    ConnectionAttempt,
+    /// Any other STUN method
+    ///
+    /// The value must be a 12-bit method value not covered by the other variants, otherwise the message fails to encode.
+    /// Use the `From<u16>` conversion to get the normalized representation of a method value.
+    Other(u16),
+}
+
+impl From<u16> for StunMessageMethod {
+    /// Converts a 12-bit method value into a STUN method
+    fn from(method: u16) -> Self {
+        match method {
+            0x001 => Self::BindingRequest,
+            0x003 => Self::Allocate,
+            0x004 => Self::Refresh,
+            0x006 => Self::Send,
+            0x007 => Self::Data,
+            0x008 => Self::CreatePermission,
+            0x009 => Self::ChannelBind,
+            0x00A => Self::Connect,
+            0x00B => Self::ConnectionBind,
+            0x00C => Self::ConnectionAttempt,
+            other => Self::Other(other),
+        }
+    }
+}
+
+impl From<StunMessageMethod> for u16 {
+    /// Converts a STUN method into its 12-bit method value
+    fn from(method: StunMessageMethod) -> Self {
+        match method {
+            StunMessageMethod::BindingRequest => 0x001,
+            StunMessageMethod::Allocate => 0x003,
+            StunMessageMethod::Refresh => 0x004,
+            StunMessageMethod::Send => 0x006,
+            StunMessageMethod::Data => 0x007,
+            StunMessageMethod::CreatePermission => 0x008,
+            StunMessageMethod::ChannelBind => 0x009,
+            StunMessageMethod::Connect => 0x00A,
+            StunMessageMethod::ConnectionBind => 0x00B,
+            StunMessageMethod::ConnectionAttempt => 0x00C,
+            StunMessageMethod::Other(other) => other,
+        }
+    }
 }
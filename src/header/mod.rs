@@ -2,12 +2,14 @@ use byteorder::{NetworkEndian, ReadBytesExt, WriteBytesExt};
 use num_traits::FromPrimitive;
 use std::io::{Cursor, Read, Write};
 
+mod dialect;
 mod errors;
 mod message_class;
 mod message_method;
 
 use crate::definitions::{StunTransactionId, STUN_MAGIC_COOKIE_U32};
 use crate::utils::generate_transaction_id;
+pub use dialect::StunDialect;
 pub use errors::{HeaderDecodeError, HeaderEncodeError};
 pub use message_class::StunMessageClass;
 pub use message_method::StunMessageMethod;
@@ -31,7 +33,6 @@ pub use message_method::StunMessageMethod;
 /// a response message) and indication transactions (which consist of a
 /// single indication message).  Response classes are split into error
 /// and success responses to aid in quickly processing the STUN message.
-
 pub struct StunHeader {
     /// STUN message class
     pub message_class: StunMessageClass,
@@ -42,6 +43,10 @@ pub struct StunHeader {
     /// STUN message length
     /// Only set to a non-zero value when decoding the header
     pub message_len: u16,
+    /// STUN protocol dialect
+    ///
+    /// Struct literals have to set it as well, usually to `StunDialect::Rfc5389`.
+    pub dialect: StunDialect,
 }
 
 impl StunHeader {
@@ -65,27 +70,62 @@ impl StunHeader {
             message_class,
             transaction_id,
             message_len: 0, // Placeholder for the encoder to later fill in
+            dialect: StunDialect::Rfc5389,
+        }
+    }
+
+    /// Returns the full 128-bit transaction ID of a classic [RFC3489](https://tools.ietf.org/html/rfc3489#section-11.1) message
+    pub fn get_legacy_transaction_id(&self) -> Option<[u8; 16]> {
+        match self.dialect {
+            StunDialect::Rfc3489 {
+                transaction_id_prefix,
+            } => {
+                let mut transaction_id = [0; 16];
+                transaction_id[..4].copy_from_slice(&transaction_id_prefix);
+                transaction_id[4..].copy_from_slice(&self.transaction_id);
+
+                Some(transaction_id)
+            }
+            StunDialect::Rfc5389 => None,
         }
     }
 
     /// Decodes and returns a STUN message header
-    pub(crate) fn decode(cursor: &mut Cursor<&[u8]>) -> Result<Self, HeaderDecodeError> {
+    ///
+    /// Arguments:
+    ///
+    /// * `cursor`: Cursor pointing to the beginning of the header
+    /// * `allow_legacy`: Accept classic RFC3489 headers that don't carry the magic cookie
+    pub(crate) fn decode(
+        cursor: &mut Cursor<&[u8]>,
+        allow_legacy: bool,
+    ) -> Result<Self, HeaderDecodeError> {
         let stun_type_field = cursor.read_u16::<NetworkEndian>()?;
         let msg_len = cursor.read_u16::<NetworkEndian>()?;
         let magic_cookie = cursor.read_u32::<NetworkEndian>()?;
 
-        if magic_cookie != STUN_MAGIC_COOKIE_U32 {
+        let dialect = if magic_cookie == STUN_MAGIC_COOKIE_U32 {
+            StunDialect::Rfc5389
+        } else if allow_legacy {
+            StunDialect::Rfc3489 {
+                transaction_id_prefix: magic_cookie.to_be_bytes(),
+            }
+        } else {
             return Err(HeaderDecodeError::MagicCookieMismatch());
-        }
+        };
 
         let mut transaction_id = [0; 12];
         cursor.read_exact(&mut transaction_id)?;
 
+        // The most significant 2 bits of every STUN message MUST be zeroes
+        if stun_type_field & 0b1100_0000_0000_0000 != 0 {
+            return Err(HeaderDecodeError::InvalidMessageType(stun_type_field));
+        }
+
         let stun_class = stun_type_field & 0b0000_0001_0001_0000;
-        let stun_method = stun_type_field & 0b1111_1110_1110_1111;
+        let stun_method = Self::decode_method_bits(stun_type_field);
 
-        let message_method: StunMessageMethod = FromPrimitive::from_u16(stun_method)
-            .ok_or(HeaderDecodeError::UnrecognizedMessageMethod(stun_method))?;
+        let message_method = StunMessageMethod::from(stun_method);
         let message_class: StunMessageClass = FromPrimitive::from_u16(stun_class)
             .ok_or(HeaderDecodeError::UnrecognizedMessageClass(stun_class))?;
 
@@ -94,21 +134,50 @@ impl StunHeader {
             message_class,
             message_len: msg_len,
             transaction_id,
+            dialect,
         })
     }
 
     /// Encodes itself into the binary representation defined by [RFC5389](https://tools.ietf.org/html/rfc5389)
-    pub(crate) fn encode(&self) -> Result<Vec<u8>, HeaderEncodeError> {
-        let bytes = Vec::new();
-        let mut cursor = Cursor::new(bytes);
+    ///
+    /// Classic RFC3489 headers carry the first 32 bits of the transaction ID in place of the magic cookie.
+    pub(crate) fn encode<W: Write>(&self, writer: &mut W) -> Result<(), HeaderEncodeError> {
+        // The `Other` variant only holds 12-bit method values not covered by the known variants
+        if let StunMessageMethod::Other(method) = self.message_method {
+            if method > 0x0FFF || StunMessageMethod::from(method) != self.message_method {
+                return Err(HeaderEncodeError::InvalidMessageMethod(method));
+            }
+        }
 
-        let stun_type_field = self.message_class as u16 | self.message_method as u16;
+        let stun_type_field =
+            self.message_class as u16 | Self::encode_method_bits(self.message_method.into());
+
+        writer.write_u16::<NetworkEndian>(stun_type_field)?;
+        writer.write_u16::<NetworkEndian>(self.message_len)?;
+        match self.dialect {
+            StunDialect::Rfc5389 => writer.write_u32::<NetworkEndian>(STUN_MAGIC_COOKIE_U32)?,
+            StunDialect::Rfc3489 {
+                transaction_id_prefix,
+            } => writer.write_all(&transaction_id_prefix)?,
+        }
+        writer.write_all(&self.transaction_id)?;
 
-        cursor.write_u16::<NetworkEndian>(stun_type_field)?;
-        cursor.write_u16::<NetworkEndian>(self.message_len)?;
-        cursor.write_u32::<NetworkEndian>(STUN_MAGIC_COOKIE_U32)?;
-        cursor.write_all(&self.transaction_id)?;
+        Ok(())
+    }
+
+    /// Extracts the 12-bit method value from the message type field
+    ///
+    /// The method bits M0-M3, M4-M6 and M7-M11 are separated by the class bits C0 and C1.
+    fn decode_method_bits(stun_type_field: u16) -> u16 {
+        (stun_type_field & 0b0011_1110_0000_0000) >> 2
+            | (stun_type_field & 0b0000_0000_1110_0000) >> 1
+            | (stun_type_field & 0b0000_0000_0000_1111)
+    }
 
-        Ok(cursor.get_ref().to_vec())
+    /// Spreads the 12-bit method value across the message type field bits, leaving space for the class bits
+    fn encode_method_bits(method: u16) -> u16 {
+        (method & 0b0000_1111_1000_0000) << 2
+            | (method & 0b0000_0000_0111_0000) << 1
+            | (method & 0b0000_0000_0000_1111)
     }
 }
@@ -3,6 +3,14 @@
 //! STUN Coder is a STUN protocol encoder and decoder for Rust.
 //! The implementation is done according to [Session Traversal Utilities for NAT (STUN)](https://tools.ietf.org/html/rfc5389).
 //! STUN extensions specified by the [Interactive Connectivity Establishment (ICE) protocol](https://tools.ietf.org/html/rfc8445#section-7.1) are also supported.
+//! Methods and attributes defined by [Traversal Using Relays around NAT (TURN)](https://tools.ietf.org/html/rfc8656) are supported as well.
+//! The [RFC8489](https://tools.ietf.org/html/rfc8489) MESSAGE-INTEGRITY-SHA256 attribute and password algorithms can be used for message integrity.
+//! A sans-IO [NAT behavior discovery](https://tools.ietf.org/html/rfc5780) engine is provided via `NatBehaviorDiscovery`.
+//! [Third-party authorization](https://tools.ietf.org/html/rfc7635) is supported via the ACCESS-TOKEN attribute and `AccessToken`.
+//! Classic [RFC3489](https://tools.ietf.org/html/rfc3489) messages can be decoded via `StunMessage::decode_legacy` and encoded by setting a 128-bit transaction ID.
+//! The long-term credential handshake is run by `LongTermCredentialClient` on the client side and by `LongTermAuthenticator` on the server side. Servers that don't keep per-client state can plug the HMAC-signed nonces of `StatelessNonce` into the authenticator.
+//! Binding requests can be answered via the sans-IO `StunBindingResponder`, and retransmitted requests replayed from `StunResponseCache`.
+//! Request retransmissions and response matching are handled by the sans-IO `StunClientTransaction`, while `StunTransactionCorrelator` routes responses among many outstanding transactions and `StunRtoEstimator` adapts the RTO to each server.
 //!
 //! An example of creating and encoding a STUN binding request:
 //!```
@@ -140,18 +148,47 @@
 #[macro_use]
 extern crate num_derive;
 
+mod access_token;
 mod attribute;
+mod channel_data;
 mod definitions;
 mod header;
 mod message;
+mod nat_discovery;
+mod server;
+mod transaction;
 mod utils;
 
 #[cfg(test)]
 mod tests;
 
-pub use attribute::{AttributeDecodeError, AttributeEncodeError, StunAttribute};
+pub use access_token::{AccessToken, AccessTokenAlgorithm, AccessTokenError};
+pub use attribute::{
+    AttributeDecodeError, AttributeEncodeError, StunAddressFamily, StunAttribute, StunAttributeRef,
+    StunPasswordAlgorithm,
+};
+pub use channel_data::{ChannelData, ChannelDataDecodeError, ChannelDataEncodeError};
 pub use header::{
-    HeaderDecodeError, HeaderEncodeError, StunHeader, StunMessageClass, StunMessageMethod,
+    HeaderDecodeError, HeaderEncodeError, StunDialect, StunHeader, StunMessageClass,
+    StunMessageMethod,
+};
+pub use message::{
+    IntegrityKeyGenerationError, MessageDecodeError, MessageEncodeError, StunMessage,
+    StunMessageRef,
+};
+pub use nat_discovery::{
+    NatBehaviorDiscovery, NatBehaviorReport, NatDiscoveryError, NatDiscoveryTransmit,
+    NatFilteringBehavior, NatMappingBehavior, NatTest,
+};
+pub use server::{
+    AuthenticationError, CredentialStore, InMemoryCredentialStore, InMemoryNonceSource,
+    LongTermAuthenticator, NonceError, NonceSource, StatelessNonce, StunAuthentication,
+    StunBindingResponder, StunClock, StunResponseCache, StunSecurityFeatures, StunSystemClock,
+};
+pub use transaction::{
+    LongTermCredentialClient, LongTermCredentialError, StunClientTransaction, StunCorrelatedEvent,
+    StunRtoEstimator, StunTransactionCorrelator, StunTransactionEvent, StunTransport,
+};
+pub use utils::{
+    check_for_channel_data_header, check_for_stun_message_header, generate_transaction_id,
 };
-pub use message::{MessageDecodeError, MessageEncodeError, StunMessage};
-pub use utils::{check_for_stun_message_header, generate_transaction_id};
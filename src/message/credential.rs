@@ -0,0 +1,8 @@
+/// Secret used for MessageIntegrity computation and verification
+#[derive(Debug, Copy, Clone)]
+pub(crate) enum IntegrityCredential<'a> {
+    /// Password the key is derived from, based on the REALM, USERNAME and PASSWORD-ALGORITHM attributes of the message
+    Password(&'a str),
+    /// Precomputed HMAC key
+    Key(&'a [u8]),
+}
@@ -1,11 +1,30 @@
+use crypto::digest::Digest;
+use crypto::sha1::Sha1;
+use crypto::sha2::Sha256;
+use crypto::util::fixed_time_eq;
 use std::io::Cursor;
+use std::time::SystemTime;
 
-use crate::definitions::{STUN_FINGERPRINT_ATTR_SIZE, STUN_INTEGRITY_ATTR_SIZE};
+use crate::access_token::{AccessToken, AccessTokenAlgorithm, AccessTokenError};
+use crate::attribute::{
+    AttributeDecodeError, StunAttribute, StunAttributeRef, StunPasswordAlgorithm,
+};
+use crate::definitions::STUN_FINGERPRINT_ATTR_SIZE;
 use crate::StunHeader;
-use crate::{AttributeDecodeError, StunAttribute};
 
-pub use super::errors::{IntegrityKeyGenerationError, MessageDecodeError, MessageEncodeError};
+use super::credential::IntegrityCredential;
+use super::errors::MessageDecodeError;
 use super::message::StunMessage;
+use super::message_ref::StunMessageRef;
+
+/// Decoding options relaxing the checks done on received messages
+#[derive(Debug, Copy, Clone, Default)]
+pub(crate) struct DecodeOptions {
+    /// Accept classic RFC3489 messages that don't carry the magic cookie
+    pub allow_legacy: bool,
+    /// Keep unknown comprehension-required attributes instead of failing
+    pub allow_unknown_required: bool,
+}
 
 impl StunMessage {
     /// Decodes and returns the STUN message
@@ -17,41 +36,470 @@ impl StunMessage {
     pub fn decode(
         bytes: &[u8],
         integrity_password: Option<&str>,
+    ) -> Result<Self, MessageDecodeError> {
+        Ok(StunMessageRef::decode(bytes, integrity_password)?.into_owned())
+    }
+
+    /// Decodes and returns the STUN message, verifying the message integrity with a key returned by the lookup function
+    ///
+    /// See [`StunMessageRef::decode_with_key_lookup`].
+    ///
+    /// Arguments:
+    ///
+    /// * `bytes`: binary encoded message to decode from
+    /// * `key_lookup`: Returns the HMAC key for the decoded message
+    pub fn decode_with_key_lookup<F>(
+        bytes: &[u8],
+        key_lookup: F,
+    ) -> Result<Self, MessageDecodeError>
+    where
+        F: FnOnce(&StunMessageRef) -> Option<Vec<u8>>,
+    {
+        Ok(StunMessageRef::decode_with_key_lookup(bytes, key_lookup)?.into_owned())
+    }
+
+    /// Decodes and returns the STUN message, verifying the message integrity with a precomputed HMAC key
+    ///
+    /// Arguments:
+    ///
+    /// * `bytes`: binary encoded message to decode from
+    /// * `integrity_key`: Optionally set HMAC key that will be used for message integrity verification
+    pub fn decode_with_key(
+        bytes: &[u8],
+        integrity_key: Option<&[u8]>,
+    ) -> Result<Self, MessageDecodeError> {
+        Ok(StunMessageRef::decode_with_key(bytes, integrity_key)?.into_owned())
+    }
+
+    /// Decodes and returns the STUN message, verifying the message integrity with the MAC key of its access token
+    ///
+    /// See [`StunMessageRef::decode_with_access_token`].
+    ///
+    /// Arguments:
+    ///
+    /// * `bytes`: binary encoded message to decode from
+    /// * `server_name`: STUN server name the token has been issued for
+    /// * `now`: Current time, used to reject expired tokens
+    /// * `key_lookup`: Returns the AEAD algorithm and the long-term key identified by the key id
+    pub fn decode_with_access_token<F>(
+        bytes: &[u8],
+        server_name: &str,
+        now: SystemTime,
+        key_lookup: F,
+    ) -> Result<(Self, AccessToken), MessageDecodeError>
+    where
+        F: FnOnce(&str) -> Option<(AccessTokenAlgorithm, Vec<u8>)>,
+    {
+        let (message, token) =
+            StunMessageRef::decode_with_access_token(bytes, server_name, now, key_lookup)?;
+
+        Ok((message.into_owned(), token))
+    }
+
+    /// Decodes and returns the STUN message, accepting classic [RFC3489](https://tools.ietf.org/html/rfc3489) messages as well
+    ///
+    /// See [`StunMessageRef::decode_legacy`].
+    ///
+    /// Arguments:
+    ///
+    /// * `bytes`: binary encoded message to decode from
+    /// * `integrity_password`: Optionally set key that will be used for message integrity verification
+    pub fn decode_legacy(
+        bytes: &[u8],
+        integrity_password: Option<&str>,
+    ) -> Result<Self, MessageDecodeError> {
+        Ok(StunMessageRef::decode_legacy(bytes, integrity_password)?.into_owned())
+    }
+
+    /// Decodes and returns the STUN message, keeping unknown comprehension-required attributes
+    ///
+    /// See [`StunMessageRef::decode_lenient`].
+    ///
+    /// Arguments:
+    ///
+    /// * `bytes`: binary encoded message to decode from
+    /// * `integrity_password`: Optionally set key that will be used for message integrity verification
+    pub fn decode_lenient(
+        bytes: &[u8],
+        integrity_password: Option<&str>,
+    ) -> Result<Self, MessageDecodeError> {
+        Ok(StunMessageRef::decode_lenient(bytes, integrity_password)?.into_owned())
+    }
+}
+
+impl<'a> StunMessageRef<'a> {
+    /// Decodes and returns a borrowed view of the STUN message
+    ///
+    /// Variable-length attribute values refer to the provided `bytes` instead of being copied.
+    ///
+    /// Arguments:
+    ///
+    /// * `bytes`: binary encoded message to decode from
+    /// * `integrity_password`: Optionally set key that will be used for message integrity verification
+    pub fn decode(
+        bytes: &'a [u8],
+        integrity_password: Option<&str>,
+    ) -> Result<Self, MessageDecodeError> {
+        Self::decode_with_credential(
+            bytes,
+            integrity_password.map(IntegrityCredential::Password),
+            DecodeOptions::default(),
+        )
+    }
+
+    /// Decodes and returns a borrowed view of the STUN message, accepting classic [RFC3489](https://tools.ietf.org/html/rfc3489) messages as well
+    ///
+    /// Classic messages don't carry the magic cookie and use a 128-bit transaction ID instead.
+    /// The dialect the message has been encoded with is reported by the `dialect` field of the header.
+    /// As any 20-byte sequence starting with two zero bits passes as a classic header,
+    /// this mode is not suitable for separating STUN packets from other multiplexed protocols.
+    ///
+    /// Arguments:
+    ///
+    /// * `bytes`: binary encoded message to decode from
+    /// * `integrity_password`: Optionally set key that will be used for message integrity verification
+    ///
+    /// ```
+    /// # use stun_coder::{StunDialect, StunMessageRef};
+    /// // Classic binding request without attributes
+    /// let msg_bytes: [u8; 20] = [
+    ///     0x00, 0x01, 0x00, 0x00, 0xb7, 0xe7, 0xa7, 0x01, 0xbc, 0x34, 0xd6, 0x86, 0xfa, 0x87,
+    ///     0xdf, 0xae, 0x01, 0x02, 0x03, 0x04,
+    /// ];
+    ///
+    /// let decoded_msg = StunMessageRef::decode_legacy(&msg_bytes, None).unwrap();
+    ///
+    /// assert_eq!(
+    ///     decoded_msg.get_header().dialect,
+    ///     StunDialect::Rfc3489 {
+    ///         transaction_id_prefix: [0xb7, 0xe7, 0xa7, 0x01]
+    ///     }
+    /// );
+    /// ```
+    pub fn decode_legacy(
+        bytes: &'a [u8],
+        integrity_password: Option<&str>,
+    ) -> Result<Self, MessageDecodeError> {
+        Self::decode_with_credential(
+            bytes,
+            integrity_password.map(IntegrityCredential::Password),
+            DecodeOptions {
+                allow_legacy: true,
+                ..DecodeOptions::default()
+            },
+        )
+    }
+
+    /// Decodes and returns a borrowed view of the STUN message, keeping unknown comprehension-required attributes
+    ///
+    /// Instead of failing the decoding, attributes of unknown types in the 0x0000-0x7FFF range are returned as `Unknown` attributes.
+    /// This allows a server to answer such requests with a 420 (Unknown Attribute) error response
+    /// listing all of them, as per [RFC5389 Section 7.3.1](https://tools.ietf.org/html/rfc5389#section-7.3.1).
+    ///
+    /// Arguments:
+    ///
+    /// * `bytes`: binary encoded message to decode from
+    /// * `integrity_password`: Optionally set key that will be used for message integrity verification
+    ///
+    /// ```
+    /// # use stun_coder::{StunAttribute, StunMessage, StunMessageRef};
+    /// let request = StunMessage::create_request()
+    ///     .add_attribute(StunAttribute::Unknown {
+    ///         attr_type: 0x7F00,
+    ///         value: vec![1, 2, 3, 4],
+    ///     })
+    ///     .encode(None)
+    ///     .unwrap();
+    ///
+    /// assert!(StunMessageRef::decode(&request, None).is_err());
+    ///
+    /// let decoded_msg = StunMessageRef::decode_lenient(&request, None).unwrap();
+    /// assert_eq!(decoded_msg.get_unknown_required_attributes(), vec![0x7F00]);
+    /// ```
+    pub fn decode_lenient(
+        bytes: &'a [u8],
+        integrity_password: Option<&str>,
+    ) -> Result<Self, MessageDecodeError> {
+        Self::decode_with_credential(
+            bytes,
+            integrity_password.map(IntegrityCredential::Password),
+            DecodeOptions {
+                allow_unknown_required: true,
+                ..DecodeOptions::default()
+            },
+        )
+    }
+
+    /// Decodes and returns a borrowed view of the STUN message, verifying the message integrity with a precomputed HMAC key
+    ///
+    /// Arguments:
+    ///
+    /// * `bytes`: binary encoded message to decode from
+    /// * `integrity_key`: Optionally set HMAC key that will be used for message integrity verification
+    pub fn decode_with_key(
+        bytes: &'a [u8],
+        integrity_key: Option<&[u8]>,
+    ) -> Result<Self, MessageDecodeError> {
+        Self::decode_with_credential(
+            bytes,
+            integrity_key.map(IntegrityCredential::Key),
+            DecodeOptions::default(),
+        )
+    }
+
+    /// Decodes and returns a borrowed view of the STUN message, verifying the message integrity with a key returned by the lookup function
+    ///
+    /// The lookup function receives the decoded, but not yet verified, message and returns the HMAC key for it.
+    /// This allows finding the credential of messages that carry the USERHASH attribute instead of USERNAME.
+    /// The lookup function is only called if the message contains an integrity attribute.
+    ///
+    /// Arguments:
+    ///
+    /// * `bytes`: binary encoded message to decode from
+    /// * `key_lookup`: Returns the HMAC key for the decoded message, or `None` if no credential has been found
+    ///
+    /// ```
+    /// # use stun_coder::{StunMessage, StunMessageRef, StunPasswordAlgorithm};
+    /// let key = StunMessage::calculate_long_term_key(
+    ///     "user",
+    ///     "example.org",
+    ///     "pass",
+    ///     StunPasswordAlgorithm::Md5,
+    /// )
+    /// .unwrap();
+    ///
+    /// let request = StunMessage::create_request()
+    ///     .add_userhash("user", "example.org")
+    ///     .unwrap()
+    ///     .add_attribute(stun_coder::StunAttribute::Realm {
+    ///         value: String::from("example.org"),
+    ///     })
+    ///     .add_message_integrity()
+    ///     .encode_with_key(Some(&key))
+    ///     .unwrap();
+    ///
+    /// let known_userhash = StunMessage::calculate_userhash("user", "example.org").unwrap();
+    ///
+    /// let decoded = StunMessageRef::decode_with_key_lookup(&request, |msg| {
+    ///     if msg.get_userhash() == Some(known_userhash) {
+    ///         Some(key.clone())
+    ///     } else {
+    ///         None
+    ///     }
+    /// })
+    /// .unwrap();
+    /// ```
+    pub fn decode_with_key_lookup<F>(
+        bytes: &'a [u8],
+        key_lookup: F,
+    ) -> Result<Self, MessageDecodeError>
+    where
+        F: FnOnce(&StunMessageRef) -> Option<Vec<u8>>,
+    {
+        let message = Self::decode_with_credential(bytes, None, DecodeOptions::default())?;
+
+        if !message.has_integrity() {
+            return Ok(message);
+        }
+
+        let integrity_key = key_lookup(&message).ok_or(MessageDecodeError::CredentialNotFound())?;
+
+        // Decode again, verifying the integrity attributes this time
+        Self::decode_with_credential(
+            bytes,
+            Some(IntegrityCredential::Key(&integrity_key)),
+            DecodeOptions::default(),
+        )
+    }
+
+    /// Decodes and returns a borrowed view of the STUN message, verifying the message integrity with the MAC key of its access token
+    ///
+    /// As per [RFC7635 Section 4.1](https://tools.ietf.org/html/rfc7635#section-4.1) the USERNAME attribute carries the key id
+    /// of the long-term key that protects the self-contained token conveyed in the ACCESS-TOKEN attribute.
+    /// The MAC key of the decrypted token is used as the integrity key instead of a key derived from a password.
+    /// Messages without an integrity attribute are rejected, as well as expired tokens and tokens whose MAC key
+    /// is neither 20 bytes (HMAC-SHA1) nor 32 bytes (HMAC-SHA256) long, as per [RFC7635 Section 6.2](https://tools.ietf.org/html/rfc7635#section-6.2).
+    ///
+    /// Arguments:
+    ///
+    /// * `bytes`: binary encoded message to decode from
+    /// * `server_name`: STUN server name the token has been issued for
+    /// * `now`: Current time, used to reject expired tokens
+    /// * `key_lookup`: Returns the AEAD algorithm and the long-term key identified by the key id, or `None` if the key id is unknown
+    pub fn decode_with_access_token<F>(
+        bytes: &'a [u8],
+        server_name: &str,
+        now: SystemTime,
+        key_lookup: F,
+    ) -> Result<(Self, AccessToken), MessageDecodeError>
+    where
+        F: FnOnce(&str) -> Option<(AccessTokenAlgorithm, Vec<u8>)>,
+    {
+        let message = Self::decode_with_credential(bytes, None, DecodeOptions::default())?;
+
+        let (key_id, encrypted_token) = match (message.get_username(), message.get_access_token()) {
+            (Some(key_id), Some(encrypted_token)) => (key_id, encrypted_token),
+            _ => return Err(MessageDecodeError::MissingAccessToken()),
+        };
+
+        // The token alone doesn't authenticate the message, as it can be replayed
+        if !message.has_integrity() {
+            return Err(MessageDecodeError::MissingMessageIntegrity());
+        }
+
+        let (algorithm, key) = key_lookup(key_id).ok_or(MessageDecodeError::CredentialNotFound())?;
+        let token = AccessToken::decrypt(encrypted_token, algorithm, &key, server_name)?;
+
+        if token.mac_key.len() != 20 && token.mac_key.len() != 32 {
+            return Err(AccessTokenError::InvalidMacKeyLength(token.mac_key.len()).into());
+        }
+
+        if token.is_expired(now) {
+            return Err(AccessTokenError::Expired().into());
+        }
+
+        // Decode again, verifying the integrity attributes this time
+        let message = Self::decode_with_credential(
+            bytes,
+            Some(IntegrityCredential::Key(&token.mac_key)),
+            DecodeOptions::default(),
+        )?;
+
+        Ok((message, token))
+    }
+
+    // Checks whether the message contains a MessageIntegrity or MessageIntegritySha256 attribute
+    fn has_integrity(&self) -> bool {
+        self.attributes.iter().any(|attr| {
+            matches!(
+                attr,
+                StunAttributeRef::MessageIntegrity { .. }
+                    | StunAttributeRef::MessageIntegritySha256 { .. }
+            )
+        })
+    }
+
+    /// Verifies the value of a MessageIntegrity/MessageIntegritySha256 attribute
+    ///
+    /// Arguments:
+    ///
+    /// * `digest`: Hash function used for HMAC
+    /// * `integrity_key`: HMAC key
+    /// * `bytes`: Encoded message
+    /// * `attr_end`: Position right after the integrity attribute
+    /// * `attr_value`: Integrity attribute value
+    /// * `allow_truncation`: Whether the value may be a truncated HMAC. Only allowed for MessageIntegritySha256.
+    fn verify_integrity<D: Digest>(
+        digest: D,
+        integrity_key: &[u8],
+        bytes: &[u8],
+        attr_end: usize,
+        attr_value: &[u8],
+        allow_truncation: bool,
+    ) -> Result<(), MessageDecodeError> {
+        let attr_size = 4 + ((attr_value.len() + 3) & !3);
+        let attr_start = attr_end - attr_size;
+
+        let hmac = StunMessage::calculate_integrity_hash(
+            digest,
+            integrity_key,
+            &bytes[0..attr_start],
+            attr_size,
+        );
+
+        let expected_len = if allow_truncation {
+            attr_value.len()
+        } else {
+            hmac.len()
+        };
+
+        // Verify message integrity in constant time, so that the comparison doesn't leak how many bytes match.
+        // Empty values are rejected upfront, as `fixed_time_eq` doesn't support them.
+        let is_valid = !attr_value.is_empty()
+            && attr_value.len() == expected_len
+            && matches!(hmac.get(..expected_len), Some(computed) if fixed_time_eq(computed, attr_value));
+        if !is_valid {
+            return Err(MessageDecodeError::MessageIntegrityFail {
+                attr_value: attr_value.to_vec(),
+                computed_value: hmac,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Decodes the message and verifies its integrity attributes if a credential is provided
+    ///
+    /// As per [RFC8489 Section 14.6](https://tools.ietf.org/html/rfc8489#section-14.6) all attributes following the
+    /// MessageIntegrity attribute are ignored, except for the MessageIntegritySha256 and Fingerprint attributes.
+    /// All attributes following the MessageIntegritySha256 attribute are ignored, except for the Fingerprint attribute.
+    /// Classic RFC3489 messages and unknown comprehension-required attributes are only accepted if the corresponding options are set.
+    pub(crate) fn decode_with_credential(
+        bytes: &'a [u8],
+        credential: Option<IntegrityCredential>,
+        options: DecodeOptions,
     ) -> Result<Self, MessageDecodeError> {
         let mut cursor = Cursor::new(bytes);
 
         // Decode header
-        let header = StunHeader::decode(&mut cursor)?;
+        let header = StunHeader::decode(&mut cursor, options.allow_legacy)?;
         // Decode attributes
         let mut attributes = Vec::new();
 
         let mut integrity_attr_passed = false;
+        let mut integrity_sha256_attr_passed = false;
 
-        // Track for username/realm occurrences
+        // Track for username/realm/password algorithm occurrences
         let mut username = None;
         let mut realm = None;
+        let mut password_algorithm = StunPasswordAlgorithm::default();
 
         // Message length does not include the 20-byte header.
         while cursor.position() < 20 + header.message_len as u64 {
-            let decoded = StunAttribute::decode(&mut cursor, header.transaction_id);
+            let decoded = StunAttributeRef::decode(&mut cursor, header.transaction_id);
 
             match decoded {
+                // Attributes with type values between 0x8000 and 0xFFFF are
+                // comprehension-optional attributes, which means that those attributes
+                // can be ignored by the STUN agent if it does not understand them.
+                // Only return an error when the attribute is comprehension-required
+                Ok(StunAttributeRef::Unknown { attr_type, .. })
+                    if attr_type < 0x8000 && !options.allow_unknown_required =>
+                {
+                    return Err(MessageDecodeError::AttributeDecodeFailure {
+                        source: AttributeDecodeError::UnrecognizedAttributeType { attr_type },
+                        transaction_id: header.transaction_id,
+                    });
+                }
                 Ok(decoded) => {
-                    // Ignore all attributes after the MESSAGE-INTEGRITY attribute.
-                    // As per [RFC5389 Section 15.4](https://tools.ietf.org/html/rfc5389#section-15.4)
-                    if !integrity_attr_passed {
-                        attributes.push(decoded.clone());
+                    // Ignore attributes that are not covered by the integrity attributes
+                    let is_accepted = match decoded {
+                        StunAttributeRef::Other(StunAttribute::Fingerprint { .. }) => true,
+                        StunAttributeRef::MessageIntegritySha256 { .. } => {
+                            !integrity_sha256_attr_passed
+                        }
+                        _ => !integrity_attr_passed && !integrity_sha256_attr_passed,
+                    };
+
+                    if !is_accepted {
+                        continue;
                     }
 
-                    // Handle Fingerprint and MessageIntegrity attributes
+                    // Handle Fingerprint and integrity attributes
                     match decoded {
-                        StunAttribute::Username { value } => {
+                        StunAttributeRef::Username { value } => {
                             username = Some(value);
                         }
-                        StunAttribute::Realm { value } => {
+                        StunAttributeRef::Realm { value } => {
                             realm = Some(value);
                         }
-                        StunAttribute::Fingerprint { value } => {
+                        StunAttributeRef::Other(StunAttribute::PasswordAlgorithm {
+                            algorithm,
+                            ..
+                        }) => {
+                            password_algorithm = algorithm;
+                        }
+                        StunAttributeRef::Other(StunAttribute::Fingerprint { value }) => {
                             let attr_pos = cursor.position() as usize - STUN_FINGERPRINT_ATTR_SIZE;
 
                             // Make sure the Fingerprint attribute is the last one
@@ -59,14 +507,14 @@ impl StunMessage {
                                 return Err(
                                     MessageDecodeError::IncorrectFingerprintAttributePosition {
                                         msg_len: bytes.len(),
-                                        attr_pos: attr_pos as usize,
+                                        attr_pos,
                                     },
                                 );
                             }
 
                             // Compute fingerprint for verification
                             let computed_fingerprint =
-                                Self::calculate_fingerprint(&cursor.get_ref()[0..attr_pos]);
+                                StunMessage::calculate_fingerprint(&bytes[0..attr_pos]);
 
                             // Make sure the fingerprint matches
                             if computed_fingerprint != value {
@@ -75,58 +523,62 @@ impl StunMessage {
                                     computed_value: computed_fingerprint,
                                 });
                             }
-
-                            if integrity_attr_passed {
-                                // Push the attribute to the list explicitly since it's after the MessageIntegrity attribute
-                                attributes.push(decoded);
-                            }
                         }
-                        StunAttribute::MessageIntegrity { key } => {
-                            // Mark MessageIntegrity attribute as passed so we can ignore attributes that happen after it
-                            // With the exception of the Fingerprint attribute
+                        StunAttributeRef::MessageIntegrity { key } => {
                             integrity_attr_passed = true;
 
-                            // If an `integrity_password` has been supplied, recalculate and verify the HMAC value
-                            if let Some(integrity_password) = integrity_password {
-                                let integrity_key = Self::calculate_integrity_key(
-                                    integrity_password,
-                                    realm.clone(),
-                                    username.clone(),
+                            // If a credential has been supplied, recalculate and verify the HMAC value
+                            if let Some(credential) = credential {
+                                let integrity_key = StunMessage::resolve_integrity_key(
+                                    credential,
+                                    realm,
+                                    username,
+                                    password_algorithm,
                                 )?;
 
-                                let hmac = Self::calculate_integrity_hash(
+                                Self::verify_integrity(
+                                    Sha1::new(),
                                     &integrity_key,
-                                    &cursor.get_ref()[0..(cursor.position() as usize
-                                        - STUN_INTEGRITY_ATTR_SIZE)
-                                        as usize],
-                                );
+                                    bytes,
+                                    cursor.position() as usize,
+                                    key,
+                                    false,
+                                )?;
+                            }
+                        }
+                        StunAttributeRef::MessageIntegritySha256 { key } => {
+                            integrity_sha256_attr_passed = true;
 
-                                // Verify message integrity
-                                if hmac != key {
-                                    return Err(MessageDecodeError::MessageIntegrityFail {
-                                        attr_value: key,
-                                        computed_value: hmac,
-                                    });
-                                }
+                            // If a credential has been supplied, recalculate and verify the HMAC value
+                            if let Some(credential) = credential {
+                                let integrity_key = StunMessage::resolve_integrity_key(
+                                    credential,
+                                    realm,
+                                    username,
+                                    password_algorithm,
+                                )?;
+
+                                Self::verify_integrity(
+                                    Sha256::new(),
+                                    &integrity_key,
+                                    bytes,
+                                    cursor.position() as usize,
+                                    key,
+                                    true,
+                                )?;
                             }
                         }
                         _ => {}
                     };
+
+                    attributes.push(decoded);
                 }
+                // Return an error on any attribute decoding error
                 Err(err) => {
-                    match err {
-                        AttributeDecodeError::UnrecognizedAttributeType { attr_type } => {
-                            // Attributes with type values between 0x8000 and 0xFFFF are
-                            // comprehension-optional attributes, which means that those attributes
-                            // can be ignored by the STUN agent if it does not understand them.
-                            // Only return an error when the attribute is comprehension-required
-                            if attr_type <= 0x8000 {
-                                return Err(MessageDecodeError::AttributeDecodeFailure{source: err, transaction_id: header.transaction_id});
-                            }
-                        }
-                        // Return an error on any other attribute decoding error
-                        _ => return Err(MessageDecodeError::AttributeDecodeFailure{source: err, transaction_id: header.transaction_id}),
-                    }
+                    return Err(MessageDecodeError::AttributeDecodeFailure {
+                        source: err,
+                        transaction_id: header.transaction_id,
+                    })
                 }
             }
         }
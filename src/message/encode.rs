@@ -1,9 +1,71 @@
-use std::io::{Cursor, Write};
+use bytes::BufMut;
+use crc::{crc32, Hasher32};
+use crypto::hmac::Hmac;
+use crypto::mac::Mac;
+use crypto::sha1::Sha1;
+use crypto::sha2::Sha256;
+use std::io::{self, Write};
 
-pub use super::errors::{IntegrityKeyGenerationError, MessageDecodeError, MessageEncodeError};
+use super::credential::IntegrityCredential;
+use super::errors::MessageEncodeError;
 use super::message::StunMessage;
 
-use crate::attribute::StunAttribute;
+use crate::attribute::{StunAttribute, StunPasswordAlgorithm};
+use crate::definitions::{
+    STUN_HEADER_SIZE, STUN_INTEGRITY_ATTR_SIZE, STUN_INTEGRITY_SHA256_ATTR_SIZE,
+};
+use crate::header::StunDialect;
+
+/// Message layout collected before any bytes are written
+struct EncodingPlan<'a> {
+    /// Message length value set in the header
+    message_len: u16,
+    /// Message length value used for HMAC computation, if a placeholder MessageIntegrity attribute is present
+    integrity_message_len: Option<u16>,
+    /// Message length value used for HMAC-SHA256 computation along with the HMAC length to keep,
+    /// if a placeholder MessageIntegritySha256 attribute is present
+    integrity_sha256: Option<(u16, usize)>,
+    /// Whether a placeholder Fingerprint attribute is present
+    compute_fingerprint: bool,
+    /// Username preceding the MessageIntegrity attribute
+    username: Option<&'a str>,
+    /// Realm preceding the MessageIntegrity attribute
+    realm: Option<&'a str>,
+    /// Algorithm requested via the PasswordAlgorithm attribute
+    password_algorithm: StunPasswordAlgorithm,
+}
+
+/// Forwards the written bytes to the underlying writer while feeding the running HMAC and CRC32 computations
+struct ChecksumWriter<'w, W: Write> {
+    inner: &'w mut W,
+    hmac: Option<Hmac<Sha1>>,
+    hmac_sha256: Option<Hmac<Sha256>>,
+    crc: Option<crc32::Digest>,
+}
+
+impl<'w, W: Write> Write for ChecksumWriter<'w, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+
+        if let Some(hmac) = &mut self.hmac {
+            hmac.input(&buf[..written]);
+        }
+
+        if let Some(hmac) = &mut self.hmac_sha256 {
+            hmac.input(&buf[..written]);
+        }
+
+        if let Some(crc) = &mut self.crc {
+            crc.write(&buf[..written]);
+        }
+
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
 
 impl StunMessage {
     /// Encodes the STUN message into a binary representation
@@ -12,36 +74,149 @@ impl StunMessage {
     ///
     /// * `integrity_password`: Optionally set key that will be used for message integrity generation. Required if a MessageIntegrity attribute is present.
     pub fn encode(&self, integrity_password: Option<&str>) -> Result<Vec<u8>, MessageEncodeError> {
-        let attr_count = self.attributes.len();
-        let mut cursor = Cursor::new(Vec::new());
+        let plan = self.plan_encoding()?;
 
-        // Encode and write the header
-        let encoded_header = &self.header.encode()?;
-        cursor.write_all(encoded_header)?;
+        let mut buf = Vec::with_capacity(STUN_HEADER_SIZE + plan.message_len as usize);
+        self.encode_with_plan(
+            &mut buf,
+            &plan,
+            integrity_password.map(IntegrityCredential::Password),
+        )?;
 
-        // Mark that a message integrity attribute is present
-        let mut msg_integrity_present = false;
+        Ok(buf)
+    }
 
-        // Track for username/realm occurrences
-        let mut username = None;
-        let mut realm = None;
+    /// Encodes the STUN message using a precomputed HMAC key
+    ///
+    /// Useful when the key can't be derived from the message attributes, e.g. for responses
+    /// to requests that selected the SHA-256 password algorithm. See [`calculate_long_term_key`](StunMessage::calculate_long_term_key).
+    ///
+    /// Arguments:
+    ///
+    /// * `integrity_key`: Optionally set HMAC key. Required if a placeholder MessageIntegrity or MessageIntegritySha256 attribute is present.
+    pub fn encode_with_key(
+        &self,
+        integrity_key: Option<&[u8]>,
+    ) -> Result<Vec<u8>, MessageEncodeError> {
+        let plan = self.plan_encoding()?;
 
-        // Process each attribute and encode it
-        for (idx, attr) in self.attributes.clone().iter().enumerate() {
-            let processed_attr = match attr {
-                // Track the username attribute being set
-                StunAttribute::Username { value } => {
-                    username = Some(value.clone());
+        let mut buf = Vec::with_capacity(STUN_HEADER_SIZE + plan.message_len as usize);
+        self.encode_with_plan(&mut buf, &plan, integrity_key.map(IntegrityCredential::Key))?;
 
-                    attr.clone()
-                }
-                // Track the realm attribute being set
-                StunAttribute::Realm { value } => {
-                    realm = Some(value.clone());
+        Ok(buf)
+    }
 
-                    attr.clone()
-                }
-                // Process the Fingerprint attribute
+    /// Encodes the STUN message into the provided buffer and returns the amount of bytes written
+    ///
+    /// The message length, HMAC and fingerprint values are computed in place, without intermediate allocations.
+    /// Use [`encoded_len`](StunMessage::encoded_len) to size the buffer up front.
+    ///
+    /// Arguments:
+    ///
+    /// * `buf`: Output buffer. Must be at least `encoded_len()` bytes long.
+    /// * `integrity_password`: Optionally set key that will be used for message integrity generation. Required if a MessageIntegrity attribute is present.
+    ///
+    /// ```
+    /// # use stun_coder::{StunAttribute, StunMessage};
+    /// let message = StunMessage::create_request()
+    ///     .add_attribute(StunAttribute::Software {
+    ///         description: String::from("rust-stun-coder"),
+    ///     })
+    ///     .add_fingerprint();
+    ///
+    /// let mut send_buf = [0u8; 1500];
+    /// let len = message.encode_into(&mut send_buf, None).unwrap();
+    ///
+    /// assert_eq!(len, message.encoded_len().unwrap());
+    /// assert_eq!(&send_buf[..len], &message.encode(None).unwrap()[..]);
+    /// ```
+    pub fn encode_into(
+        &self,
+        buf: &mut [u8],
+        integrity_password: Option<&str>,
+    ) -> Result<usize, MessageEncodeError> {
+        let plan = self.plan_encoding()?;
+        let encoded_len = STUN_HEADER_SIZE + plan.message_len as usize;
+
+        if buf.len() < encoded_len {
+            return Err(MessageEncodeError::BufferTooSmall {
+                required: encoded_len,
+                available: buf.len(),
+            });
+        }
+
+        self.encode_with_plan(
+            &mut &mut buf[..encoded_len],
+            &plan,
+            integrity_password.map(IntegrityCredential::Password),
+        )?;
+
+        Ok(encoded_len)
+    }
+
+    /// Appends the encoded STUN message to the provided [`BufMut`](bytes::BufMut) and returns the amount of bytes written
+    ///
+    /// Arguments:
+    ///
+    /// * `buf`: Output buffer. Must have at least `encoded_len()` bytes of remaining capacity.
+    /// * `integrity_password`: Optionally set key that will be used for message integrity generation. Required if a MessageIntegrity attribute is present.
+    pub fn encode_to<B: BufMut>(
+        &self,
+        buf: &mut B,
+        integrity_password: Option<&str>,
+    ) -> Result<usize, MessageEncodeError> {
+        let plan = self.plan_encoding()?;
+        let encoded_len = STUN_HEADER_SIZE + plan.message_len as usize;
+
+        if buf.remaining_mut() < encoded_len {
+            return Err(MessageEncodeError::BufferTooSmall {
+                required: encoded_len,
+                available: buf.remaining_mut(),
+            });
+        }
+
+        self.encode_with_plan(
+            &mut buf.writer(),
+            &plan,
+            integrity_password.map(IntegrityCredential::Password),
+        )?;
+
+        Ok(encoded_len)
+    }
+
+    /// Returns the size of the encoded message in bytes, including the header
+    pub fn encoded_len(&self) -> Result<usize, MessageEncodeError> {
+        let plan = self.plan_encoding()?;
+
+        Ok(STUN_HEADER_SIZE + plan.message_len as usize)
+    }
+
+    /// Validates the attribute order and computes the message layout
+    ///
+    /// As per [RFC8489 Section 14.6](https://tools.ietf.org/html/rfc8489#section-14.6) the MessageIntegritySha256 attribute
+    /// can only follow the MessageIntegrity attribute, and only the Fingerprint attribute can follow either of them.
+    fn plan_encoding(&self) -> Result<EncodingPlan<'_>, MessageEncodeError> {
+        let attr_count = self.attributes.len();
+        let transaction_id = self.header.transaction_id;
+
+        let mut plan = EncodingPlan {
+            message_len: 0,
+            integrity_message_len: None,
+            integrity_sha256: None,
+            compute_fingerprint: false,
+            username: None,
+            realm: None,
+            password_algorithm: StunPasswordAlgorithm::default(),
+        };
+
+        // Total size of the encoded attributes
+        let mut attrs_len = 0;
+        // Mark that the message integrity attributes are present
+        let mut msg_integrity_present = false;
+        let mut msg_integrity_sha256_present = false;
+
+        for (idx, attr) in self.attributes.iter().enumerate() {
+            match attr {
                 StunAttribute::Fingerprint { value } => {
                     // Make sure that the Fingerprint attribute is the last one
                     if attr_count - 1 != idx {
@@ -50,67 +225,188 @@ impl StunMessage {
                             fingerprint_attr_idx: idx,
                         });
                     }
-                    // Check if it contains a placeholder value and replace it with the computed fingerprint
-                    if *value == 0 {
-                        // Update the encoded message length so the correct fingerprint can be calculated
-                        self.set_message_length(cursor.get_mut(), 8);
 
-                        // Update the fingerprint value
-                        let fingerprint = Self::calculate_fingerprint(cursor.get_ref());
+                    // A placeholder value is replaced with the computed fingerprint
+                    plan.compute_fingerprint = *value == 0;
+                }
+                StunAttribute::MessageIntegritySha256 { .. } if msg_integrity_sha256_present => {
+                    return Err(MessageEncodeError::AttributeAfterIntegrity());
+                }
+                StunAttribute::MessageIntegritySha256 { key } => {
+                    msg_integrity_sha256_present = true;
+
+                    // Placeholder data is replaced with the calculated HMAC value
+                    if key.iter().all(|byte| *byte == 0) {
+                        let (attr_len, hash_len) = if key.is_empty() {
+                            (STUN_INTEGRITY_SHA256_ATTR_SIZE, 32)
+                        } else {
+                            (attr.encoded_len(transaction_id)?, key.len())
+                        };
+
+                        attrs_len += attr_len;
+                        plan.integrity_sha256 =
+                            Some((Self::checked_message_len(attrs_len)?, hash_len));
 
-                        StunAttribute::Fingerprint { value: fingerprint }
-                    } else {
-                        attr.clone()
+                        continue;
                     }
                 }
-                // Process the MessageIntegrity attribute
+                // If a MessageIntegrity attribute has been already addded,
+                // no other attributes (except MessageIntegritySha256 and Fingerprint) can be added after it
+                _ if msg_integrity_present || msg_integrity_sha256_present => {
+                    return Err(MessageEncodeError::AttributeAfterIntegrity());
+                }
                 StunAttribute::MessageIntegrity { key } => {
-                    // Mark it as present
                     msg_integrity_present = true;
 
-                    // In case of placeholder data, replace it with the calculated HMAC value
+                    // Placeholder data is replaced with the calculated HMAC value
                     if key.is_empty() {
-                        if let Some(integrity_password) = integrity_password {
-                            // Calculate the integrity key
-                            let integrity_key = Self::calculate_integrity_key(
-                                integrity_password,
-                                realm.clone(),
-                                username.clone(),
-                            )?;
-
-                            let hmac =
-                                Self::calculate_integrity_hash(&integrity_key, cursor.get_ref());
-
-                            StunAttribute::MessageIntegrity { key: hmac }
-                        } else {
-                            // Return an error if no `integrity_password` is submitted with placeholder data
-                            return Err(MessageEncodeError::MissingIntegrityPassword());
-                        }
-                    } else {
-                        // Return pre-submitted data
-                        attr.clone()
+                        attrs_len += STUN_INTEGRITY_ATTR_SIZE;
+                        plan.integrity_message_len = Some(Self::checked_message_len(attrs_len)?);
+
+                        continue;
                     }
                 }
-                _ => {
-                    // If a MessageIntegrity attribute has been already addded,
-                    // no other attributes (except Fingerprint) can be added after it
-                    if msg_integrity_present {
-                        return Err(MessageEncodeError::AttributeAfterIntegrity());
+                // Track the attributes used for the integrity key derivation
+                StunAttribute::Username { value } => plan.username = Some(value),
+                StunAttribute::Realm { value } => plan.realm = Some(value),
+                StunAttribute::PasswordAlgorithm { algorithm, .. } => {
+                    plan.password_algorithm = *algorithm
+                }
+                _ => {}
+            }
+
+            attrs_len += attr.encoded_len(transaction_id)?;
+        }
+
+        plan.message_len = Self::checked_message_len(attrs_len)?;
+
+        Ok(plan)
+    }
+
+    // Makes sure the attributes fit into the message length field
+    fn checked_message_len(attrs_len: usize) -> Result<u16, MessageEncodeError> {
+        if attrs_len > u16::MAX as usize {
+            return Err(MessageEncodeError::MessageTooBig(attrs_len));
+        }
+
+        Ok(attrs_len as u16)
+    }
+
+    /// Writes the message according to a precomputed plan
+    fn encode_with_plan<W: Write>(
+        &self,
+        writer: &mut W,
+        plan: &EncodingPlan,
+        credential: Option<IntegrityCredential>,
+    ) -> Result<(), MessageEncodeError> {
+        let transaction_id = self.header.transaction_id;
+        let is_legacy = self.header.dialect != StunDialect::Rfc5389;
+
+        let mut header = self.header;
+        header.message_len = plan.message_len;
+
+        let mut writer = ChecksumWriter {
+            inner: writer,
+            hmac: None,
+            hmac_sha256: None,
+            crc: None,
+        };
+
+        if plan.integrity_message_len.is_some() || plan.integrity_sha256.is_some() {
+            // Return an error if no credential is submitted with placeholder data
+            let credential = credential.ok_or(MessageEncodeError::MissingIntegrityPassword())?;
+            let integrity_key = Self::resolve_integrity_key(
+                credential,
+                plan.realm,
+                plan.username,
+                plan.password_algorithm,
+            )?;
+
+            // The HMAC is computed over a header which message length ends with the integrity attribute
+            let mut header_bytes = [0u8; STUN_HEADER_SIZE];
+            let mut integrity_header = header;
+
+            if let Some(integrity_message_len) = plan.integrity_message_len {
+                integrity_header.message_len = integrity_message_len;
+                integrity_header.encode(&mut &mut header_bytes[..])?;
+
+                let mut hmac = Hmac::new(Sha1::new(), &integrity_key);
+                hmac.input(&header_bytes);
+                writer.hmac = Some(hmac);
+            }
+
+            if let Some((integrity_message_len, _)) = plan.integrity_sha256 {
+                integrity_header.message_len = integrity_message_len;
+                integrity_header.encode(&mut &mut header_bytes[..])?;
+
+                let mut hmac = Hmac::new(Sha256::new(), &integrity_key);
+                hmac.input(&header_bytes);
+                writer.hmac_sha256 = Some(hmac);
+            }
+        }
+
+        // Encode and write the header. Only the fingerprint covers it as is.
+        let (hmac, hmac_sha256) = (writer.hmac.take(), writer.hmac_sha256.take());
+        if plan.compute_fingerprint {
+            writer.crc = Some(crc32::Digest::new(crc32::IEEE));
+        }
+        header.encode(&mut writer)?;
+        writer.hmac = hmac;
+        writer.hmac_sha256 = hmac_sha256;
+
+        // Encode and write each attribute
+        for attr in self.attributes.iter() {
+            match attr {
+                StunAttribute::MessageIntegrity { key } if key.is_empty() => {
+                    if let (Some(mut hmac), Some(integrity_message_len)) =
+                        (writer.hmac.take(), plan.integrity_message_len)
+                    {
+                        if is_legacy {
+                            let hmac_input_len = STUN_HEADER_SIZE + integrity_message_len as usize
+                                - STUN_INTEGRITY_ATTR_SIZE;
+                            let padding_len = Self::legacy_integrity_padding_len(hmac_input_len);
+                            hmac.input(&[0u8; 64][..padding_len]);
+                        }
+
+                        let mut hash = [0u8; 20];
+                        hmac.raw_result(&mut hash);
+
+                        StunAttribute::encode_message_integrity(&mut writer, &hash)?;
                     }
+                }
+                StunAttribute::MessageIntegritySha256 { key } if key.iter().all(|b| *b == 0) => {
+                    if let (Some(mut hmac), Some((integrity_message_len, hash_len))) =
+                        (writer.hmac_sha256.take(), plan.integrity_sha256)
+                    {
+                        if is_legacy {
+                            let hmac_input_len = STUN_HEADER_SIZE + integrity_message_len as usize
+                                - (4 + ((hash_len + 3) & !3));
+                            let padding_len = Self::legacy_integrity_padding_len(hmac_input_len);
+                            hmac.input(&[0u8; 64][..padding_len]);
+                        }
+
+                        let mut hash = [0u8; 32];
+                        hmac.raw_result(&mut hash);
 
-                    attr.clone()
+                        StunAttribute::encode_message_integrity_sha256(
+                            &mut writer,
+                            &hash[..hash_len],
+                        )?;
+                    }
                 }
-            };
+                StunAttribute::Fingerprint { value: 0 } => {
+                    if let Some(crc) = writer.crc.take() {
+                        let fingerprint = StunAttribute::Fingerprint {
+                            value: crc.sum32() ^ 0x5354_554e,
+                        };
 
-            // Encode and write the attribute
-            let encoded_attr = processed_attr.encode(self.header.transaction_id)?;
-            cursor.write_all(&encoded_attr)?;
+                        fingerprint.encode(&mut writer, transaction_id)?;
+                    }
+                }
+                _ => attr.encode(&mut writer, transaction_id)?,
+            }
         }
 
-        // Update the encoded message length
-        self.set_message_length(cursor.get_mut(), 0);
-
-        // Return the encoded data
-        Ok(cursor.get_ref().to_vec())
+        Ok(())
     }
 }
@@ -1,4 +1,7 @@
-use crate::{AttributeDecodeError, AttributeEncodeError, HeaderDecodeError, HeaderEncodeError, definitions::StunTransactionId};
+use crate::{
+    definitions::StunTransactionId, AccessTokenError, AttributeDecodeError, AttributeEncodeError,
+    HeaderDecodeError, HeaderEncodeError,
+};
 use thiserror::Error;
 
 /// Integrity Key Errors
@@ -10,6 +13,9 @@ pub enum IntegrityKeyGenerationError {
     /// Fails key generation due to missing `username`. May happen when a STUN message contains the REALM but not the username attribute
     #[error("No username has been provided for long-term credential key generation")]
     MissingUsername(),
+    /// The password algorithm requested via the PASSWORD-ALGORITHM attribute is not supported
+    #[error("Unsupported password algorithm: {0:#X?}.")]
+    UnsupportedPasswordAlgorithm(u16),
 }
 
 /// Message encoding errors.
@@ -24,11 +30,11 @@ pub enum MessageDecodeError {
     /// Failure to decode a STUN attribute
     #[error("Error decoding STUN attribute.")]
     AttributeDecodeFailure {
+        /// Underlying attribute decoding error.
         #[source]
-        ///
         source: AttributeDecodeError,
         /// STUN transaction id.
-        transaction_id: StunTransactionId
+        transaction_id: StunTransactionId,
     },
     /// Failure to generate an integrity verification key
     #[error("Error decoding STUN attribute.")]
@@ -60,6 +66,18 @@ pub enum MessageDecodeError {
         /// Calculated HMAC
         computed_value: Vec<u8>,
     },
+    /// The key lookup didn't return a key for a message containing integrity attributes.
+    #[error("No integrity key has been found for the message.")]
+    CredentialNotFound(),
+    /// The message doesn't contain the USERNAME and ACCESS-TOKEN attributes required for third-party authorization.
+    #[error("Missing USERNAME or ACCESS-TOKEN attribute.")]
+    MissingAccessToken(),
+    /// The message carries an access token but no MessageIntegrity or MessageIntegritySha256 attribute.
+    #[error("Missing message integrity attribute.")]
+    MissingMessageIntegrity(),
+    /// Failure to decrypt or validate the self-contained token of the ACCESS-TOKEN attribute
+    #[error("Error decrypting or validating the access token.")]
+    AccessTokenDecryptionFailure(#[from] AccessTokenError),
 }
 
 /// Message decoding errors.
@@ -85,10 +103,22 @@ pub enum MessageEncodeError {
         /// Index of the Fingerprint attribute
         fingerprint_attr_idx: usize,
     },
-    /// An attribute was added after the MessageIntegrity attribute. Only a single Fingerprint attribute can be added after it.
-    #[error("An attribute was added after the MessageIntegrity attribute. Only the Fingerprint attribute can be placed after the MessageIntegrity attribute.")]
+    /// An attribute was added after the MessageIntegrity attribute. Only a single MessageIntegritySha256 and a single Fingerprint attribute can be added after it.
+    /// Only the Fingerprint attribute can be added after the MessageIntegritySha256 attribute.
+    #[error("An attribute was added after the MessageIntegrity attribute. Only the MessageIntegritySha256 and Fingerprint attributes can be placed after the MessageIntegrity attribute.")]
     AttributeAfterIntegrity(),
     /// A placeholder MessageIntegrity attribute was set, but no `integrity_key` argument was provided to the `encode` function making the HMAC computation impossible.
     #[error("Missing message integrity password. A placeholder HMAC value is set in MessageIntegrity attribute but no `integrity_password` is provided as an encoding argument.")]
     MissingIntegrityPassword(),
+    /// The provided output buffer can't hold the encoded message.
+    #[error("Output buffer too small. Required: {required}, available: {available}.")]
+    BufferTooSmall {
+        /// Size of the encoded message
+        required: usize,
+        /// Space available in the provided buffer
+        available: usize,
+    },
+    /// The encoded attributes don't fit into the 16-bit message length field.
+    #[error("Message too big: {0} bytes of attributes.")]
+    MessageTooBig(usize),
 }
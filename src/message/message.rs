@@ -1,5 +1,3 @@
-pub use super::errors::{IntegrityKeyGenerationError, MessageDecodeError, MessageEncodeError};
-
 use crate::attribute::StunAttribute;
 use crate::header::StunHeader;
 
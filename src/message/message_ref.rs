@@ -0,0 +1,551 @@
+use std::net::SocketAddr;
+
+use super::message::StunMessage;
+use crate::attribute::{StunAddressFamily, StunAttribute, StunAttributeRef, StunPasswordAlgorithm};
+use crate::header::StunHeader;
+
+/// Borrowed view of a [STUN message](https://tools.ietf.org/html/rfc5389#section-6).
+///
+/// Decoding a `StunMessageRef` doesn't copy the values of the USERNAME, MESSAGE-INTEGRITY, MESSAGE-INTEGRITY-SHA256,
+/// ERROR-CODE, REALM, NONCE, SOFTWARE and DATA attributes, nor the ones of unknown attributes.
+/// Instead, they refer to the buffer the message was decoded from.
+/// The other attributes are decoded by value into [`StunAttributeRef::Other`], which allocates
+/// for variable-length values such as UNKNOWN-ATTRIBUTES or ACCESS-TOKEN.
+/// This makes it suitable for hot paths, such as answering ICE connectivity checks.
+///
+/// Typed accessors return the first occurrence of the corresponding attribute,
+/// as only the first occurrence needs to be processed by a receiver.
+///
+/// An example that decodes a sample request and reads its SOFTWARE attribute without copying it:
+/// ```
+/// // Encoded message
+/// let msg_bytes: Vec<u8> = vec![
+///     0x01, 0x01, 0x00, 0x48, 0x21, 0x12, 0xa4, 0x42, 0xb7, 0xe7, 0xa7, 0x01, 0xbc, 0x34,
+///     0xd6, 0x86, 0xfa, 0x87, 0xdf, 0xae, 0x80, 0x22, 0x00, 0x0b, 0x74, 0x65, 0x73, 0x74,
+///     0x20, 0x76, 0x65, 0x63, 0x74, 0x6f, 0x72, 0x00, 0x00, 0x20, 0x00, 0x14, 0x00, 0x02,
+///     0xa1, 0x47, 0x01, 0x13, 0xa9, 0xfa, 0xa5, 0xd3, 0xf1, 0x79, 0xbc, 0x25, 0xf4, 0xb5,
+///     0xbe, 0xd2, 0xb9, 0xd9, 0x00, 0x08, 0x00, 0x14, 0xBD, 0x3, 0x6D, 0x6A, 0x33, 0x17,
+///     0x50, 0xDF, 0xE2, 0xED, 0xC5, 0x8E, 0x64, 0x34, 0x55, 0xCF, 0xF5, 0xC8, 0xE2, 0x64,
+///     0x80, 0x28, 0x00, 0x04, 0x4F, 0x26, 0x02, 0x93,
+/// ];
+///
+/// // Decode the message
+/// let decoded_msg =
+///     stun_coder::StunMessageRef::decode(&msg_bytes, Some("VOkJxbRl1RmTxUk/WvJxBt")).unwrap();
+///
+/// assert_eq!(decoded_msg.get_software(), Some("test vector"));
+/// assert!(decoded_msg.get_xor_mapped_address().is_some());
+///
+/// // Convert it into an owned message if needed
+/// let owned_msg = decoded_msg.into_owned();
+/// ```
+#[derive(Debug, Clone)]
+pub struct StunMessageRef<'a> {
+    /// STUN message header
+    pub(super) header: StunHeader,
+    /// STUN message attributes
+    pub(super) attributes: Vec<StunAttributeRef<'a>>,
+}
+
+impl<'a> StunMessageRef<'a> {
+    /// Returns an immutable reference to the message header
+    pub fn get_header(&self) -> &StunHeader {
+        &self.header
+    }
+
+    /// Returns an immutable reference to the message attributes
+    pub fn get_attributes(&self) -> &[StunAttributeRef<'a>] {
+        &self.attributes
+    }
+
+    /// Converts the borrowed message into an owned one
+    pub fn into_owned(self) -> StunMessage {
+        StunMessage {
+            header: self.header,
+            attributes: self
+                .attributes
+                .into_iter()
+                .map(StunAttributeRef::into_owned)
+                .collect(),
+        }
+    }
+
+    /// Returns the value of the USERNAME attribute
+    pub fn get_username(&self) -> Option<&'a str> {
+        self.attributes.iter().find_map(|attr| match attr {
+            StunAttributeRef::Username { value } => Some(*value),
+            _ => None,
+        })
+    }
+
+    /// Returns the value of the REALM attribute
+    pub fn get_realm(&self) -> Option<&'a str> {
+        self.attributes.iter().find_map(|attr| match attr {
+            StunAttributeRef::Realm { value } => Some(*value),
+            _ => None,
+        })
+    }
+
+    /// Returns the value of the NONCE attribute
+    pub fn get_nonce(&self) -> Option<&'a str> {
+        self.attributes.iter().find_map(|attr| match attr {
+            StunAttributeRef::Nonce { value } => Some(*value),
+            _ => None,
+        })
+    }
+
+    /// Returns the value of the SOFTWARE attribute
+    pub fn get_software(&self) -> Option<&'a str> {
+        self.attributes.iter().find_map(|attr| match attr {
+            StunAttributeRef::Software { description } => Some(*description),
+            _ => None,
+        })
+    }
+
+    /// Returns the HMAC value of the MESSAGE-INTEGRITY attribute
+    pub fn get_message_integrity(&self) -> Option<&'a [u8]> {
+        self.attributes.iter().find_map(|attr| match attr {
+            StunAttributeRef::MessageIntegrity { key } => Some(*key),
+            _ => None,
+        })
+    }
+
+    /// Returns the (possibly truncated) HMAC value of the MESSAGE-INTEGRITY-SHA256 attribute
+    pub fn get_message_integrity_sha256(&self) -> Option<&'a [u8]> {
+        self.attributes.iter().find_map(|attr| match attr {
+            StunAttributeRef::MessageIntegritySha256 { key } => Some(*key),
+            _ => None,
+        })
+    }
+
+    /// Returns the class, number and reason phrase of the ERROR-CODE attribute
+    pub fn get_error_code(&self) -> Option<(u8, u8, &'a str)> {
+        self.attributes.iter().find_map(|attr| match attr {
+            StunAttributeRef::ErrorCode {
+                class,
+                number,
+                reason,
+            } => Some((*class, *number, *reason)),
+            _ => None,
+        })
+    }
+
+    /// Returns the attribute types listed in the UNKNOWN-ATTRIBUTES attribute
+    pub fn get_unknown_attributes(&self) -> Option<&[u16]> {
+        self.attributes.iter().find_map(|attr| match attr {
+            StunAttributeRef::Other(StunAttribute::UnknownAttributes { types }) => {
+                Some(types.as_slice())
+            }
+            _ => None,
+        })
+    }
+
+    /// Returns the application data of the DATA attribute
+    pub fn get_data(&self) -> Option<&'a [u8]> {
+        self.attributes.iter().find_map(|attr| match attr {
+            StunAttributeRef::Data { value } => Some(*value),
+            _ => None,
+        })
+    }
+
+    /// Returns the transport address of the XOR-MAPPED-ADDRESS attribute
+    pub fn get_xor_mapped_address(&self) -> Option<SocketAddr> {
+        self.find_other(|attr| match attr {
+            StunAttribute::XorMappedAddress { socket_addr } => Some(*socket_addr),
+            _ => None,
+        })
+    }
+
+    /// Returns the transport address of the MAPPED-ADDRESS attribute
+    pub fn get_mapped_address(&self) -> Option<SocketAddr> {
+        self.find_other(|attr| match attr {
+            StunAttribute::MappedAddress { socket_addr } => Some(*socket_addr),
+            _ => None,
+        })
+    }
+
+    /// Returns the transport address of the RESPONSE-ORIGIN attribute
+    pub fn get_response_origin(&self) -> Option<SocketAddr> {
+        self.find_other(|attr| match attr {
+            StunAttribute::ResponseOrigin { socket_addr } => Some(*socket_addr),
+            _ => None,
+        })
+    }
+
+    /// Returns the transport address of the OTHER-ADDRESS attribute
+    pub fn get_other_address(&self) -> Option<SocketAddr> {
+        self.find_other(|attr| match attr {
+            StunAttribute::OtherAddress { socket_addr } => Some(*socket_addr),
+            _ => None,
+        })
+    }
+
+    /// Returns the transport address of the RESPONSE-ADDRESS attribute
+    pub fn get_response_address(&self) -> Option<SocketAddr> {
+        self.find_other(|attr| match attr {
+            StunAttribute::ResponseAddress { socket_addr } => Some(*socket_addr),
+            _ => None,
+        })
+    }
+
+    /// Returns the transport address of the SOURCE-ADDRESS attribute
+    pub fn get_source_address(&self) -> Option<SocketAddr> {
+        self.find_other(|attr| match attr {
+            StunAttribute::SourceAddress { socket_addr } => Some(*socket_addr),
+            _ => None,
+        })
+    }
+
+    /// Returns the transport address of the CHANGED-ADDRESS attribute
+    pub fn get_changed_address(&self) -> Option<SocketAddr> {
+        self.find_other(|attr| match attr {
+            StunAttribute::ChangedAddress { socket_addr } => Some(*socket_addr),
+            _ => None,
+        })
+    }
+
+    /// Returns the transport address of the REFLECTED-FROM attribute
+    pub fn get_reflected_from(&self) -> Option<SocketAddr> {
+        self.find_other(|attr| match attr {
+            StunAttribute::ReflectedFrom { socket_addr } => Some(*socket_addr),
+            _ => None,
+        })
+    }
+
+    /// Returns the "change IP" and "change port" flags of the CHANGE-REQUEST attribute
+    pub fn get_change_request(&self) -> Option<(bool, bool)> {
+        self.find_other(|attr| match attr {
+            StunAttribute::ChangeRequest {
+                change_ip,
+                change_port,
+            } => Some((*change_ip, *change_port)),
+            _ => None,
+        })
+    }
+
+    /// Returns the port of the RESPONSE-PORT attribute
+    pub fn get_response_port(&self) -> Option<u16> {
+        self.find_other(|attr| match attr {
+            StunAttribute::ResponsePort { port } => Some(*port),
+            _ => None,
+        })
+    }
+
+    /// Returns the value of the PRIORITY attribute
+    pub fn get_priority(&self) -> Option<u32> {
+        self.find_other(|attr| match attr {
+            StunAttribute::Priority { value } => Some(*value),
+            _ => None,
+        })
+    }
+
+    /// Returns the tiebreaker value of the ICE-CONTROLLING attribute
+    pub fn get_ice_controlling(&self) -> Option<u64> {
+        self.find_other(|attr| match attr {
+            StunAttribute::IceControlling { tie_breaker } => Some(*tie_breaker),
+            _ => None,
+        })
+    }
+
+    /// Returns the tiebreaker value of the ICE-CONTROLLED attribute
+    pub fn get_ice_controlled(&self) -> Option<u64> {
+        self.find_other(|attr| match attr {
+            StunAttribute::IceControlled { tie_breaker } => Some(*tie_breaker),
+            _ => None,
+        })
+    }
+
+    /// Returns the STUN server name of the THIRD-PARTY-AUTHORIZATION attribute
+    pub fn get_third_party_authorization(&self) -> Option<&str> {
+        self.attributes.iter().find_map(|attr| match attr {
+            StunAttributeRef::Other(StunAttribute::ThirdPartyAuthorization { server_name }) => {
+                Some(server_name.as_str())
+            }
+            _ => None,
+        })
+    }
+
+    /// Returns the encrypted self-contained token of the ACCESS-TOKEN attribute
+    pub fn get_access_token(&self) -> Option<&[u8]> {
+        self.attributes.iter().find_map(|attr| match attr {
+            StunAttributeRef::Other(StunAttribute::AccessToken { value }) => Some(value.as_slice()),
+            _ => None,
+        })
+    }
+
+    /// Returns the value of the CONNECTION-ID attribute
+    pub fn get_connection_id(&self) -> Option<u32> {
+        self.find_other(|attr| match attr {
+            StunAttribute::ConnectionId { value } => Some(*value),
+            _ => None,
+        })
+    }
+
+    /// Returns the transport address of the first XOR-RELAYED-ADDRESS attribute
+    pub fn get_xor_relayed_address(&self) -> Option<SocketAddr> {
+        self.find_other(|attr| match attr {
+            StunAttribute::XorRelayedAddress { socket_addr } => Some(*socket_addr),
+            _ => None,
+        })
+    }
+
+    /// Returns the transport addresses of all the XOR-RELAYED-ADDRESS attributes
+    ///
+    /// As per [RFC8656 Section 7.2](https://tools.ietf.org/html/rfc8656#section-7.2) a dual-stack allocation
+    /// is answered with one IPv4 and one IPv6 XOR-RELAYED-ADDRESS attribute.
+    pub fn get_xor_relayed_addresses(&self) -> Vec<SocketAddr> {
+        self.attributes
+            .iter()
+            .filter_map(|attr| match attr {
+                StunAttributeRef::Other(StunAttribute::XorRelayedAddress { socket_addr }) => {
+                    Some(*socket_addr)
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the address family of the REQUESTED-ADDRESS-FAMILY attribute
+    pub fn get_requested_address_family(&self) -> Option<StunAddressFamily> {
+        self.find_other(|attr| match attr {
+            StunAttribute::RequestedAddressFamily { family } => Some(*family),
+            _ => None,
+        })
+    }
+
+    /// Returns the address family of the ADDITIONAL-ADDRESS-FAMILY attribute
+    pub fn get_additional_address_family(&self) -> Option<StunAddressFamily> {
+        self.find_other(|attr| match attr {
+            StunAttribute::AdditionalAddressFamily { family } => Some(*family),
+            _ => None,
+        })
+    }
+
+    /// Returns the address family, class, number and reason phrase of the ADDRESS-ERROR-CODE attribute
+    pub fn get_address_error_code(&self) -> Option<(StunAddressFamily, u8, u8, &str)> {
+        self.attributes.iter().find_map(|attr| match attr {
+            StunAttributeRef::Other(StunAttribute::AddressErrorCode {
+                family,
+                class,
+                number,
+                reason,
+            }) => Some((*family, *class, *number, reason.as_str())),
+            _ => None,
+        })
+    }
+
+    /// Returns the value of the NOMINATION attribute
+    pub fn get_nomination(&self) -> Option<u32> {
+        self.find_other(|attr| match attr {
+            StunAttribute::Nomination { value } => Some(*value),
+            _ => None,
+        })
+    }
+
+    /// Returns the network ID and network cost of the GOOG-NETWORK-INFO attribute
+    pub fn get_goog_network_info(&self) -> Option<(u16, u16)> {
+        self.find_other(|attr| match attr {
+            StunAttribute::GoogNetworkInfo {
+                network_id,
+                network_cost,
+            } => Some((*network_id, *network_cost)),
+            _ => None,
+        })
+    }
+
+    /// Returns the transaction ID of the GOOG-LAST-ICE-CHECK-RECEIVED attribute
+    pub fn get_goog_last_ice_check_received(&self) -> Option<&[u8]> {
+        self.attributes.iter().find_map(|attr| match attr {
+            StunAttributeRef::Other(StunAttribute::GoogLastIceCheckReceived { value }) => {
+                Some(value.as_slice())
+            }
+            _ => None,
+        })
+    }
+
+    /// Returns the values of the GOOG-MISC-INFO attribute
+    pub fn get_goog_misc_info(&self) -> Option<&[u16]> {
+        self.attributes.iter().find_map(|attr| match attr {
+            StunAttributeRef::Other(StunAttribute::GoogMiscInfo { values }) => {
+                Some(values.as_slice())
+            }
+            _ => None,
+        })
+    }
+
+    /// Returns the value of the GOOG-DELTA attribute
+    pub fn get_goog_delta(&self) -> Option<&[u8]> {
+        self.attributes.iter().find_map(|attr| match attr {
+            StunAttributeRef::Other(StunAttribute::GoogDelta { value }) => Some(value.as_slice()),
+            _ => None,
+        })
+    }
+
+    /// Returns the value of the GOOG-DELTA-ACK attribute
+    pub fn get_goog_delta_ack(&self) -> Option<u64> {
+        self.find_other(|attr| match attr {
+            StunAttribute::GoogDeltaAck { value } => Some(*value),
+            _ => None,
+        })
+    }
+
+    /// Returns the version of the MS-VERSION attribute
+    pub fn get_ms_version(&self) -> Option<u32> {
+        self.find_other(|attr| match attr {
+            StunAttribute::MsVersion { version } => Some(*version),
+            _ => None,
+        })
+    }
+
+    /// Returns the connection ID and sequence number of the MS-SEQUENCE-NUMBER attribute
+    pub fn get_ms_sequence_number(&self) -> Option<([u8; 20], u32)> {
+        self.find_other(|attr| match attr {
+            StunAttribute::MsSequenceNumber {
+                connection_id,
+                sequence_number,
+            } => Some((*connection_id, *sequence_number)),
+            _ => None,
+        })
+    }
+
+    /// Returns the stream type and service quality of the MS-SERVICE-QUALITY attribute
+    pub fn get_ms_service_quality(&self) -> Option<(u16, u16)> {
+        self.find_other(|attr| match attr {
+            StunAttribute::MsServiceQuality {
+                stream_type,
+                service_quality,
+            } => Some((*stream_type, *service_quality)),
+            _ => None,
+        })
+    }
+
+    /// Returns the message type of the BANDWIDTH-ADMISSION-CONTROL-MESSAGE attribute
+    pub fn get_bandwidth_admission_control(&self) -> Option<u16> {
+        self.find_other(|attr| match attr {
+            StunAttribute::BandwidthAdmissionControl { message_type } => Some(*message_type),
+            _ => None,
+        })
+    }
+
+    /// Returns the host name of the MS-ALTERNATE-HOST-NAME attribute
+    pub fn get_ms_alternate_host_name(&self) -> Option<&str> {
+        self.attributes.iter().find_map(|attr| match attr {
+            StunAttributeRef::Other(StunAttribute::MsAlternateHostName { host_name }) => {
+                Some(host_name.as_str())
+            }
+            _ => None,
+        })
+    }
+
+    /// Returns the candidate foundation of the CANDIDATE-IDENTIFIER attribute
+    pub fn get_candidate_identifier(&self) -> Option<[u8; 4]> {
+        self.find_other(|attr| match attr {
+            StunAttribute::CandidateIdentifier { foundation } => Some(*foundation),
+            _ => None,
+        })
+    }
+
+    /// Returns the version of the MS-IMPLEMENTATION-VERSION attribute
+    pub fn get_ms_implementation_version(&self) -> Option<u32> {
+        self.find_other(|attr| match attr {
+            StunAttribute::MsImplementationVersion { version } => Some(*version),
+            _ => None,
+        })
+    }
+
+    /// Returns the request and response counters of the TRANSACTION_TRANSMIT_COUNTER attribute
+    pub fn get_transaction_transmit_counter(&self) -> Option<(u8, u8)> {
+        self.find_other(|attr| match attr {
+            StunAttribute::TransactionTransmitCounter { request, response } => {
+                Some((*request, *response))
+            }
+            _ => None,
+        })
+    }
+
+    /// Returns the ticket of the MOBILITY-TICKET attribute
+    pub fn get_mobility_ticket(&self) -> Option<&[u8]> {
+        self.attributes.iter().find_map(|attr| match attr {
+            StunAttributeRef::Other(StunAttribute::MobilityTicket { value }) => {
+                Some(value.as_slice())
+            }
+            _ => None,
+        })
+    }
+
+    /// Returns the ECN field value and the valid flag of the ECN-CHECK attribute
+    pub fn get_ecn_check(&self) -> Option<(u8, bool)> {
+        self.find_other(|attr| match attr {
+            StunAttribute::EcnCheck { ecn_field, valid } => Some((*ecn_field, *valid)),
+            _ => None,
+        })
+    }
+
+    /// Returns the values of all the ORIGIN attributes
+    pub fn get_origins(&self) -> Vec<&str> {
+        self.attributes
+            .iter()
+            .filter_map(|attr| match attr {
+                StunAttributeRef::Other(StunAttribute::Origin { value }) => Some(value.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the value of the USERHASH attribute
+    pub fn get_userhash(&self) -> Option<[u8; 32]> {
+        self.find_other(|attr| match attr {
+            StunAttribute::Userhash { value } => Some(*value),
+            _ => None,
+        })
+    }
+
+    /// Returns the algorithm of the PASSWORD-ALGORITHM attribute
+    pub fn get_password_algorithm(&self) -> Option<StunPasswordAlgorithm> {
+        self.find_other(|attr| match attr {
+            StunAttribute::PasswordAlgorithm { algorithm, .. } => Some(*algorithm),
+            _ => None,
+        })
+    }
+
+    /// Checks whether the USE-CANDIDATE attribute is present
+    pub fn has_use_candidate(&self) -> bool {
+        self.find_other(|attr| match attr {
+            StunAttribute::UseCandidate => Some(()),
+            _ => None,
+        })
+        .is_some()
+    }
+
+    /// Returns the types of all the unknown comprehension-required attributes
+    ///
+    /// Only messages decoded via `decode_lenient` can contain such attributes.
+    pub fn get_unknown_required_attributes(&self) -> Vec<u16> {
+        self.attributes
+            .iter()
+            .filter_map(|attr| match attr {
+                StunAttributeRef::Unknown { attr_type, .. } if *attr_type < 0x8000 => {
+                    Some(*attr_type)
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Checks whether the FINGERPRINT attribute is present
+    pub fn has_fingerprint(&self) -> bool {
+        self.find_other(|attr| match attr {
+            StunAttribute::Fingerprint { .. } => Some(()),
+            _ => None,
+        })
+        .is_some()
+    }
+
+    /// Finds the first attribute decoded by value that matches the provided filter
+    pub(crate) fn find_other<T>(&self, filter: impl Fn(&StunAttribute) -> Option<T>) -> Option<T> {
+        self.attributes.iter().find_map(|attr| match attr {
+            StunAttributeRef::Other(attr) => filter(attr),
+            _ => None,
+        })
+    }
+}
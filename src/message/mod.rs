@@ -1,9 +1,13 @@
+mod credential;
 mod decode;
 mod encode;
 mod errors;
 mod message;
+mod message_ref;
 mod private_utils;
 mod utils;
 
+pub(crate) use decode::DecodeOptions;
 pub use errors::{IntegrityKeyGenerationError, MessageDecodeError, MessageEncodeError};
 pub use message::StunMessage;
+pub use message_ref::StunMessageRef;
@@ -3,34 +3,40 @@ use crc::{crc32, Hasher32};
 use crypto::digest::Digest;
 use crypto::hmac::Hmac;
 use crypto::mac::Mac;
-use crypto::md5::Md5;
-use crypto::sha1::Sha1;
+use std::borrow::Cow;
 
-pub use super::errors::{IntegrityKeyGenerationError, MessageDecodeError, MessageEncodeError};
+use super::credential::IntegrityCredential;
+use super::errors::IntegrityKeyGenerationError;
 
 use super::message::StunMessage;
-use crate::definitions::{STUN_HEADER_SIZE, STUN_INTEGRITY_ATTR_SIZE};
+use crate::attribute::StunPasswordAlgorithm;
+use crate::definitions::{STUN_HEADER_SIZE, STUN_MAGIC_COOKIE};
+use crate::header::StunHeader;
 
 impl StunMessage {
-    /// Encodes and sets the encoded message length
+    /// Creates a message without attributes from a copy of the header, keeping its dialect
     ///
     /// Arguments:
     ///
-    /// * `encoded_message`: Encoded message buffer
-    /// * `len`: length number to be set
-    pub(super) fn set_encoded_message_length(encoded_message: &mut [u8], len: u16) {
-        NetworkEndian::write_u16(&mut encoded_message[2..4], len);
+    /// * `header`: Header of the message. Its message length is reset.
+    pub(crate) fn with_header(header: StunHeader) -> Self {
+        Self {
+            header: StunHeader {
+                message_len: 0,
+                ..header
+            },
+            attributes: Vec::new(),
+        }
     }
 
-    /// Sets the correct length into the encoding buffer
+    /// Encodes and sets the encoded message length
+    ///
     /// Arguments:
     ///
     /// * `encoded_message`: Encoded message buffer
-    /// * `placeholder_size`: Adds to the calculated size of the current buffer
-    pub(super) fn set_message_length(&self, encoded_message: &mut [u8], placeholder_size: u16) {
-        let msg_len = encoded_message.len() as u16 - STUN_HEADER_SIZE as u16 + placeholder_size;
-
-        Self::set_encoded_message_length(encoded_message, msg_len);
+    /// * `len`: length number to be set
+    pub(super) fn set_encoded_message_length(encoded_message: &mut [u8], len: u16) {
+        NetworkEndian::write_u16(&mut encoded_message[2..4], len);
     }
 
     /// Calculates the CRC32 Fingerprint for the message according to [RFC5389](https://tools.ietf.org/html/rfc5389#section-15.5)
@@ -44,35 +50,24 @@ impl StunMessage {
         crc.sum32() ^ 0x5354_554e
     }
 
-    /// Calculates the integrity key used for generating HMAC according to [RFC5389](https://tools.ietf.org/html/rfc5389#section-15.4)
+    /// Calculates the integrity key used for generating HMAC according to [RFC8489](https://tools.ietf.org/html/rfc8489#section-9.2.2)
     ///
     /// Arguments:
     ///
     /// * `integrity_password`: Password used for HMAC
     /// * `realm`: Optional argument that contains the "realm". If provided long-term credentials key will be generated
     /// * `username`: Optional argument that contains the "username". Required it the `realm` is provided.
+    /// * `algorithm`: Algorithm used for the long-term credentials key derivation
     pub(super) fn calculate_integrity_key(
         integrity_password: &str,
-        realm: Option<String>,
-        username: Option<String>,
+        realm: Option<&str>,
+        username: Option<&str>,
+        algorithm: StunPasswordAlgorithm,
     ) -> Result<Vec<u8>, IntegrityKeyGenerationError> {
         let key = match realm {
             Some(realm) => {
                 if let Some(username) = username {
-                    let hash_input = format!(
-                        "{}:{}:{}",
-                        username,
-                        realm,
-                        stringprep::saslprep(integrity_password)?
-                    );
-
-                    let mut hasher = Md5::new();
-                    hasher.input_str(&hash_input);
-
-                    let mut res = vec![0u8; 16];
-                    hasher.result(&mut res);
-
-                    res
+                    Self::calculate_long_term_key(username, realm, integrity_password, algorithm)?
                 } else {
                     return Err(IntegrityKeyGenerationError::MissingUsername());
                 }
@@ -85,25 +80,75 @@ impl StunMessage {
         Ok(key)
     }
 
-    /// Calculates the integrity hash according to [RFC5389](https://tools.ietf.org/html/rfc5389#section-15.4)
+    /// Resolves the HMAC key from the provided credential
     ///
     /// Arguments:
     ///
+    /// * `credential`: Password or precomputed key
+    /// * `realm`: REALM attribute value of the message
+    /// * `username`: USERNAME attribute value of the message
+    /// * `algorithm`: Algorithm set via the PASSWORD-ALGORITHM attribute of the message
+    pub(super) fn resolve_integrity_key<'k>(
+        credential: IntegrityCredential<'k>,
+        realm: Option<&str>,
+        username: Option<&str>,
+        algorithm: StunPasswordAlgorithm,
+    ) -> Result<Cow<'k, [u8]>, IntegrityKeyGenerationError> {
+        match credential {
+            IntegrityCredential::Password(password) => Ok(Cow::Owned(
+                Self::calculate_integrity_key(password, realm, username, algorithm)?,
+            )),
+            IntegrityCredential::Key(key) => Ok(Cow::Borrowed(key)),
+        }
+    }
+
+    /// Returns the amount of zero bytes padding the HMAC input of a classic RFC3489 message
+    ///
+    /// As per [RFC3489 Section 11.2.8](https://tools.ietf.org/html/rfc3489#section-11.2.8) the text used as input to HMAC
+    /// is padded with zeroes so as to be a multiple of 64 bytes.
+    ///
+    /// Arguments:
+    ///
+    /// * `hmac_input_len`: Length of the message up to the integrity attribute
+    pub(super) fn legacy_integrity_padding_len(hmac_input_len: usize) -> usize {
+        (64 - (hmac_input_len & 63)) & 63
+    }
+
+    /// Calculates the integrity hash according to [RFC5389](https://tools.ietf.org/html/rfc5389#section-15.4) and [RFC8489](https://tools.ietf.org/html/rfc8489#section-14.6)
+    ///
+    /// Classic RFC3489 messages, which lack the magic cookie, are hashed according to [RFC3489](https://tools.ietf.org/html/rfc3489#section-11.2.8).
+    ///
+    /// Arguments:
+    ///
+    /// * `digest`: Hash function used for HMAC (SHA1 for MessageIntegrity, SHA256 for MessageIntegritySha256)
     /// * `key`: HMAC key to use
-    /// * `msg_integrity_buffer`: Encoded message without the Integrity and Fingerprint attribute sections
-    pub(super) fn calculate_integrity_hash(key: &[u8], encoded_message: &[u8]) -> Vec<u8> {
-        let mut msg_integrity_buffer = encoded_message.to_vec();
-        let buffer_len = msg_integrity_buffer.len();
+    /// * `encoded_message`: Encoded message up to the integrity attribute
+    /// * `integrity_attr_size`: Size of the integrity attribute, including its TLV header
+    pub(super) fn calculate_integrity_hash<D: Digest>(
+        digest: D,
+        key: &[u8],
+        encoded_message: &[u8],
+        integrity_attr_size: usize,
+    ) -> Vec<u8> {
+        let mut header = [0u8; STUN_HEADER_SIZE];
+        header.copy_from_slice(&encoded_message[0..STUN_HEADER_SIZE]);
 
-        // Set the message length to a number that includes the MessageIntegrity attribute size but ignores everything after it
+        // Set the message length to a number that includes the integrity attribute size but ignores everything after it
         Self::set_encoded_message_length(
-            &mut msg_integrity_buffer,
-            (buffer_len - STUN_HEADER_SIZE + STUN_INTEGRITY_ATTR_SIZE) as u16,
+            &mut header,
+            (encoded_message.len() - STUN_HEADER_SIZE + integrity_attr_size) as u16,
         );
 
         // Calculate hash
-        let mut mac = Hmac::new(Sha1::new(), key);
-        mac.input(&msg_integrity_buffer);
+        let mut mac = Hmac::new(digest, key);
+        mac.input(&header);
+        mac.input(&encoded_message[STUN_HEADER_SIZE..]);
+
+        if encoded_message[4..8] != STUN_MAGIC_COOKIE {
+            let padding_len = Self::legacy_integrity_padding_len(encoded_message.len());
+            mac.input(&[0u8; 64][..padding_len]);
+        }
+
         mac.result().code().to_vec()
     }
 }
@@ -1,11 +1,14 @@
+use crypto::digest::Digest;
+use crypto::md5::Md5;
+use crypto::sha2::Sha256;
 use stringprep::saslprep;
 
-pub use super::errors::{IntegrityKeyGenerationError, MessageDecodeError, MessageEncodeError};
 use crate::definitions::StunTransactionId;
 use crate::header::StunHeader;
-use crate::header::{StunMessageClass, StunMessageMethod};
-use crate::StunAttribute;
+use crate::header::{StunDialect, StunMessageClass, StunMessageMethod};
+use crate::{StunAttribute, StunPasswordAlgorithm};
 
+use super::errors::IntegrityKeyGenerationError;
 use super::message::StunMessage;
 
 impl StunMessage {
@@ -39,6 +42,35 @@ impl StunMessage {
         Self::default().set_message_class(StunMessageClass::Indication)
     }
 
+    /// Creates a response to the request with the provided header
+    ///
+    /// The response echoes the method and the transaction ID of the request,
+    /// including the full 128-bit transaction ID of classic RFC3489 requests.
+    ///
+    /// Arguments:
+    ///
+    /// * `request_header`: Header of the request
+    /// * `class`: Class of the response
+    ///
+    /// ```
+    /// # use stun_coder::{StunMessage, StunMessageClass};
+    /// let request = StunMessage::create_request();
+    /// let response = StunMessage::create_response_to(request.get_header(), StunMessageClass::ErrorResponse)
+    ///     .add_error_code(4, 0, "Bad Request");
+    ///
+    /// assert_eq!(
+    ///     response.get_header().transaction_id,
+    ///     request.get_header().transaction_id
+    /// );
+    /// ```
+    pub fn create_response_to(request_header: &StunHeader, class: StunMessageClass) -> Self {
+        let mut response = Self::new(request_header.message_method, class);
+        response.header.transaction_id = request_header.transaction_id;
+        response.header.dialect = request_header.dialect;
+
+        response
+    }
+
     /// Sets message transaction id
     pub fn set_transaction_id(mut self, transaction_id: StunTransactionId) -> Self {
         self.header.transaction_id = transaction_id;
@@ -46,6 +78,24 @@ impl StunMessage {
         self
     }
 
+    /// Sets the 128-bit transaction id of a classic [RFC3489](https://tools.ietf.org/html/rfc3489#section-11.1) message
+    ///
+    /// The message is encoded without the magic cookie, which is replaced by the first 32 bits of the transaction id.
+    /// Attributes introduced after RFC3489, such as XOR-MAPPED-ADDRESS, are not understood by classic agents.
+    pub fn set_legacy_transaction_id(mut self, transaction_id: [u8; 16]) -> Self {
+        let mut transaction_id_prefix = [0; 4];
+        transaction_id_prefix.copy_from_slice(&transaction_id[..4]);
+
+        self.header.dialect = StunDialect::Rfc3489 {
+            transaction_id_prefix,
+        };
+        self.header
+            .transaction_id
+            .copy_from_slice(&transaction_id[4..]);
+
+        self
+    }
+
     /// Sets message class
     pub fn set_message_class(mut self, class: StunMessageClass) -> Self {
         self.header.message_class = class;
@@ -77,6 +127,42 @@ impl StunMessage {
         self
     }
 
+    /// Adds an ErrorCode attribute
+    ///
+    /// Arguments:
+    ///
+    /// * `class`: Hundreds digit of the error code, between 3 and 6
+    /// * `number`: Error code modulo 100
+    /// * `reason`: Human-readable reason phrase
+    pub fn add_error_code(mut self, class: u8, number: u8, reason: &str) -> Self {
+        self.attributes.push(StunAttribute::ErrorCode {
+            class,
+            number,
+            reason: String::from(reason),
+        });
+
+        self
+    }
+
+    /// Adds the REALM and NONCE attributes challenging the client to authenticate with its long-term credential
+    ///
+    /// Used in 401 (Unauthorized) and 438 (Stale Nonce) error responses, as per [RFC5389 Section 10.2.2](https://tools.ietf.org/html/rfc5389#section-10.2.2).
+    ///
+    /// Arguments:
+    ///
+    /// * `realm`: Realm of the server
+    /// * `nonce`: Nonce the client has to use in its next request
+    pub fn add_long_term_credential_challenge(mut self, realm: &str, nonce: &str) -> Self {
+        self.attributes.push(StunAttribute::Realm {
+            value: String::from(realm),
+        });
+        self.attributes.push(StunAttribute::Nonce {
+            value: String::from(nonce),
+        });
+
+        self
+    }
+
     /// Adds a Fingerprint attribute at the end of the message
     ///
     /// NOTE: This function should be invoked only when all other attributes are added
@@ -97,13 +183,23 @@ impl StunMessage {
         self
     }
 
-    /// Adds USER, REALM and MESSAGE-INTEGRITY attributes for long term credential authentication
+    /// Adds USERNAME, REALM, NONCE and MESSAGE-INTEGRITY attributes for long term credential authentication
+    ///
+    /// The realm and the nonce are the ones received in the last 401 (Unauthorized) or 438 (Stale Nonce) error response of the server.
+    /// See [`LongTermCredentialClient`](crate::LongTermCredentialClient) for a client that handles those responses.
     ///
     /// NOTE: This function should be invoked only when all other attributes are added but before the Fingerprint attribute
+    ///
+    /// Arguments:
+    ///
+    /// * `username`: Username. Processed via SASLprep.
+    /// * `realm`: REALM attribute value of the error response. Processed via SASLprep.
+    /// * `nonce`: NONCE attribute value of the error response
     pub fn add_long_term_credential_message_integrity(
         mut self,
         username: &str,
         realm: &str,
+        nonce: &str,
     ) -> Result<Self, stringprep::Error> {
         self.attributes.push(StunAttribute::Username {
             value: saslprep(username)?.to_string(),
@@ -113,8 +209,130 @@ impl StunMessage {
             value: saslprep(realm)?.to_string(),
         });
 
+        self.attributes.push(StunAttribute::Nonce {
+            value: String::from(nonce),
+        });
+
         Ok(self.add_message_integrity())
     }
+
+    /// Adds a Userhash attribute computed from the provided username and realm.
+    ///
+    /// The username is not sent in clear, so the message has to be encoded with a precomputed key
+    /// using `encode_with_key` and [`calculate_long_term_key`](StunMessage::calculate_long_term_key).
+    ///
+    /// Arguments:
+    ///
+    /// * `username`: Username to hash. Processed via SASLprep before hashing.
+    /// * `realm`: Realm to hash. Processed via SASLprep before hashing.
+    pub fn add_userhash(mut self, username: &str, realm: &str) -> Result<Self, stringprep::Error> {
+        let value = Self::calculate_userhash(username, realm)?;
+
+        self.attributes.push(StunAttribute::Userhash { value });
+
+        Ok(self)
+    }
+
+    /// Calculates the USERHASH value according to [RFC8489](https://tools.ietf.org/html/rfc8489#section-14.4)
+    ///
+    /// userhash = SHA-256(SASLprep(username) ":" SASLprep(realm))
+    ///
+    /// Servers can use it to build an index of the known users by their USERHASH values.
+    pub fn calculate_userhash(username: &str, realm: &str) -> Result<[u8; 32], stringprep::Error> {
+        let mut hasher = Sha256::new();
+        hasher.input_str(&format!("{}:{}", saslprep(username)?, saslprep(realm)?));
+
+        let mut userhash = [0u8; 32];
+        hasher.result(&mut userhash);
+
+        Ok(userhash)
+    }
+
+    /// Adds a MessageIntegritySha256 attribute with a placeholder value.
+    ///
+    /// The full 32 byte HMAC-SHA256 value is computed during encoding.
+    /// If a MessageIntegrity attribute is present as well, this one has to be added after it.
+    pub fn add_message_integrity_sha256(mut self) -> Self {
+        self.attributes
+            .push(StunAttribute::MessageIntegritySha256 { key: Vec::new() });
+
+        self
+    }
+
+    /// Adds a MessageIntegritySha256 attribute with a placeholder value for a truncated HMAC.
+    ///
+    /// Arguments:
+    ///
+    /// * `length`: Amount of HMAC-SHA256 bytes to keep. Must be between 16 and 32 and a multiple of 4.
+    pub fn add_truncated_message_integrity_sha256(mut self, length: usize) -> Self {
+        self.attributes.push(StunAttribute::MessageIntegritySha256 {
+            key: vec![0u8; length],
+        });
+
+        self
+    }
+
+    /// Calculates the long-term credential key according to [RFC8489](https://tools.ietf.org/html/rfc8489#section-9.2.2)
+    ///
+    /// The key is the MD5 or SHA-256 hash of `username ":" realm ":" SASLprep(password)`.
+    /// It can be used with `encode_with_key`/`decode_with_key` when the message itself doesn't contain
+    /// the attributes the key is derived from (e.g. responses to requests using SHA-256).
+    ///
+    /// Arguments:
+    ///
+    /// * `username`: USERNAME attribute value
+    /// * `realm`: REALM attribute value
+    /// * `password`: Long-term password
+    /// * `algorithm`: Key derivation algorithm
+    ///
+    /// ```
+    /// # use stun_coder::{StunMessage, StunPasswordAlgorithm};
+    /// let key = StunMessage::calculate_long_term_key(
+    ///     "user",
+    ///     "example.org",
+    ///     "pass",
+    ///     StunPasswordAlgorithm::Sha256,
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(key.len(), 32);
+    /// ```
+    pub fn calculate_long_term_key(
+        username: &str,
+        realm: &str,
+        password: &str,
+        algorithm: StunPasswordAlgorithm,
+    ) -> Result<Vec<u8>, IntegrityKeyGenerationError> {
+        let hash_input = format!("{}:{}:{}", username, realm, saslprep(password)?);
+
+        let key = match algorithm {
+            StunPasswordAlgorithm::Md5 => {
+                let mut hasher = Md5::new();
+                hasher.input_str(&hash_input);
+
+                let mut res = vec![0u8; 16];
+                hasher.result(&mut res);
+
+                res
+            }
+            StunPasswordAlgorithm::Sha256 => {
+                let mut hasher = Sha256::new();
+                hasher.input_str(&hash_input);
+
+                let mut res = vec![0u8; 32];
+                hasher.result(&mut res);
+
+                res
+            }
+            StunPasswordAlgorithm::Other(algorithm) => {
+                return Err(IntegrityKeyGenerationError::UnsupportedPasswordAlgorithm(
+                    algorithm,
+                ))
+            }
+        };
+
+        Ok(key)
+    }
 }
 
 impl std::default::Default for StunMessage {
@@ -0,0 +1,486 @@
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+use super::errors::NatDiscoveryError;
+use super::report::{NatBehaviorReport, NatFilteringBehavior, NatMappingBehavior};
+use crate::definitions::StunTransactionId;
+use crate::{
+    StunAttribute, StunClientTransaction, StunMessage, StunMessageClass, StunMessageMethod,
+    StunMessageRef, StunTransactionEvent, StunTransport,
+};
+
+/// Tests of the [RFC5780](https://tools.ietf.org/html/rfc5780#section-4) NAT behavior discovery sequence
+///
+/// The filtering tests are run right after Test I, before any packets are sent to the alternate
+/// server address. Otherwise the mapping tests would open the NAT filters for the alternate address.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum NatTest {
+    /// Test I: Binding request to the primary server address.
+    /// Learns the mapped address and the alternate server address.
+    Binding,
+    /// Mapping Test II: Binding request to the alternate IP address and the primary port.
+    MappingAlternateAddress,
+    /// Mapping Test III: Binding request to the alternate IP address and port.
+    MappingAlternateAddressAndPort,
+    /// Filtering Test II: Binding request to the primary server address with the "change IP" and "change port" flags set.
+    FilteringChangeAddressAndPort,
+    /// Filtering Test III: Binding request to the primary server address with the "change port" flag set.
+    FilteringChangePort,
+}
+
+/// Datagram that has to be sent by the caller
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct NatDiscoveryTransmit {
+    /// Destination transport address
+    pub destination: SocketAddr,
+    /// Encoded Binding request
+    pub payload: Vec<u8>,
+}
+
+/// Test that is currently in progress
+struct PendingTest {
+    test: NatTest,
+    destination: SocketAddr,
+    /// Transport address the response is expected from
+    source: SocketAddr,
+    transaction_id: StunTransactionId,
+    /// Request transaction, started once the request is first polled
+    transaction: Option<StunClientTransaction>,
+    /// (Re)transmission of the request waiting to be handed to the caller
+    transmit: Option<Vec<u8>>,
+}
+
+/// Sans-IO [RFC5780](https://tools.ietf.org/html/rfc5780#section-4) NAT behavior discovery engine
+///
+/// The engine runs the mapping and filtering behavior tests one by one against a server that has
+/// an alternate IP address and port, and produces a [`NatBehaviorReport`].
+/// It doesn't perform any IO by itself. Instead, the caller:
+///
+/// * sends the datagrams returned by `poll_transmit` from a single local socket,
+/// * feeds every datagram received on that socket into `handle_input`, along with its source address,
+/// * calls `handle_timeout` once the instant returned by `poll_timeout` is reached,
+/// * collects the result via `poll_report` once it's available.
+///
+/// Each test request is retransmitted as per [RFC5389 Section 7.2.1](https://tools.ietf.org/html/rfc5389#section-7.2.1),
+/// so that a single lost datagram doesn't affect the result. A test that gets no response once its transaction
+/// times out is considered failed, which is the expected outcome of some filtering tests.
+///
+/// ```no_run
+/// use std::net::UdpSocket;
+/// use std::time::Instant;
+/// use stun_coder::NatBehaviorDiscovery;
+///
+/// let server = "198.51.100.1:3478".parse().unwrap();
+///
+/// // The socket can't be connected, as the tests reach the alternate address of the server too.
+/// // Bind it to the concrete address of the interface the server is reached through instead.
+/// let probe = UdpSocket::bind("0.0.0.0:0").unwrap();
+/// probe.connect(server).unwrap();
+/// let socket = UdpSocket::bind((probe.local_addr().unwrap().ip(), 0)).unwrap();
+///
+/// let mut discovery = NatBehaviorDiscovery::new(server, socket.local_addr().unwrap());
+/// let mut buf = [0u8; 1500];
+///
+/// let report = loop {
+///     if let Some(report) = discovery.poll_report() {
+///         break report;
+///     }
+///
+///     if let Some(transmit) = discovery.poll_transmit(Instant::now()) {
+///         socket.send_to(&transmit.payload, transmit.destination).unwrap();
+///     }
+///
+///     let deadline = discovery.poll_timeout().unwrap();
+///     socket
+///         .set_read_timeout(Some(deadline.saturating_duration_since(Instant::now()).max(std::time::Duration::from_millis(1))))
+///         .unwrap();
+///
+///     match socket.recv_from(&mut buf) {
+///         Ok((len, source)) => {
+///             discovery.handle_input(&buf[..len], source);
+///         }
+///         Err(_) => discovery.handle_timeout(Instant::now()),
+///     }
+/// };
+///
+/// println!("{:?}", report);
+/// ```
+pub struct NatBehaviorDiscovery {
+    /// Primary server transport address
+    server: SocketAddr,
+    /// Local transport address the tests are run from
+    local_address: SocketAddr,
+    /// Initial retransmission timeout of the test requests
+    rto: Duration,
+    /// Maximum amount of transmissions of each test request (Rc)
+    max_transmissions: u32,
+    /// Test in progress
+    pending: Option<PendingTest>,
+    /// Mapped address learned via Test I
+    mapped_address: Option<SocketAddr>,
+    /// Alternate server address learned via Test I
+    other_address: Option<SocketAddr>,
+    /// Mapped address learned via Mapping Test II
+    alternate_mapped_address: Option<SocketAddr>,
+    /// Detected filtering behavior
+    filtering: Option<NatFilteringBehavior>,
+    /// Final result, ready to be collected
+    result: Option<Result<NatBehaviorReport, NatDiscoveryError>>,
+}
+
+impl NatBehaviorDiscovery {
+    /// Creates a new NAT behavior discovery engine
+    ///
+    /// Arguments:
+    ///
+    /// * `server`: Primary transport address of a server supporting [RFC5780](https://tools.ietf.org/html/rfc5780)
+    /// * `local_address`: Local transport address of the socket used for the tests. Used to detect the absence of a NAT.
+    ///   It must be the concrete address of the interface, as the mapped address is never equal to an unspecified address such as `0.0.0.0`.
+    pub fn new(server: SocketAddr, local_address: SocketAddr) -> Self {
+        let mut discovery = Self {
+            server,
+            local_address,
+            rto: StunClientTransaction::DEFAULT_RTO,
+            max_transmissions: StunClientTransaction::DEFAULT_MAX_TRANSMISSIONS,
+            pending: None,
+            mapped_address: None,
+            other_address: None,
+            alternate_mapped_address: None,
+            filtering: None,
+            result: None,
+        };
+
+        discovery.start_test(NatTest::Binding, server);
+
+        discovery
+    }
+
+    /// Sets the initial retransmission timeout of the test requests
+    pub fn set_rto(mut self, rto: Duration) -> Self {
+        self.rto = rto;
+
+        self
+    }
+
+    /// Sets the maximum amount of transmissions of each test request (Rc). At least one transmission is always done.
+    pub fn set_max_transmissions(mut self, max_transmissions: u32) -> Self {
+        self.max_transmissions = max_transmissions.max(1);
+
+        self
+    }
+
+    /// Returns the test in progress
+    pub fn get_current_test(&self) -> Option<NatTest> {
+        self.pending.as_ref().map(|pending| pending.test)
+    }
+
+    /// Returns the next (re)transmission of the request of the test in progress
+    ///
+    /// Arguments:
+    ///
+    /// * `now`: Current time, used to schedule the retransmissions
+    pub fn poll_transmit(&mut self, now: Instant) -> Option<NatDiscoveryTransmit> {
+        let (rto, max_transmissions) = (self.rto, self.max_transmissions);
+        let pending = self.pending.as_mut()?;
+
+        if pending.transaction.is_none() {
+            let mut request =
+                StunMessage::create_request().set_transaction_id(pending.transaction_id);
+            request = match pending.test {
+                NatTest::FilteringChangeAddressAndPort => {
+                    request.add_attribute(StunAttribute::ChangeRequest {
+                        change_ip: true,
+                        change_port: true,
+                    })
+                }
+                NatTest::FilteringChangePort => {
+                    request.add_attribute(StunAttribute::ChangeRequest {
+                        change_ip: false,
+                        change_port: true,
+                    })
+                }
+                _ => request,
+            };
+
+            match StunClientTransaction::new(&request, None, StunTransport::Unreliable, now) {
+                Ok(transaction) => {
+                    let mut transaction = transaction
+                        .set_rto(rto)
+                        .set_max_transmissions(max_transmissions);
+
+                    if let Some(StunTransactionEvent::Transmit(payload)) =
+                        transaction.handle_timeout(now)
+                    {
+                        pending.transmit = Some(payload);
+                    }
+                    pending.transaction = Some(transaction);
+                }
+                Err(err) => {
+                    self.finish(Err(err.into()));
+
+                    return None;
+                }
+            }
+        }
+
+        let payload = pending.transmit.take()?;
+
+        Some(NatDiscoveryTransmit {
+            destination: pending.destination,
+            payload,
+        })
+    }
+
+    /// Returns the instant `handle_timeout` has to be called at
+    pub fn poll_timeout(&self) -> Option<Instant> {
+        self.pending
+            .as_ref()
+            .and_then(|pending| pending.transaction.as_ref())
+            .and_then(|transaction| transaction.poll_timeout())
+    }
+
+    /// Handles the expiration of the retransmission timer of the test in progress
+    ///
+    /// Arguments:
+    ///
+    /// * `now`: Current time
+    pub fn handle_timeout(&mut self, now: Instant) {
+        let pending = match self.pending.as_mut() {
+            Some(pending) => pending,
+            None => return,
+        };
+        let test = pending.test;
+
+        match pending
+            .transaction
+            .as_mut()
+            .and_then(|transaction| transaction.handle_timeout(now))
+        {
+            Some(StunTransactionEvent::Transmit(payload)) => {
+                pending.transmit = Some(payload);
+
+                return;
+            }
+            Some(StunTransactionEvent::Timeout) => {}
+            _ => return,
+        }
+
+        self.pending = None;
+
+        match test {
+            // The server has to be reachable for the mapping tests
+            NatTest::Binding
+            | NatTest::MappingAlternateAddress
+            | NatTest::MappingAlternateAddressAndPort => {
+                self.finish(Err(NatDiscoveryError::Timeout(test)))
+            }
+            // Responses from the alternate IP address and port are filtered
+            NatTest::FilteringChangeAddressAndPort => {
+                self.start_test(NatTest::FilteringChangePort, self.server)
+            }
+            // Responses from the alternate port are filtered as well
+            NatTest::FilteringChangePort => {
+                self.finish_filtering(NatFilteringBehavior::AddressAndPortDependent)
+            }
+        }
+    }
+
+    /// Handles a datagram received on the test socket
+    ///
+    /// Returns `true` if the datagram is a response to the test in progress.
+    /// Other datagrams are ignored, including responses that don't come from the server address the test expects them from.
+    /// The only exception is a success response to a filtering test sent from the primary server address. It means that the server
+    /// doesn't honor the CHANGE-REQUEST attribute, so it can't tell anything about the NAT filtering behavior,
+    /// and the discovery fails with `NatDiscoveryError::ChangeRequestNotHonored`.
+    ///
+    /// Arguments:
+    ///
+    /// * `bytes`: Received datagram
+    /// * `source`: Transport address the datagram has been received from
+    pub fn handle_input(&mut self, bytes: &[u8], source: SocketAddr) -> bool {
+        let response = match StunMessageRef::decode(bytes, None) {
+            Ok(response) => response,
+            Err(_) => return false,
+        };
+
+        let header = response.get_header();
+        let test = match &self.pending {
+            Some(pending)
+                if pending.transaction.is_some()
+                    && pending.transaction_id == header.transaction_id
+                    && header.message_method == StunMessageMethod::BindingRequest =>
+            {
+                pending.test
+            }
+            _ => return false,
+        };
+
+        // The response has to come from the expected server address, as advertised by the server itself
+        let expected_source = self.pending.as_ref().map(|pending| pending.source);
+        let origin = response.get_response_origin().unwrap_or(source);
+        if Some(source) != expected_source || Some(origin) != expected_source {
+            let is_filtering_test = matches!(
+                test,
+                NatTest::FilteringChangeAddressAndPort | NatTest::FilteringChangePort
+            );
+
+            // Timing out instead would report the filtering behavior as address and port dependent
+            if is_filtering_test
+                && source == self.server
+                && origin == self.server
+                && header.message_class == StunMessageClass::SuccessResponse
+            {
+                self.pending = None;
+                self.finish(Err(NatDiscoveryError::ChangeRequestNotHonored(test)));
+
+                return true;
+            }
+
+            return false;
+        }
+
+        match header.message_class {
+            StunMessageClass::SuccessResponse => {}
+            StunMessageClass::ErrorResponse => {
+                let (class, number) = response
+                    .get_error_code()
+                    .map_or((0, 0), |(class, number, _)| (class, number));
+
+                self.pending = None;
+                self.finish(Err(NatDiscoveryError::ErrorResponse {
+                    test,
+                    class,
+                    number,
+                }));
+
+                return true;
+            }
+            _ => return false,
+        }
+
+        self.pending = None;
+
+        let mapped_address = match response
+            .get_xor_mapped_address()
+            .or_else(|| response.get_mapped_address())
+        {
+            Some(mapped_address) => mapped_address,
+            None => {
+                self.finish(Err(NatDiscoveryError::MissingMappedAddress(test)));
+
+                return true;
+            }
+        };
+
+        match test {
+            NatTest::Binding => {
+                let other_address = match response.get_other_address() {
+                    Some(other_address) => other_address,
+                    None => {
+                        self.finish(Err(NatDiscoveryError::MissingOtherAddress()));
+
+                        return true;
+                    }
+                };
+
+                self.mapped_address = Some(mapped_address);
+                self.other_address = Some(other_address);
+
+                self.start_test(NatTest::FilteringChangeAddressAndPort, self.server);
+            }
+            NatTest::MappingAlternateAddress => {
+                if Some(mapped_address) == self.mapped_address {
+                    self.finish_mapping(NatMappingBehavior::EndpointIndependent);
+                } else {
+                    self.alternate_mapped_address = Some(mapped_address);
+
+                    if let Some(other_address) = self.other_address {
+                        self.start_test(NatTest::MappingAlternateAddressAndPort, other_address);
+                    }
+                }
+            }
+            NatTest::MappingAlternateAddressAndPort => {
+                if Some(mapped_address) == self.alternate_mapped_address {
+                    self.finish_mapping(NatMappingBehavior::AddressDependent);
+                } else {
+                    self.finish_mapping(NatMappingBehavior::AddressAndPortDependent);
+                }
+            }
+            NatTest::FilteringChangeAddressAndPort => {
+                self.finish_filtering(NatFilteringBehavior::EndpointIndependent)
+            }
+            NatTest::FilteringChangePort => {
+                self.finish_filtering(NatFilteringBehavior::AddressDependent)
+            }
+        }
+
+        true
+    }
+
+    /// Returns the discovery result once all the tests are finished
+    ///
+    /// The result is handed out only once.
+    pub fn poll_report(&mut self) -> Option<Result<NatBehaviorReport, NatDiscoveryError>> {
+        self.result.take()
+    }
+
+    // Prepares the request for the next test
+    fn start_test(&mut self, test: NatTest, destination: SocketAddr) {
+        // The filtering tests ask the server to respond from its alternate address and/or port
+        let source = match (test, self.other_address) {
+            (NatTest::FilteringChangeAddressAndPort, Some(other_address)) => other_address,
+            (NatTest::FilteringChangePort, Some(other_address)) => {
+                SocketAddr::new(self.server.ip(), other_address.port())
+            }
+            _ => destination,
+        };
+
+        self.pending = Some(PendingTest {
+            test,
+            destination,
+            source,
+            transaction_id: crate::generate_transaction_id(),
+            transaction: None,
+            transmit: None,
+        });
+    }
+
+    // Stores the filtering behavior and proceeds to the mapping tests
+    fn finish_filtering(&mut self, filtering: NatFilteringBehavior) {
+        self.filtering = Some(filtering);
+
+        match (self.mapped_address, self.other_address) {
+            // No NAT, no need to run the mapping tests
+            (Some(mapped_address), _) if mapped_address == self.local_address => {
+                self.finish_mapping(NatMappingBehavior::EndpointIndependent)
+            }
+            (_, Some(other_address)) => {
+                let destination = SocketAddr::new(other_address.ip(), self.server.port());
+                self.start_test(NatTest::MappingAlternateAddress, destination);
+            }
+            _ => {}
+        }
+    }
+
+    // Stores the mapping behavior and builds the report
+    fn finish_mapping(&mut self, mapping: NatMappingBehavior) {
+        if let (Some(mapped_address), Some(other_address), Some(filtering)) =
+            (self.mapped_address, self.other_address, self.filtering)
+        {
+            self.finish(Ok(NatBehaviorReport {
+                local_address: self.local_address,
+                mapped_address,
+                other_address,
+                behind_nat: mapped_address != self.local_address,
+                mapping,
+                filtering,
+            }));
+        }
+    }
+
+    // Stops the discovery with the provided result
+    fn finish(&mut self, result: Result<NatBehaviorReport, NatDiscoveryError>) {
+        self.pending = None;
+        self.result = Some(result);
+    }
+}
@@ -0,0 +1,34 @@
+use thiserror::Error;
+
+use super::NatTest;
+use crate::MessageEncodeError;
+
+/// NAT behavior discovery errors.
+#[derive(Error, Debug)]
+pub enum NatDiscoveryError {
+    /// Failed to encode a Binding request.
+    #[error("Failed to encode the Binding request.")]
+    RequestEncodeFailure(#[from] MessageEncodeError),
+    /// No response has been received for a test that requires one.
+    #[error("No response received for the {0:?} test.")]
+    Timeout(NatTest),
+    /// The server returned an error response.
+    #[error("Error response received for the {test:?} test: {class}{number:02}.")]
+    ErrorResponse {
+        /// Test the error response has been received for
+        test: NatTest,
+        /// Error class
+        class: u8,
+        /// Error number
+        number: u8,
+    },
+    /// The Binding response contains neither a XOR-MAPPED-ADDRESS nor a MAPPED-ADDRESS attribute.
+    #[error("The Binding response of the {0:?} test contains no mapped address.")]
+    MissingMappedAddress(NatTest),
+    /// The Binding response contains no OTHER-ADDRESS attribute, meaning the server doesn't support NAT behavior discovery.
+    #[error("The server doesn't support NAT behavior discovery: no OTHER-ADDRESS attribute in the Binding response.")]
+    MissingOtherAddress(),
+    /// The server responded to a filtering test from its primary address, ignoring the CHANGE-REQUEST attribute.
+    #[error("The server doesn't honor the CHANGE-REQUEST attribute of the {0:?} test.")]
+    ChangeRequestNotHonored(NatTest),
+}
@@ -0,0 +1,7 @@
+mod discovery;
+mod errors;
+mod report;
+
+pub use discovery::{NatBehaviorDiscovery, NatDiscoveryTransmit, NatTest};
+pub use errors::NatDiscoveryError;
+pub use report::{NatBehaviorReport, NatFilteringBehavior, NatMappingBehavior};
@@ -0,0 +1,40 @@
+use std::net::SocketAddr;
+
+/// [NAT mapping behavior](https://tools.ietf.org/html/rfc4787#section-4.1)
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum NatMappingBehavior {
+    /// The NAT reuses the port mapping for subsequent packets sent from the same internal IP address and port to any external IP address and port.
+    EndpointIndependent,
+    /// The NAT reuses the port mapping for subsequent packets sent from the same internal IP address and port to the same external IP address, regardless of the external port.
+    AddressDependent,
+    /// The NAT reuses the port mapping for subsequent packets sent from the same internal IP address and port to the same external IP address and port while the mapping is still active.
+    AddressAndPortDependent,
+}
+
+/// [NAT filtering behavior](https://tools.ietf.org/html/rfc4787#section-5)
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum NatFilteringBehavior {
+    /// The NAT filters out only packets not destined to the internal address and port, regardless of the external IP address and port source.
+    EndpointIndependent,
+    /// The NAT filters out packets not destined to the internal address and port, or packets from an external IP address the internal endpoint didn't send packets to.
+    AddressDependent,
+    /// The NAT filters out packets not destined to the internal address and port, or packets from an external IP address and port the internal endpoint didn't send packets to.
+    AddressAndPortDependent,
+}
+
+/// Result of the [RFC5780](https://tools.ietf.org/html/rfc5780#section-4) NAT behavior discovery
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct NatBehaviorReport {
+    /// Local transport address the tests were run from
+    pub local_address: SocketAddr,
+    /// Server reflexive transport address learned via the first Binding request
+    pub mapped_address: SocketAddr,
+    /// Alternate server transport address learned via the OTHER-ADDRESS attribute
+    pub other_address: SocketAddr,
+    /// Whether the mapped address differs from the local one
+    pub behind_nat: bool,
+    /// Mapping behavior. Endpoint-independent if there is no NAT.
+    pub mapping: NatMappingBehavior,
+    /// Filtering behavior
+    pub filtering: NatFilteringBehavior,
+}
@@ -0,0 +1,301 @@
+use std::net::SocketAddr;
+
+use super::credential_store::CredentialStore;
+use super::errors::AuthenticationError;
+use super::nonce_source::{InMemoryNonceSource, NonceSource};
+use crate::{
+    check_for_stun_message_header, AttributeDecodeError, MessageDecodeError, StunHeader,
+    StunMessage, StunMessageClass, StunMessageRef, StunPasswordAlgorithm,
+};
+
+/// Outcome of the authentication of a request
+#[derive(Debug)]
+pub enum StunAuthentication<'a> {
+    /// The request carries valid credentials
+    Authenticated {
+        /// Verified request
+        request: StunMessageRef<'a>,
+        /// Long-term key of the user. The response has to be protected with it via `encode_with_key`.
+        key: Vec<u8>,
+    },
+    /// The request has been rejected. The encoded error response has to be sent back to the client.
+    Rejected(Vec<u8>),
+    /// The message is an indication or a response. It must not be answered, as per
+    /// [RFC5389 Section 7.3](https://tools.ietf.org/html/rfc5389#section-7.3).
+    Ignored,
+}
+
+/// Server side of the [RFC5389 long-term credential mechanism](https://tools.ietf.org/html/rfc5389#section-10.2.2)
+///
+/// Checks the credentials of the received requests against the keys of a [`CredentialStore`]:
+///
+/// * Requests without a MESSAGE-INTEGRITY or MESSAGE-INTEGRITY-SHA256 attribute are challenged with a 401 (Unauthorized) error response carrying the REALM and a fresh NONCE.
+/// * Requests with an integrity attribute but without the USERNAME, REALM or NONCE attributes,
+///   or with a PASSWORD-ALGORITHM attribute selecting an unsupported algorithm, are rejected with a 400 (Bad Request) error response.
+/// * Requests with an expired or unknown nonce are rejected with a 438 (Stale Nonce) error response carrying a fresh NONCE.
+/// * Requests for another realm, of unknown users, or failing the integrity check, are rejected with a 401 (Unauthorized) error response.
+///
+/// The key is looked up for the password algorithm selected by the PASSWORD-ALGORITHM attribute of the request, MD5 if it is absent.
+/// The same key verifies both the MESSAGE-INTEGRITY and the MESSAGE-INTEGRITY-SHA256 attributes.
+///
+/// The nonces are issued and validated by a [`NonceSource`]. By default, they are kept in memory until they expire.
+///
+/// ```
+/// # use std::net::SocketAddr;
+/// # use stun_coder::{InMemoryCredentialStore, LongTermAuthenticator, StunAuthentication, StunMessage, StunMessageRef};
+/// let mut store = InMemoryCredentialStore::new();
+/// store.add_user("user", "example.org", "pass").unwrap();
+///
+/// let mut authenticator = LongTermAuthenticator::new(store, "example.org");
+/// let client: SocketAddr = "192.0.2.1:32853".parse().unwrap();
+///
+/// // The first request is challenged
+/// let request = StunMessage::create_request().encode(None).unwrap();
+/// let challenge = match authenticator.authenticate(&request, client).unwrap() {
+///     StunAuthentication::Rejected(response) => response,
+///     _ => unreachable!(),
+/// };
+///
+/// let challenge = StunMessageRef::decode(&challenge, None).unwrap();
+/// assert_eq!(challenge.get_error_code(), Some((4, 1, "Unauthorized")));
+///
+/// // The retried request is authenticated
+/// let request = StunMessage::create_request()
+///     .add_long_term_credential_message_integrity(
+///         "user",
+///         challenge.get_realm().unwrap(),
+///         challenge.get_nonce().unwrap(),
+///     )
+///     .unwrap()
+///     .encode(Some("pass"))
+///     .unwrap();
+///
+/// match authenticator.authenticate(&request, client).unwrap() {
+///     StunAuthentication::Authenticated { request, key } => {
+///         let response = StunMessage::create_response_to(
+///             request.get_header(),
+///             stun_coder::StunMessageClass::SuccessResponse,
+///         )
+///         .add_message_integrity()
+///         .encode_with_key(Some(&key))
+///         .unwrap();
+///     }
+///     _ => unreachable!(),
+/// }
+/// ```
+pub struct LongTermAuthenticator<S: CredentialStore, N: NonceSource = InMemoryNonceSource> {
+    /// Source of the long-term keys
+    store: S,
+    /// Realm of the server
+    realm: String,
+    /// Issuer and validator of the nonces
+    nonces: N,
+}
+
+impl<S: CredentialStore> LongTermAuthenticator<S, InMemoryNonceSource> {
+    /// Creates an authenticator keeping the issued nonces in memory
+    ///
+    /// Arguments:
+    ///
+    /// * `store`: Source of the long-term keys
+    /// * `realm`: Realm of the server
+    pub fn new(store: S, realm: &str) -> Self {
+        Self::with_nonce_source(store, realm, InMemoryNonceSource::new())
+    }
+}
+
+impl<S: CredentialStore, N: NonceSource> LongTermAuthenticator<S, N> {
+    /// Creates an authenticator using the provided nonce source
+    ///
+    /// Arguments:
+    ///
+    /// * `store`: Source of the long-term keys
+    /// * `realm`: Realm of the server
+    /// * `nonces`: Issuer and validator of the nonces
+    pub fn with_nonce_source(store: S, realm: &str, nonces: N) -> Self {
+        Self {
+            store,
+            realm: String::from(realm),
+            nonces,
+        }
+    }
+
+    /// Returns the realm of the server
+    pub fn get_realm(&self) -> &str {
+        &self.realm
+    }
+
+    /// Returns an immutable reference to the credential store
+    pub fn get_store(&self) -> &S {
+        &self.store
+    }
+
+    /// Returns a mutable reference to the credential store
+    pub fn get_store_mut(&mut self) -> &mut S {
+        &mut self.store
+    }
+
+    /// Returns an immutable reference to the nonce source
+    pub fn get_nonce_source(&self) -> &N {
+        &self.nonces
+    }
+
+    /// Returns a mutable reference to the nonce source
+    pub fn get_nonce_source_mut(&mut self) -> &mut N {
+        &mut self.nonces
+    }
+
+    /// Authenticates a received request
+    ///
+    /// Returns the verified request along with the key of the user, or the error response to send back.
+    /// Messages other than requests are ignored, as answering them would allow reflection loops between agents.
+    ///
+    /// Arguments:
+    ///
+    /// * `bytes`: Received request
+    /// * `source`: Transport address the request has been received from
+    pub fn authenticate<'a>(
+        &mut self,
+        bytes: &'a [u8],
+        source: SocketAddr,
+    ) -> Result<StunAuthentication<'a>, AuthenticationError> {
+        let request = match StunMessageRef::decode(bytes, None) {
+            Ok(request) => request,
+            // A truncated MESSAGE-INTEGRITY value fails the integrity check
+            Err(
+                err @ MessageDecodeError::AttributeDecodeFailure {
+                    source: AttributeDecodeError::InvalidIntegrityLength(_),
+                    ..
+                },
+            ) => {
+                return match check_for_stun_message_header(bytes) {
+                    Some(header) if header.message_class == StunMessageClass::Request => {
+                        self.reject(&header, false, source, 1, "Unauthorized")
+                    }
+                    Some(_) => Ok(StunAuthentication::Ignored),
+                    None => Err(err.into()),
+                };
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        if request.get_header().message_class != StunMessageClass::Request {
+            return Ok(StunAuthentication::Ignored);
+        }
+
+        if request.get_message_integrity().is_none()
+            && request.get_message_integrity_sha256().is_none()
+        {
+            return self.reject(
+                request.get_header(),
+                request.has_fingerprint(),
+                source,
+                1,
+                "Unauthorized",
+            );
+        }
+
+        let (username, realm, nonce) = match (
+            request.get_username(),
+            request.get_realm(),
+            request.get_nonce(),
+        ) {
+            (Some(username), Some(realm), Some(nonce)) => (username, realm, nonce),
+            _ => return Self::reject_bad_request(request.get_header()),
+        };
+
+        let algorithm = request.get_password_algorithm().unwrap_or_default();
+        if let StunPasswordAlgorithm::Other(_) = algorithm {
+            return Self::reject_bad_request(request.get_header());
+        }
+
+        // Keys of other realms the store may hold must not be usable here
+        if realm != self.realm {
+            return self.reject(
+                request.get_header(),
+                request.has_fingerprint(),
+                source,
+                1,
+                "Unauthorized",
+            );
+        }
+
+        if !self.nonces.validate_nonce(nonce, source) {
+            return self.reject(
+                request.get_header(),
+                request.has_fingerprint(),
+                source,
+                38,
+                "Stale Nonce",
+            );
+        }
+
+        let key = match self.store.get_key(username, realm, algorithm) {
+            Some(key) => key,
+            None => {
+                return self.reject(
+                    request.get_header(),
+                    request.has_fingerprint(),
+                    source,
+                    1,
+                    "Unauthorized",
+                )
+            }
+        };
+
+        match StunMessageRef::decode_with_key(bytes, Some(&key)) {
+            Ok(request) => Ok(StunAuthentication::Authenticated { request, key }),
+            Err(MessageDecodeError::MessageIntegrityFail { .. }) => self.reject(
+                request.get_header(),
+                request.has_fingerprint(),
+                source,
+                1,
+                "Unauthorized",
+            ),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Issues a fresh nonce, for instance to challenge a client proactively
+    ///
+    /// Arguments:
+    ///
+    /// * `client`: Transport address of the client the nonce is issued to
+    pub fn create_nonce(&mut self, client: SocketAddr) -> String {
+        self.nonces.create_nonce(client)
+    }
+
+    // Creates a 400 (Bad Request) error response
+    fn reject_bad_request<'a>(
+        request_header: &StunHeader,
+    ) -> Result<StunAuthentication<'a>, AuthenticationError> {
+        let response =
+            StunMessage::create_response_to(request_header, StunMessageClass::ErrorResponse)
+                .add_error_code(4, 0, "Bad Request");
+
+        Ok(StunAuthentication::Rejected(response.encode(None)?))
+    }
+
+    // Creates an error response carrying the realm and a fresh nonce
+    fn reject<'a>(
+        &mut self,
+        request_header: &StunHeader,
+        fingerprint: bool,
+        client: SocketAddr,
+        number: u8,
+        reason: &str,
+    ) -> Result<StunAuthentication<'a>, AuthenticationError> {
+        let nonce = self.nonces.create_nonce(client);
+
+        let mut response =
+            StunMessage::create_response_to(request_header, StunMessageClass::ErrorResponse)
+                .add_error_code(4, number, reason)
+                .add_long_term_credential_challenge(&self.realm, &nonce);
+
+        if fingerprint {
+            response = response.add_fingerprint();
+        }
+
+        Ok(StunAuthentication::Rejected(response.encode(None)?))
+    }
+}
@@ -0,0 +1,154 @@
+use std::net::SocketAddr;
+
+use crate::message::DecodeOptions;
+use crate::{
+    MessageEncodeError, StunAttribute, StunDialect, StunMessage, StunMessageClass,
+    StunMessageMethod, StunMessageRef,
+};
+
+/// Sans-IO [RFC5389](https://tools.ietf.org/html/rfc5389#section-7.3) Binding request responder
+///
+/// Takes a received datagram along with its source transport address and returns the encoded response to send back to the source:
+///
+/// * Binding requests are answered with a success response carrying the XOR-MAPPED-ADDRESS attribute,
+///   and optionally the MAPPED-ADDRESS attribute for clients that don't understand the former.
+/// * Binding requests containing unknown comprehension-required attributes are answered with
+///   a 420 (Unknown Attribute) error response listing them in the UNKNOWN-ATTRIBUTES attribute.
+/// * Indications, responses, requests of other methods and malformed messages are ignored.
+///
+/// The response echoes the transaction ID of the request. If the request carries a FINGERPRINT attribute,
+/// so does the response.
+///
+/// ```no_run
+/// use std::net::UdpSocket;
+/// use stun_coder::StunBindingResponder;
+///
+/// let socket = UdpSocket::bind("0.0.0.0:3478").unwrap();
+/// let responder = StunBindingResponder::new().set_software("rust-stun-coder");
+/// let mut buf = [0u8; 1500];
+///
+/// loop {
+///     let (len, source) = socket.recv_from(&mut buf).unwrap();
+///
+///     if let Ok(Some(response)) = responder.handle_request(&buf[..len], source) {
+///         socket.send_to(&response, source).unwrap();
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct StunBindingResponder {
+    /// Description added to the responses via the SOFTWARE attribute
+    software: Option<String>,
+    /// Add the MAPPED-ADDRESS attribute to the success responses
+    mapped_address: bool,
+    /// Answer classic RFC3489 requests
+    legacy_requests: bool,
+}
+
+impl StunBindingResponder {
+    /// Creates a new responder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the description added to the responses via the SOFTWARE attribute
+    pub fn set_software(mut self, description: &str) -> Self {
+        self.software = Some(String::from(description));
+
+        self
+    }
+
+    /// Adds the MAPPED-ADDRESS attribute to the success responses, in addition to the XOR-MAPPED-ADDRESS attribute
+    pub fn set_mapped_address(mut self, enabled: bool) -> Self {
+        self.mapped_address = enabled;
+
+        self
+    }
+
+    /// Answers classic [RFC3489](https://tools.ietf.org/html/rfc3489) requests, that don't carry the magic cookie
+    ///
+    /// As per [RFC5389 Section 12.2](https://tools.ietf.org/html/rfc5389#section-12.2) the responses to such requests
+    /// use the 128-bit transaction ID of the request and carry the MAPPED-ADDRESS attribute instead of XOR-MAPPED-ADDRESS.
+    /// As any datagram starting with two zero bits passes as a classic request, this is not suitable for
+    /// sockets shared with other protocols.
+    pub fn set_legacy_requests(mut self, enabled: bool) -> Self {
+        self.legacy_requests = enabled;
+
+        self
+    }
+
+    /// Handles a received datagram
+    ///
+    /// Returns the encoded response, or `None` if the datagram has to be ignored.
+    ///
+    /// Arguments:
+    ///
+    /// * `bytes`: Received datagram
+    /// * `source`: Transport address the datagram has been received from
+    pub fn handle_request(
+        &self,
+        bytes: &[u8],
+        source: SocketAddr,
+    ) -> Result<Option<Vec<u8>>, MessageEncodeError> {
+        let request = match StunMessageRef::decode_with_credential(
+            bytes,
+            None,
+            DecodeOptions {
+                allow_legacy: self.legacy_requests,
+                allow_unknown_required: true,
+            },
+        ) {
+            Ok(request) => request,
+            Err(_) => return Ok(None),
+        };
+
+        let header = request.get_header();
+        if header.message_class != StunMessageClass::Request
+            || header.message_method != StunMessageMethod::BindingRequest
+        {
+            return Ok(None);
+        }
+
+        let is_legacy = header.dialect != StunDialect::Rfc5389;
+        let unknown_attributes = request.get_unknown_required_attributes();
+
+        let mut response = if !unknown_attributes.is_empty() {
+            StunMessage::create_response_to(header, StunMessageClass::ErrorResponse)
+                .add_error_code(4, 20, "Unknown Attribute")
+                .add_attribute(StunAttribute::UnknownAttributes {
+                    types: unknown_attributes,
+                })
+        } else if is_legacy {
+            StunMessage::create_response_to(header, StunMessageClass::SuccessResponse)
+                .add_attribute(StunAttribute::MappedAddress {
+                    socket_addr: source,
+                })
+        } else {
+            let mut response =
+                StunMessage::create_response_to(header, StunMessageClass::SuccessResponse)
+                    .add_attribute(StunAttribute::XorMappedAddress {
+                        socket_addr: source,
+                    });
+
+            if self.mapped_address {
+                response = response.add_attribute(StunAttribute::MappedAddress {
+                    socket_addr: source,
+                });
+            }
+
+            response
+        };
+
+        if let Some(software) = &self.software {
+            response = response.add_attribute(StunAttribute::Software {
+                description: software.clone(),
+            });
+        }
+
+        if request.has_fingerprint() && !is_legacy {
+            response = response.add_fingerprint();
+        }
+
+        Ok(Some(response.encode(None)?))
+    }
+}
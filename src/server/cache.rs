@@ -0,0 +1,240 @@
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+use super::clock::{StunClock, StunSystemClock};
+use crate::check_for_stun_message_header;
+use crate::definitions::StunTransactionId;
+
+/// Key identifying a request: the client transport address and the transaction ID
+type CacheKey = (SocketAddr, StunTransactionId);
+
+/// Cache of the responses sent by a server, as per [RFC5389 Section 7.3.1](https://tools.ietf.org/html/rfc5389#section-7.3.1)
+///
+/// A server must answer a retransmitted request with the same response it sent to the original request,
+/// rather than processing the request again. This matters for non-idempotent requests, such as TURN Allocate,
+/// that would fail or have side effects when executed twice.
+///
+/// Responses are keyed by the client transport address and the transaction ID of the request.
+/// They expire after the configured lifetime, 40 seconds by default, covering the whole retransmission schedule of a client.
+/// Once the capacity is reached, the oldest responses are evicted first.
+///
+/// ```
+/// # use stun_coder::{StunBindingResponder, StunMessage, StunResponseCache};
+/// let responder = StunBindingResponder::new();
+/// let mut cache = StunResponseCache::new();
+///
+/// let source = "192.0.2.1:32853".parse().unwrap();
+/// let request = StunMessage::create_request().encode(None).unwrap();
+///
+/// // Process the request only if it isn't a retransmission
+/// let response = match cache.get_for_request(source, &request) {
+///     Some(response) => response.to_vec(),
+///     None => {
+///         let response = responder.handle_request(&request, source).unwrap().unwrap();
+///         cache.insert_for_request(source, &request, response.clone());
+///
+///         response
+///     }
+/// };
+///
+/// // The retransmitted request gets the same response
+/// assert_eq!(cache.get_for_request(source, &request), Some(&response[..]));
+/// ```
+pub struct StunResponseCache<C: StunClock = StunSystemClock> {
+    /// Source of the current time
+    clock: C,
+    /// Maximum amount of cached responses
+    capacity: usize,
+    /// Time a response is kept for
+    lifetime: Duration,
+    /// Cached responses along with their expiration time
+    responses: HashMap<CacheKey, (Vec<u8>, Instant)>,
+    /// Keys in insertion order, which is also the expiration order. Holds exactly one entry per cached response.
+    order: VecDeque<(CacheKey, Instant)>,
+}
+
+impl StunResponseCache<StunSystemClock> {
+    /// Creates a cache using the system clock
+    pub fn new() -> Self {
+        Self::with_clock(StunSystemClock)
+    }
+}
+
+impl Default for StunResponseCache<StunSystemClock> {
+    /// Creates a cache using the system clock
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: StunClock> StunResponseCache<C> {
+    /// Default maximum amount of cached responses
+    pub const DEFAULT_CAPACITY: usize = 1024;
+    /// Default time a response is kept for
+    pub const DEFAULT_LIFETIME: Duration = Duration::from_secs(40);
+
+    /// Creates a cache using the provided clock
+    pub fn with_clock(clock: C) -> Self {
+        Self {
+            clock,
+            capacity: Self::DEFAULT_CAPACITY,
+            lifetime: Self::DEFAULT_LIFETIME,
+            responses: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Sets the maximum amount of cached responses
+    pub fn set_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+
+        self
+    }
+
+    /// Sets the time a response is kept for
+    pub fn set_lifetime(mut self, lifetime: Duration) -> Self {
+        self.lifetime = lifetime;
+
+        self
+    }
+
+    /// Returns the amount of cached responses, including the expired ones that haven't been purged yet
+    pub fn len(&self) -> usize {
+        self.responses.len()
+    }
+
+    /// Checks whether no responses are cached
+    pub fn is_empty(&self) -> bool {
+        self.responses.is_empty()
+    }
+
+    /// Caches the response to a request
+    ///
+    /// Arguments:
+    ///
+    /// * `client`: Transport address the request has been received from
+    /// * `transaction_id`: Transaction ID of the request
+    /// * `response`: Encoded response
+    pub fn insert(
+        &mut self,
+        client: SocketAddr,
+        transaction_id: StunTransactionId,
+        response: Vec<u8>,
+    ) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let now = self.clock.now();
+        self.purge_at(now);
+
+        let key = (client, transaction_id);
+        let expires_at = now + self.lifetime;
+
+        if self.responses.contains_key(&key) {
+            // The replaced response moves to the back of the queue
+            self.remove_from_order(&key);
+        } else {
+            // Evict the oldest responses to make room for the new one
+            while self.responses.len() >= self.capacity {
+                match self.order.pop_front() {
+                    Some((oldest, _)) => {
+                        self.responses.remove(&oldest);
+                    }
+                    None => break,
+                }
+            }
+        }
+
+        self.responses.insert(key, (response, expires_at));
+        self.order.push_back((key, expires_at));
+    }
+
+    /// Caches the response to the provided encoded request
+    ///
+    /// Nothing is cached if the request doesn't start with a valid STUN header.
+    ///
+    /// Arguments:
+    ///
+    /// * `client`: Transport address the request has been received from
+    /// * `request`: Encoded request
+    /// * `response`: Encoded response
+    pub fn insert_for_request(&mut self, client: SocketAddr, request: &[u8], response: Vec<u8>) {
+        if let Some(header) = check_for_stun_message_header(request) {
+            self.insert(client, header.transaction_id, response);
+        }
+    }
+
+    /// Returns the cached response to a request, unless it has expired
+    ///
+    /// Arguments:
+    ///
+    /// * `client`: Transport address the request has been received from
+    /// * `transaction_id`: Transaction ID of the request
+    pub fn get(&self, client: SocketAddr, transaction_id: &StunTransactionId) -> Option<&[u8]> {
+        let now = self.clock.now();
+
+        match self.responses.get(&(client, *transaction_id)) {
+            Some((response, expires_at)) if *expires_at > now => Some(response.as_slice()),
+            _ => None,
+        }
+    }
+
+    /// Returns the cached response to the provided encoded request, unless it has expired
+    ///
+    /// Arguments:
+    ///
+    /// * `client`: Transport address the request has been received from
+    /// * `request`: Encoded request
+    pub fn get_for_request(&self, client: SocketAddr, request: &[u8]) -> Option<&[u8]> {
+        let header = check_for_stun_message_header(request)?;
+
+        self.get(client, &header.transaction_id)
+    }
+
+    /// Removes the cached response to a request
+    ///
+    /// Arguments:
+    ///
+    /// * `client`: Transport address the request has been received from
+    /// * `transaction_id`: Transaction ID of the request
+    pub fn remove(
+        &mut self,
+        client: SocketAddr,
+        transaction_id: &StunTransactionId,
+    ) -> Option<Vec<u8>> {
+        let key = (client, *transaction_id);
+        let (response, _) = self.responses.remove(&key)?;
+        self.remove_from_order(&key);
+
+        Some(response)
+    }
+
+    /// Removes the expired responses
+    pub fn purge(&mut self) {
+        let now = self.clock.now();
+
+        self.purge_at(now);
+    }
+
+    // Removes the responses expired at the provided time
+    fn purge_at(&mut self, now: Instant) {
+        while let Some((_, expires_at)) = self.order.front() {
+            if *expires_at > now {
+                break;
+            }
+
+            if let Some((key, _)) = self.order.pop_front() {
+                self.responses.remove(&key);
+            }
+        }
+    }
+
+    // Removes the queue entry of a response
+    fn remove_from_order(&mut self, key: &CacheKey) {
+        if let Some(index) = self.order.iter().position(|(queued, _)| queued == key) {
+            self.order.remove(index);
+        }
+    }
+}
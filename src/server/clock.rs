@@ -0,0 +1,19 @@
+use std::time::Instant;
+
+/// Source of the current time
+///
+/// Allows replacing the system clock, e.g. to test time-dependent behavior deterministically.
+pub trait StunClock {
+    /// Returns the current time
+    fn now(&self) -> Instant;
+}
+
+/// Clock returning the system time via `Instant::now`
+#[derive(Debug, Copy, Clone, Default)]
+pub struct StunSystemClock;
+
+impl StunClock for StunSystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+
+use stringprep::saslprep;
+
+use crate::{IntegrityKeyGenerationError, StunMessage, StunPasswordAlgorithm};
+
+/// Source of the long-term credential keys used by a [`LongTermAuthenticator`](crate::LongTermAuthenticator)
+///
+/// Storing the keys rather than the passwords allows the server to verify the requests without knowing the passwords,
+/// as per [RFC5389 Section 10.2](https://tools.ietf.org/html/rfc5389#section-10.2).
+pub trait CredentialStore {
+    /// Returns the long-term key of the user, or `None` if the user or the password algorithm is unknown
+    ///
+    /// The key is derived from `username ":" realm ":" SASLprep(password)` with the password algorithm,
+    /// see [`calculate_long_term_key`](StunMessage::calculate_long_term_key).
+    ///
+    /// Arguments:
+    ///
+    /// * `username`: USERNAME attribute value of the request
+    /// * `realm`: REALM attribute value of the request
+    /// * `algorithm`: Password algorithm selected by the PASSWORD-ALGORITHM attribute of the request. MD5 if it is absent.
+    fn get_key(
+        &self,
+        username: &str,
+        realm: &str,
+        algorithm: StunPasswordAlgorithm,
+    ) -> Option<Vec<u8>>;
+}
+
+/// Credential store keeping the long-term keys in memory
+///
+/// ```
+/// # use stun_coder::{CredentialStore, InMemoryCredentialStore, StunPasswordAlgorithm};
+/// let mut store = InMemoryCredentialStore::new();
+/// store.add_user("user", "example.org", "pass").unwrap();
+///
+/// assert!(store.get_key("user", "example.org", StunPasswordAlgorithm::Md5).is_some());
+/// assert!(store.get_key("user", "example.org", StunPasswordAlgorithm::Sha256).is_some());
+/// assert!(store.get_key("user", "example.com", StunPasswordAlgorithm::Md5).is_none());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryCredentialStore {
+    /// Long-term keys of every password algorithm, keyed by username and realm
+    keys: HashMap<(String, String), HashMap<StunPasswordAlgorithm, Vec<u8>>>,
+}
+
+impl InMemoryCredentialStore {
+    /// Creates an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a user, computing its MD5 and SHA-256 long-term keys from the password
+    ///
+    /// Arguments:
+    ///
+    /// * `username`: Username. Processed via SASLprep.
+    /// * `realm`: Realm. Processed via SASLprep.
+    /// * `password`: Long-term password
+    pub fn add_user(
+        &mut self,
+        username: &str,
+        realm: &str,
+        password: &str,
+    ) -> Result<(), IntegrityKeyGenerationError> {
+        let username = saslprep(username)?.to_string();
+        let realm = saslprep(realm)?.to_string();
+
+        let mut keys = HashMap::new();
+        for algorithm in [StunPasswordAlgorithm::Md5, StunPasswordAlgorithm::Sha256].iter() {
+            let key =
+                StunMessage::calculate_long_term_key(&username, &realm, password, *algorithm)?;
+            keys.insert(*algorithm, key);
+        }
+
+        self.keys.insert((username, realm), keys);
+
+        Ok(())
+    }
+
+    /// Adds a precomputed long-term key of a user
+    ///
+    /// The keys of the other password algorithms the user may have are kept.
+    ///
+    /// Arguments:
+    ///
+    /// * `username`: USERNAME attribute value the user authenticates with
+    /// * `realm`: REALM attribute value the user authenticates with
+    /// * `algorithm`: Password algorithm the key has been derived with
+    /// * `key`: Long-term key
+    pub fn add_key(
+        &mut self,
+        username: &str,
+        realm: &str,
+        algorithm: StunPasswordAlgorithm,
+        key: Vec<u8>,
+    ) {
+        self.keys
+            .entry((String::from(username), String::from(realm)))
+            .or_default()
+            .insert(algorithm, key);
+    }
+
+    /// Removes a user along with all its keys. Returns `true` if the user was known.
+    pub fn remove_user(&mut self, username: &str, realm: &str) -> bool {
+        self.keys
+            .remove(&(String::from(username), String::from(realm)))
+            .is_some()
+    }
+}
+
+impl CredentialStore for InMemoryCredentialStore {
+    fn get_key(
+        &self,
+        username: &str,
+        realm: &str,
+        algorithm: StunPasswordAlgorithm,
+    ) -> Option<Vec<u8>> {
+        self.keys
+            .get(&(String::from(username), String::from(realm)))
+            .and_then(|keys| keys.get(&algorithm))
+            .cloned()
+    }
+}
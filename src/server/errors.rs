@@ -0,0 +1,32 @@
+use thiserror::Error;
+
+use crate::{MessageDecodeError, MessageEncodeError};
+
+/// Server-side authentication errors.
+#[derive(Error, Debug)]
+pub enum AuthenticationError {
+    /// Failed to decode the request. Malformed requests are silently discarded.
+    #[error("Failed to decode the request.")]
+    RequestDecodeFailure(#[from] MessageDecodeError),
+    /// Failed to encode the error response.
+    #[error("Failed to encode the error response.")]
+    ResponseEncodeFailure(#[from] MessageEncodeError),
+}
+
+/// Stateless nonce validation errors.
+#[derive(Error, Debug)]
+pub enum NonceError {
+    /// The nonce doesn't start with the nonce cookie and the security feature set.
+    #[error("Missing nonce cookie.")]
+    MissingCookie(),
+    /// The signed part of the nonce is not a valid base64-encoded timestamp and HMAC.
+    #[error("Invalid nonce encoding.")]
+    InvalidEncoding(),
+    /// The HMAC of the nonce doesn't match.
+    /// Either the nonce has been issued for another client address or with another key, or it has been tampered with.
+    #[error("Failed to authenticate the nonce.")]
+    AuthenticationFailure(),
+    /// The lifetime of the nonce has elapsed.
+    #[error("The nonce has expired.")]
+    Expired(),
+}
@@ -0,0 +1,17 @@
+mod authenticator;
+mod binding;
+mod cache;
+mod clock;
+mod credential_store;
+mod errors;
+mod nonce;
+mod nonce_source;
+
+pub use authenticator::{LongTermAuthenticator, StunAuthentication};
+pub use binding::StunBindingResponder;
+pub use cache::StunResponseCache;
+pub use clock::{StunClock, StunSystemClock};
+pub use credential_store::{CredentialStore, InMemoryCredentialStore};
+pub use errors::{AuthenticationError, NonceError};
+pub use nonce::{StatelessNonce, StunSecurityFeatures};
+pub use nonce_source::{InMemoryNonceSource, NonceSource};
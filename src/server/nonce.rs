@@ -0,0 +1,259 @@
+use crypto::hmac::Hmac;
+use crypto::mac::Mac;
+use crypto::sha1::Sha1;
+use crypto::util::fixed_time_eq;
+use std::convert::TryInto;
+use std::net::{IpAddr, SocketAddr};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::errors::NonceError;
+use super::nonce_source::NonceSource;
+
+/// Prefix of the nonces advertising the security features, as per [RFC8489 Section 9.2](https://tools.ietf.org/html/rfc8489#section-9.2)
+const NONCE_COOKIE: &str = "obMatJos2";
+
+/// Length of the base64-encoded security feature set following the nonce cookie
+const SECURITY_FEATURES_SIZE: usize = 4;
+/// Length of the truncated HMAC-SHA1 signing the nonce
+const NONCE_MAC_SIZE: usize = 16;
+/// Length of the timestamp and the HMAC before the base64 encoding
+const SIGNED_PART_SIZE: usize = 8 + NONCE_MAC_SIZE;
+
+/// [RFC8489 security feature set](https://tools.ietf.org/html/rfc8489#section-18.1) carried by the nonce cookie
+///
+/// The 24 feature bits are base64-encoded into 4 characters following the nonce cookie.
+///
+///```
+/// use stun_coder::StunSecurityFeatures;
+///
+/// let features = StunSecurityFeatures {
+///     password_algorithms: true,
+///     username_anonymity: false,
+/// };
+///
+/// assert_eq!(features.encode(), "obMatJos2gAAA");
+/// assert_eq!(StunSecurityFeatures::from_nonce("obMatJos2gAAA-rest"), Some(features));
+/// assert_eq!(StunSecurityFeatures::from_nonce("f//499k954d6OL34oL9FSTvy64sA"), None);
+///```
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct StunSecurityFeatures {
+    /// Bit 0: The server supports the PASSWORD-ALGORITHMS attribute
+    pub password_algorithms: bool,
+    /// Bit 1: The server supports the USERHASH attribute
+    pub username_anonymity: bool,
+}
+
+impl StunSecurityFeatures {
+    /// Bit 0 of the security feature set
+    const PASSWORD_ALGORITHMS_BIT: u8 = 0x80;
+    /// Bit 1 of the security feature set
+    const USERNAME_ANONYMITY_BIT: u8 = 0x40;
+
+    /// Encodes the nonce cookie followed by the security feature set
+    pub fn encode(self) -> String {
+        let mut bits = [0u8; 3];
+
+        if self.password_algorithms {
+            bits[0] |= Self::PASSWORD_ALGORITHMS_BIT;
+        }
+
+        if self.username_anonymity {
+            bits[0] |= Self::USERNAME_ANONYMITY_BIT;
+        }
+
+        format!("{}{}", NONCE_COOKIE, base64::encode(bits))
+    }
+
+    /// Decodes the security feature set of a NONCE attribute value
+    ///
+    /// Returns `None` if the nonce doesn't start with the nonce cookie. Reserved bits are ignored.
+    ///
+    /// Arguments:
+    ///
+    /// * `nonce`: NONCE attribute value
+    pub fn from_nonce(nonce: &str) -> Option<Self> {
+        let encoded_bits = nonce
+            .strip_prefix(NONCE_COOKIE)?
+            .get(..SECURITY_FEATURES_SIZE)?;
+        let bits = base64::decode(encoded_bits).ok()?;
+
+        Some(Self {
+            password_algorithms: bits[0] & Self::PASSWORD_ALGORITHMS_BIT != 0,
+            username_anonymity: bits[0] & Self::USERNAME_ANONYMITY_BIT != 0,
+        })
+    }
+}
+
+/// Stateless nonce generator and validator
+///
+/// The nonces are made of the nonce cookie, the security feature set and a base64-encoded signed part carrying
+/// the issue time and an HMAC-SHA1 (truncated to 128 bits) of the preceding fields and the client transport address.
+/// This allows a server to validate the nonces it has issued without keeping per-client state,
+/// as long as all its instances share the same key.
+///
+/// The security features advertised by a nonce are chosen each time it is created, as only the server
+/// that sends the corresponding PASSWORD-ALGORITHMS attribute or handles USERHASH may advertise them.
+///
+/// As a [`NonceSource`], it is plugged into a [`LongTermAuthenticator`](crate::LongTermAuthenticator)
+/// via `with_nonce_source`, using the system time. The nonces it issues then advertise no security features,
+/// as the authenticator sends no PASSWORD-ALGORITHMS attribute and doesn't handle USERHASH.
+/// Otherwise, clients would treat the missing PASSWORD-ALGORITHMS attribute as a bid-down attack,
+/// as per [RFC8489 Section 9.2.4](https://tools.ietf.org/html/rfc8489#section-9.2.4).
+///
+/// ```
+/// # use std::net::SocketAddr;
+/// # use std::time::{Duration, SystemTime};
+/// # use stun_coder::{NonceError, StatelessNonce, StunSecurityFeatures};
+/// let client: SocketAddr = "192.0.2.1:32853".parse().unwrap();
+/// let now = SystemTime::now();
+///
+/// let nonces = StatelessNonce::new(b"server secret");
+/// let features = StunSecurityFeatures {
+///     password_algorithms: true,
+///     username_anonymity: true,
+/// };
+///
+/// let nonce = nonces.create_nonce(features, client, now);
+/// assert!(nonce.starts_with("obMatJos2wAAA"));
+/// assert!(nonces.validate_nonce(&nonce, client, now).is_ok());
+///
+/// // Nonces are bound to the client address and expire
+/// let other_client: SocketAddr = "192.0.2.2:32853".parse().unwrap();
+/// assert!(matches!(
+///     nonces.validate_nonce(&nonce, other_client, now),
+///     Err(NonceError::AuthenticationFailure())
+/// ));
+/// assert!(matches!(
+///     nonces.validate_nonce(&nonce, client, now + Duration::from_secs(601)),
+///     Err(NonceError::Expired())
+/// ));
+/// ```
+#[derive(Debug, Clone)]
+pub struct StatelessNonce {
+    /// Key signing the nonces
+    key: Vec<u8>,
+    /// Time a nonce stays valid for
+    lifetime: Duration,
+}
+
+impl StatelessNonce {
+    /// Default time a nonce stays valid for
+    pub const DEFAULT_LIFETIME: Duration = Duration::from_secs(600);
+
+    /// Creates a nonce generator
+    ///
+    /// Arguments:
+    ///
+    /// * `key`: Secret key signing the nonces. It must be shared by all the server instances validating the nonces.
+    pub fn new(key: &[u8]) -> Self {
+        Self {
+            key: key.to_vec(),
+            lifetime: Self::DEFAULT_LIFETIME,
+        }
+    }
+
+    /// Sets the time a nonce stays valid for
+    pub fn set_lifetime(mut self, lifetime: Duration) -> Self {
+        self.lifetime = lifetime;
+
+        self
+    }
+
+    /// Creates a nonce bound to the client transport address
+    ///
+    /// Arguments:
+    ///
+    /// * `features`: Security features advertised by the nonce. They must be implemented by the server.
+    /// * `client`: Transport address of the client the nonce is issued to
+    /// * `now`: Current time
+    pub fn create_nonce(
+        &self,
+        features: StunSecurityFeatures,
+        client: SocketAddr,
+        now: SystemTime,
+    ) -> String {
+        let prefix = features.encode();
+        let timestamp = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+
+        let mut signed_part = Vec::with_capacity(SIGNED_PART_SIZE);
+        signed_part.extend_from_slice(&timestamp.to_be_bytes());
+        signed_part.extend_from_slice(&self.calculate_mac(&prefix, timestamp, client));
+
+        format!("{}{}", prefix, base64::encode(signed_part))
+    }
+
+    /// Validates a nonce issued by `create_nonce`
+    ///
+    /// Returns the security features advertised by the nonce.
+    ///
+    /// Arguments:
+    ///
+    /// * `nonce`: NONCE attribute value of the request
+    /// * `client`: Transport address the request has been received from
+    /// * `now`: Current time
+    pub fn validate_nonce(
+        &self,
+        nonce: &str,
+        client: SocketAddr,
+        now: SystemTime,
+    ) -> Result<StunSecurityFeatures, NonceError> {
+        let features = StunSecurityFeatures::from_nonce(nonce).ok_or(NonceError::MissingCookie())?;
+        let (prefix, encoded_signed_part) =
+            nonce.split_at(NONCE_COOKIE.len() + SECURITY_FEATURES_SIZE);
+
+        let signed_part =
+            base64::decode(encoded_signed_part).map_err(|_| NonceError::InvalidEncoding())?;
+        if signed_part.len() != SIGNED_PART_SIZE {
+            return Err(NonceError::InvalidEncoding());
+        }
+
+        let timestamp = u64::from_be_bytes(signed_part[..8].try_into().unwrap());
+        if !fixed_time_eq(
+            &signed_part[8..],
+            &self.calculate_mac(prefix, timestamp, client),
+        ) {
+            return Err(NonceError::AuthenticationFailure());
+        }
+
+        // Expiration times that can't be represented are never reached
+        let expires_at = UNIX_EPOCH
+            .checked_add(Duration::from_secs(timestamp))
+            .and_then(|issued_at| issued_at.checked_add(self.lifetime));
+        if matches!(expires_at, Some(expires_at) if now > expires_at) {
+            return Err(NonceError::Expired());
+        }
+
+        Ok(features)
+    }
+
+    // Calculates the truncated HMAC of the nonce prefix, the timestamp and the client transport address
+    fn calculate_mac(&self, prefix: &str, timestamp: u64, client: SocketAddr) -> Vec<u8> {
+        let mut mac = Hmac::new(Sha1::new(), &self.key);
+        mac.input(prefix.as_bytes());
+        mac.input(&timestamp.to_be_bytes());
+
+        match client.ip() {
+            IpAddr::V4(ip) => mac.input(&ip.octets()),
+            IpAddr::V6(ip) => mac.input(&ip.octets()),
+        }
+        mac.input(&client.port().to_be_bytes());
+
+        mac.result().code()[..NONCE_MAC_SIZE].to_vec()
+    }
+}
+
+impl NonceSource for StatelessNonce {
+    fn create_nonce(&mut self, client: SocketAddr) -> String {
+        // The authenticator implements none of the security features
+        StatelessNonce::create_nonce(
+            self,
+            StunSecurityFeatures::default(),
+            client,
+            SystemTime::now(),
+        )
+    }
+
+    fn validate_nonce(&mut self, nonce: &str, client: SocketAddr) -> bool {
+        StatelessNonce::validate_nonce(self, nonce, client, SystemTime::now()).is_ok()
+    }
+}
@@ -0,0 +1,139 @@
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+
+use super::clock::{StunClock, StunSystemClock};
+
+/// Issuer and validator of the NONCE values used by a [`LongTermAuthenticator`](crate::LongTermAuthenticator)
+pub trait NonceSource {
+    /// Issues a fresh nonce
+    ///
+    /// Arguments:
+    ///
+    /// * `client`: Transport address of the client the nonce is issued to
+    fn create_nonce(&mut self, client: SocketAddr) -> String;
+
+    /// Checks whether the nonce has been issued to the client and is still valid
+    ///
+    /// Arguments:
+    ///
+    /// * `nonce`: NONCE attribute value of the request
+    /// * `client`: Transport address the request has been received from
+    fn validate_nonce(&mut self, nonce: &str, client: SocketAddr) -> bool;
+}
+
+/// Nonce source keeping the issued random nonces in memory until they expire
+///
+/// The amount of kept nonces is bounded, as every unauthenticated request gets a fresh nonce.
+/// Once the capacity is reached, the oldest nonces are evicted first.
+/// Their clients get a 438 (Stale Nonce) error response and retry with a fresh nonce.
+pub struct InMemoryNonceSource<C: StunClock = StunSystemClock> {
+    /// Source of the current time
+    clock: C,
+    /// Maximum amount of kept nonces
+    capacity: usize,
+    /// Time a nonce stays valid for
+    lifetime: Duration,
+    /// Issued nonces along with their expiration time
+    nonces: HashMap<String, Instant>,
+    /// Nonces in issue order, which is also the expiration order
+    order: VecDeque<String>,
+}
+
+impl InMemoryNonceSource<StunSystemClock> {
+    /// Creates a nonce source using the system clock
+    pub fn new() -> Self {
+        Self::with_clock(StunSystemClock)
+    }
+}
+
+impl Default for InMemoryNonceSource<StunSystemClock> {
+    /// Creates a nonce source using the system clock
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: StunClock> InMemoryNonceSource<C> {
+    /// Default maximum amount of kept nonces
+    pub const DEFAULT_CAPACITY: usize = 4096;
+    /// Default time a nonce stays valid for
+    pub const DEFAULT_LIFETIME: Duration = Duration::from_secs(600);
+
+    /// Creates a nonce source using the provided clock
+    pub fn with_clock(clock: C) -> Self {
+        Self {
+            clock,
+            capacity: Self::DEFAULT_CAPACITY,
+            lifetime: Self::DEFAULT_LIFETIME,
+            nonces: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Sets the maximum amount of kept nonces. At least one nonce is always kept.
+    pub fn set_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+
+        self
+    }
+
+    /// Sets the time a nonce stays valid for
+    pub fn set_lifetime(mut self, lifetime: Duration) -> Self {
+        self.lifetime = lifetime;
+
+        self
+    }
+
+    /// Returns the amount of kept nonces, including the expired ones that haven't been purged yet
+    pub fn len(&self) -> usize {
+        self.nonces.len()
+    }
+
+    /// Checks whether no nonces are kept
+    pub fn is_empty(&self) -> bool {
+        self.nonces.is_empty()
+    }
+
+    // Removes the nonces expired at the provided time
+    fn purge_at(&mut self, now: Instant) {
+        while let Some(oldest) = self.order.front() {
+            if matches!(self.nonces.get(oldest), Some(expires_at) if *expires_at > now) {
+                break;
+            }
+
+            if let Some(oldest) = self.order.pop_front() {
+                self.nonces.remove(&oldest);
+            }
+        }
+    }
+}
+
+impl<C: StunClock> NonceSource for InMemoryNonceSource<C> {
+    fn create_nonce(&mut self, _client: SocketAddr) -> String {
+        let now = self.clock.now();
+        self.purge_at(now);
+
+        // Evict the oldest nonces to make room for the new one
+        while self.nonces.len() >= self.capacity {
+            match self.order.pop_front() {
+                Some(oldest) => {
+                    self.nonces.remove(&oldest);
+                }
+                None => break,
+            }
+        }
+
+        let nonce = format!("{:032x}", rand::thread_rng().gen::<u128>());
+        self.nonces.insert(nonce.clone(), now + self.lifetime);
+        self.order.push_back(nonce.clone());
+
+        nonce
+    }
+
+    fn validate_nonce(&mut self, nonce: &str, _client: SocketAddr) -> bool {
+        matches!(self.nonces.get(nonce), Some(expires_at) if *expires_at > self.clock.now())
+    }
+}
@@ -0,0 +1,296 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::{
+    AccessToken, AccessTokenAlgorithm, AccessTokenError, MessageDecodeError, StunAttribute,
+    StunMessage, StunMessageRef,
+};
+
+const SERVER_NAME: &str = "turn.example.org";
+const KEY_ID: &str = "kid-1";
+const LONG_TERM_KEY: [u8; 32] = [0x5A; 32];
+
+fn sample_token() -> AccessToken {
+    AccessToken {
+        nonce: [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12],
+        mac_key: vec![0xAB; 20],
+        timestamp: 0x0000_5F5E_1000_8000,
+        lifetime: 3600,
+    }
+}
+
+/// Encrypting and decrypting tokens with both AEAD algorithms
+#[test]
+fn token_round_trip() {
+    let token = sample_token();
+
+    for (algorithm, key) in [
+        (AccessTokenAlgorithm::Aes128Gcm, &LONG_TERM_KEY[..16]),
+        (AccessTokenAlgorithm::Aes256Gcm, &LONG_TERM_KEY[..]),
+    ]
+    .iter()
+    {
+        let encrypted = token.encrypt(*algorithm, key, SERVER_NAME).unwrap();
+
+        // nonce_length + nonce + key_length + mac_key + timestamp + lifetime + tag
+        assert_eq!(encrypted.len(), 2 + 12 + 2 + 20 + 8 + 4 + 16);
+        assert_eq!(
+            &encrypted[..14],
+            &[0, 12, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]
+        );
+
+        let decrypted = AccessToken::decrypt(&encrypted, *algorithm, key, SERVER_NAME).unwrap();
+        assert_eq!(decrypted, token);
+    }
+}
+
+/// Tokens don't decrypt with the wrong server name, key or content
+#[test]
+fn token_authentication() {
+    let algorithm = AccessTokenAlgorithm::Aes256Gcm;
+    let encrypted = sample_token()
+        .encrypt(algorithm, &LONG_TERM_KEY, SERVER_NAME)
+        .unwrap();
+
+    assert!(matches!(
+        AccessToken::decrypt(&encrypted, algorithm, &LONG_TERM_KEY, "stun.example.org"),
+        Err(AccessTokenError::AuthenticationFailure())
+    ));
+    assert!(matches!(
+        AccessToken::decrypt(&encrypted, algorithm, &[0x5B; 32], SERVER_NAME),
+        Err(AccessTokenError::AuthenticationFailure())
+    ));
+
+    let mut tampered = encrypted.clone();
+    tampered[20] ^= 0x01;
+    assert!(matches!(
+        AccessToken::decrypt(&tampered, algorithm, &LONG_TERM_KEY, SERVER_NAME),
+        Err(AccessTokenError::AuthenticationFailure())
+    ));
+
+    assert!(matches!(
+        AccessToken::decrypt(&encrypted[..20], algorithm, &LONG_TERM_KEY, SERVER_NAME),
+        Err(AccessTokenError::ReadWriteFailure(_))
+    ));
+    assert!(matches!(
+        AccessToken::decrypt(&encrypted, algorithm, &LONG_TERM_KEY[..16], SERVER_NAME),
+        Err(AccessTokenError::InvalidKeyLength {
+            expected: 32,
+            actual: 16
+        })
+    ));
+
+    let mut long_nonce = encrypted;
+    long_nonce[1] = 16;
+    assert!(matches!(
+        AccessToken::decrypt(&long_nonce, algorithm, &LONG_TERM_KEY, SERVER_NAME),
+        Err(AccessTokenError::InvalidNonceLength(16))
+    ));
+}
+
+/// Timestamps use the 48.16 fixed-point format
+#[test]
+fn token_lifetime() {
+    let issued_at = UNIX_EPOCH + Duration::from_millis(1_600_000_000_500);
+    let token = AccessToken::new(vec![0; 20], issued_at, 600);
+
+    assert_eq!(token.timestamp, (1_600_000_000 << 16) | 32000);
+    assert_eq!(token.get_issued_at(), issued_at);
+
+    assert!(!token.is_expired(issued_at + Duration::from_secs(600)));
+    assert!(token.is_expired(issued_at + Duration::from_secs(601)));
+
+    // Nonces are random
+    assert_ne!(
+        token.nonce,
+        AccessToken::new(vec![0; 20], SystemTime::now(), 600).nonce
+    );
+}
+
+/// Encoding the THIRD-PARTY-AUTHORIZATION and ACCESS-TOKEN attributes
+#[test]
+fn attributes() {
+    let message = StunMessage::create_error_response()
+        .add_attribute(StunAttribute::ThirdPartyAuthorization {
+            server_name: String::from("turn"),
+        })
+        .add_attribute(StunAttribute::AccessToken {
+            value: vec![1, 2, 3, 4, 5],
+        });
+
+    let encoded = message.encode(None).unwrap();
+    assert_eq!(
+        &encoded[20..],
+        &[
+            0x80, 0x2E, 0x00, 0x04, 0x74, 0x75, 0x72, 0x6E, // THIRD-PARTY-AUTHORIZATION
+            0x00, 0x1B, 0x00, 0x05, // ACCESS-TOKEN
+            0x01, 0x02, 0x03, 0x04, 0x05, 0x00, 0x00, 0x00, // ACCESS-TOKEN value
+        ][..]
+    );
+
+    let decoded = StunMessageRef::decode(&encoded, None).unwrap();
+    assert_eq!(decoded.get_third_party_authorization(), Some("turn"));
+    assert_eq!(decoded.get_access_token(), Some(&[1u8, 2, 3, 4, 5][..]));
+}
+
+/// Verifying requests with the MAC key of their access token
+#[test]
+fn decode_with_access_token() {
+    let token = sample_token();
+    let algorithm = AccessTokenAlgorithm::Aes256Gcm;
+    let encrypted = token
+        .encrypt(algorithm, &LONG_TERM_KEY, SERVER_NAME)
+        .unwrap();
+
+    let request = StunMessage::create_request()
+        .add_attribute(StunAttribute::Username {
+            value: String::from(KEY_ID),
+        })
+        .add_attribute(StunAttribute::AccessToken { value: encrypted });
+
+    let key_lookup = |kid: &str| {
+        if kid == KEY_ID {
+            Some((algorithm, LONG_TERM_KEY.to_vec()))
+        } else {
+            None
+        }
+    };
+    let now = token.get_issued_at() + Duration::from_secs(60);
+
+    let authenticated = request
+        .clone()
+        .add_message_integrity()
+        .add_fingerprint()
+        .encode_with_key(Some(&token.mac_key))
+        .unwrap();
+
+    let (decoded, decrypted) =
+        StunMessage::decode_with_access_token(&authenticated, SERVER_NAME, now, key_lookup)
+            .unwrap();
+    assert_eq!(decrypted, token);
+    assert_eq!(decoded.get_attributes().len(), 4);
+
+    // A different MAC key fails the integrity check
+    let forged = request
+        .clone()
+        .add_message_integrity()
+        .encode_with_key(Some(&[0xCD; 20]))
+        .unwrap();
+    assert!(matches!(
+        StunMessageRef::decode_with_access_token(&forged, SERVER_NAME, now, key_lookup),
+        Err(MessageDecodeError::MessageIntegrityFail { .. })
+    ));
+
+    // A replayed token alone doesn't authenticate the message
+    let unauthenticated = request.clone().encode(None).unwrap();
+    assert!(matches!(
+        StunMessageRef::decode_with_access_token(&unauthenticated, SERVER_NAME, now, key_lookup),
+        Err(MessageDecodeError::MissingMessageIntegrity())
+    ));
+
+    // Unknown key id
+    assert!(matches!(
+        StunMessageRef::decode_with_access_token(&authenticated, SERVER_NAME, now, |_| None),
+        Err(MessageDecodeError::CredentialNotFound())
+    ));
+
+    // Token issued for another server
+    assert!(matches!(
+        StunMessageRef::decode_with_access_token(
+            &authenticated,
+            "other.example.org",
+            now,
+            key_lookup
+        ),
+        Err(MessageDecodeError::AccessTokenDecryptionFailure(
+            AccessTokenError::AuthenticationFailure()
+        ))
+    ));
+
+    // No token at all
+    let password_request = StunMessage::create_request()
+        .add_attribute(StunAttribute::Username {
+            value: String::from(KEY_ID),
+        })
+        .add_message_integrity()
+        .encode(Some("pass"))
+        .unwrap();
+    assert!(matches!(
+        StunMessageRef::decode_with_access_token(&password_request, SERVER_NAME, now, key_lookup),
+        Err(MessageDecodeError::MissingAccessToken())
+    ));
+}
+
+/// Rejecting expired tokens and tokens with a MAC key of an invalid length
+#[test]
+fn decode_with_invalid_access_token() {
+    let algorithm = AccessTokenAlgorithm::Aes256Gcm;
+    let key_lookup = |_: &str| Some((algorithm, LONG_TERM_KEY.to_vec()));
+
+    let authenticate = |token: &AccessToken| {
+        StunMessage::create_request()
+            .add_attribute(StunAttribute::Username {
+                value: String::from(KEY_ID),
+            })
+            .add_attribute(StunAttribute::AccessToken {
+                value: token
+                    .encrypt(algorithm, &LONG_TERM_KEY, SERVER_NAME)
+                    .unwrap(),
+            })
+            .add_message_integrity()
+            .encode_with_key(Some(&token.mac_key))
+            .unwrap()
+    };
+
+    // Expired token
+    let token = sample_token();
+    let expires_at = token.get_issued_at() + Duration::from_secs(token.lifetime as u64);
+    let request = authenticate(&token);
+
+    assert!(StunMessageRef::decode_with_access_token(
+        &request,
+        SERVER_NAME,
+        expires_at,
+        key_lookup
+    )
+    .is_ok());
+    assert!(matches!(
+        StunMessageRef::decode_with_access_token(
+            &request,
+            SERVER_NAME,
+            expires_at + Duration::from_secs(1),
+            key_lookup
+        ),
+        Err(MessageDecodeError::AccessTokenDecryptionFailure(
+            AccessTokenError::Expired()
+        ))
+    ));
+
+    // MAC keys other than 20 or 32 bytes long
+    for mac_key_len in [0, 16, 64].iter() {
+        let token = AccessToken {
+            mac_key: vec![0xAB; *mac_key_len],
+            ..sample_token()
+        };
+        let request = authenticate(&token);
+
+        assert!(matches!(
+            StunMessageRef::decode_with_access_token(&request, SERVER_NAME, expires_at, key_lookup),
+            Err(MessageDecodeError::AccessTokenDecryptionFailure(
+                AccessTokenError::InvalidMacKeyLength(len)
+            )) if len == *mac_key_len
+        ));
+    }
+
+    // 32-byte MAC keys are accepted
+    let token = AccessToken {
+        mac_key: vec![0xAB; 32],
+        ..sample_token()
+    };
+    assert!(StunMessageRef::decode_with_access_token(
+        &authenticate(&token),
+        SERVER_NAME,
+        expires_at,
+        key_lookup
+    )
+    .is_ok());
+}
@@ -0,0 +1,108 @@
+use crate::{
+    check_for_channel_data_header, check_for_stun_message_header, ChannelData,
+    ChannelDataDecodeError, ChannelDataEncodeError,
+};
+
+/// Tests ChannelData encoding/decoding with and without padding
+#[test]
+fn channel_data_padding() {
+    let channel_data = ChannelData::new(0x4001, vec![0xde, 0xad, 0xbe, 0xef, 0x01]);
+
+    let unpadded = channel_data.encode(false).unwrap();
+    assert_eq!(
+        unpadded,
+        vec![0x40, 0x01, 0x00, 0x05, 0xde, 0xad, 0xbe, 0xef, 0x01]
+    );
+
+    let padded = channel_data.encode(true).unwrap();
+    assert_eq!(
+        padded,
+        vec![0x40, 0x01, 0x00, 0x05, 0xde, 0xad, 0xbe, 0xef, 0x01, 0x00, 0x00, 0x00]
+    );
+    assert_eq!(padded.len(), channel_data.encoded_len(true));
+
+    // Padding is not reflected in the length field and must be ignored by the decoder
+    assert_eq!(ChannelData::decode(&unpadded).unwrap(), channel_data);
+    assert_eq!(ChannelData::decode(&padded).unwrap(), channel_data);
+
+    // Framing information for stream transports
+    assert_eq!(ChannelData::decode_message_len(&padded, true).unwrap(), 12);
+    assert_eq!(ChannelData::decode_message_len(&padded, false).unwrap(), 9);
+}
+
+/// Tests framing of several padded ChannelData messages in a single stream buffer
+#[test]
+fn channel_data_stream_framing() {
+    let messages = vec![
+        ChannelData::new(0x4000, vec![]),
+        ChannelData::new(0x7FFF, vec![1, 2, 3]),
+        ChannelData::new(0x5000, vec![4, 5, 6, 7]),
+    ];
+
+    let mut stream = Vec::new();
+    for message in messages.iter() {
+        stream.extend(message.encode(true).unwrap());
+    }
+
+    let mut offset = 0;
+    let mut decoded = Vec::new();
+    while offset < stream.len() {
+        let frame_len = ChannelData::decode_message_len(&stream[offset..], true).unwrap();
+        decoded.push(ChannelData::decode(&stream[offset..offset + frame_len]).unwrap());
+        offset += frame_len;
+    }
+
+    assert_eq!(decoded, messages);
+}
+
+/// Tests channel number validation
+#[test]
+fn channel_data_invalid_channel_number() {
+    let result = ChannelData::new(0x3FFF, vec![1]).encode(false);
+    assert!(matches!(
+        result,
+        Err(ChannelDataEncodeError::InvalidChannelNumber(0x3FFF))
+    ));
+
+    let result = ChannelData::new(0x8000, vec![1]).encode(false);
+    assert!(matches!(
+        result,
+        Err(ChannelDataEncodeError::InvalidChannelNumber(0x8000))
+    ));
+
+    let result = ChannelData::decode(&[0x80, 0x00, 0x00, 0x00]);
+    assert!(matches!(
+        result,
+        Err(ChannelDataDecodeError::InvalidChannelNumber(0x8000))
+    ));
+
+    let result = ChannelData::decode(&[0x40, 0x00, 0x00, 0x04, 0x01]);
+    assert!(matches!(
+        result,
+        Err(ChannelDataDecodeError::InsufficientData {
+            expected: 4,
+            available: 1
+        })
+    ));
+}
+
+/// Tests demultiplexing ChannelData messages from STUN messages
+#[test]
+fn channel_data_demultiplexing() {
+    let stun_bytes = vec![
+        0x00, 0x01, 0x00, 0x58, 0x21, 0x12, 0xa4, 0x42, 0xb7, 0xe7, 0xa7, 0x01, 0xbc, 0x34, 0xd6,
+        0x86, 0xfa, 0x87, 0xdf, 0xae,
+    ];
+    let channel_data_bytes = ChannelData::new(0x4001, vec![0u8; 16])
+        .encode(true)
+        .unwrap();
+
+    assert!(check_for_stun_message_header(&stun_bytes).is_some());
+    assert_eq!(check_for_channel_data_header(&stun_bytes), None);
+
+    assert!(check_for_stun_message_header(&channel_data_bytes).is_none());
+    assert_eq!(
+        check_for_channel_data_header(&channel_data_bytes),
+        Some(0x4001)
+    );
+}
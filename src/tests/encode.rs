@@ -0,0 +1,97 @@
+use bytes::BytesMut;
+
+use crate::{MessageEncodeError, StunAttribute, StunMessage};
+
+fn sample_message() -> StunMessage {
+    StunMessage::create_request()
+        .add_attribute(StunAttribute::Username {
+            value: String::from("user"),
+        })
+        .add_attribute(StunAttribute::Realm {
+            value: String::from("example.org"),
+        })
+        .add_attribute(StunAttribute::Software {
+            description: String::from("rust-stun-coder"),
+        })
+        .add_message_integrity()
+        .add_fingerprint()
+        .set_transaction_id([1u8, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12])
+}
+
+/// Encoding into a caller-provided slice produces the same bytes as `encode`
+#[test]
+fn encode_into_slice() {
+    let message = sample_message();
+    let expected = message.encode(Some("PASS")).unwrap();
+
+    assert_eq!(message.encoded_len().unwrap(), expected.len());
+
+    let mut buf = [0xFFu8; 1500];
+    let len = message.encode_into(&mut buf, Some("PASS")).unwrap();
+
+    assert_eq!(&buf[..len], &expected[..]);
+    // Bytes past the message are left untouched
+    assert!(buf[len..].iter().all(|byte| *byte == 0xFF));
+
+    // The result is decodable with the integrity and fingerprint checks
+    StunMessage::decode(&buf[..len], Some("PASS")).unwrap();
+}
+
+/// A short buffer is rejected before anything is written
+#[test]
+fn encode_into_small_buffer() {
+    let message = sample_message();
+    let required = message.encoded_len().unwrap();
+
+    let mut buf = vec![0u8; required - 1];
+    match message.encode_into(&mut buf, Some("PASS")) {
+        Err(MessageEncodeError::BufferTooSmall {
+            required: err_required,
+            available,
+        }) => {
+            assert_eq!(err_required, required);
+            assert_eq!(available, required - 1);
+        }
+        other => panic!("Unexpected result: {:?}", other),
+    }
+    assert!(buf.iter().all(|byte| *byte == 0));
+}
+
+/// Messages can be appended to a reused `BufMut`
+#[test]
+fn encode_to_buf_mut() {
+    let message = sample_message();
+    let expected = message.encode(Some("PASS")).unwrap();
+
+    let mut buf = BytesMut::with_capacity(1500);
+    let first_len = message.encode_to(&mut buf, Some("PASS")).unwrap();
+    let second_len = message.encode_to(&mut buf, Some("PASS")).unwrap();
+
+    assert_eq!(first_len, expected.len());
+    assert_eq!(&buf[..first_len], &expected[..]);
+    assert_eq!(&buf[first_len..first_len + second_len], &expected[..]);
+
+    // Reuse the buffer after clearing it
+    buf.clear();
+    message.encode_to(&mut buf, Some("PASS")).unwrap();
+    assert_eq!(&buf[..], &expected[..]);
+}
+
+/// Ordering violations are reported by `encoded_len` as well
+#[test]
+fn attribute_after_integrity() {
+    let message = StunMessage::create_request()
+        .add_message_integrity()
+        .add_attribute(StunAttribute::Software {
+            description: String::from("rust-stun-coder"),
+        });
+
+    assert!(matches!(
+        message.encoded_len(),
+        Err(MessageEncodeError::AttributeAfterIntegrity())
+    ));
+    assert!(matches!(
+        message.encode_into(&mut [0u8; 1500], Some("PASS")),
+        Err(MessageEncodeError::AttributeAfterIntegrity())
+    ));
+}
@@ -0,0 +1,137 @@
+use super::check_sample_integrity;
+use crate::{StunAttribute, StunMessage, StunMessageClass, StunMessageMethod, StunMessageRef};
+
+/// Tests the TRANSACTION_TRANSMIT_COUNTER attribute of a retransmitted request and its response
+#[test]
+fn transaction_transmit_counter() {
+    let request = StunMessage::create_request()
+        .add_attribute(StunAttribute::TransactionTransmitCounter {
+            request: 3,
+            response: 0,
+        })
+        .add_fingerprint();
+
+    let encoded_request = request.encode(None).unwrap();
+
+    assert_eq!(
+        encoded_request[20..28],
+        [0x80, 0x25, 0x00, 0x04, 0x00, 0x00, 0x03, 0x00]
+    );
+    check_sample_integrity(&encoded_request, None);
+
+    let decoded_request = StunMessageRef::decode(&encoded_request, None).unwrap();
+    let (request_count, _) = decoded_request.get_transaction_transmit_counter().unwrap();
+
+    // The server echoes the request counter
+    let response = StunMessage::create_success_response()
+        .set_transaction_id(decoded_request.get_header().transaction_id)
+        .add_attribute(StunAttribute::TransactionTransmitCounter {
+            request: request_count,
+            response: 1,
+        });
+
+    let encoded_response = response.encode(None).unwrap();
+
+    assert_eq!(
+        encoded_response[20..28],
+        [0x80, 0x25, 0x00, 0x04, 0x00, 0x00, 0x03, 0x01]
+    );
+
+    let decoded_response = StunMessageRef::decode(&encoded_response, None).unwrap();
+    assert_eq!(
+        decoded_response.get_transaction_transmit_counter(),
+        Some((3, 1))
+    );
+}
+
+/// Tests the MOBILITY-TICKET attribute of a TURN Refresh request decoding/recoding
+#[test]
+fn mobility_ticket() {
+    let message = StunMessage::new(StunMessageMethod::Refresh, StunMessageClass::Request)
+        .add_attribute(StunAttribute::MobilityTicket {
+            value: vec![0x10, 0x20, 0x30, 0x40, 0x50, 0x60],
+        })
+        .add_attribute(StunAttribute::Lifetime { seconds: 600 })
+        .add_message_integrity();
+
+    let encoded_message = message.encode(Some("pass")).unwrap();
+
+    assert_eq!(
+        encoded_message[20..32],
+        [0x80, 0x30, 0x00, 0x06, 0x10, 0x20, 0x30, 0x40, 0x50, 0x60, 0x00, 0x00]
+    );
+    check_sample_integrity(&encoded_message, Some("pass"));
+
+    let decoded = StunMessageRef::decode(&encoded_message, Some("pass")).unwrap();
+    assert_eq!(
+        decoded.get_mobility_ticket(),
+        Some(&[0x10, 0x20, 0x30, 0x40, 0x50, 0x60][..])
+    );
+}
+
+/// Tests the ECN-CHECK attribute bit fields
+#[test]
+fn ecn_check() {
+    let cases = [
+        (0b00, false, 0x00),
+        (0b01, true, 0x03),
+        (0b10, true, 0x05),
+        (0b11, false, 0x06),
+    ];
+
+    for (ecn_field, valid, encoded_bits) in cases.iter() {
+        let message =
+            StunMessage::create_success_response().add_attribute(StunAttribute::EcnCheck {
+                ecn_field: *ecn_field,
+                valid: *valid,
+            });
+
+        let encoded_message = message.encode(None).unwrap();
+
+        assert_eq!(
+            encoded_message[20..28],
+            [0x80, 0x2D, 0x00, 0x04, 0x00, 0x00, 0x00, *encoded_bits]
+        );
+        check_sample_integrity(&encoded_message, None);
+
+        let decoded = StunMessageRef::decode(&encoded_message, None).unwrap();
+        assert_eq!(decoded.get_ecn_check(), Some((*ecn_field, *valid)));
+    }
+
+    // Unused bits are ignored
+    let mut encoded_message = StunMessage::create_success_response()
+        .add_attribute(StunAttribute::EcnCheck {
+            ecn_field: 0b11,
+            valid: true,
+        })
+        .encode(None)
+        .unwrap();
+    encoded_message[24..27].copy_from_slice(&[0xFF, 0xFF, 0xFF]);
+
+    let decoded = StunMessageRef::decode(&encoded_message, None).unwrap();
+    assert_eq!(decoded.get_ecn_check(), Some((0b11, true)));
+}
+
+/// Tests multiple ORIGIN attributes decoding/recoding
+#[test]
+fn origin() {
+    let message = StunMessage::new(StunMessageMethod::Allocate, StunMessageClass::Request)
+        .add_attribute(StunAttribute::Origin {
+            value: String::from("https://example.org"),
+        })
+        .add_attribute(StunAttribute::Origin {
+            value: String::from("https://app.example.org:8443"),
+        })
+        .add_fingerprint();
+
+    let encoded_message = message.encode(None).unwrap();
+
+    assert_eq!(encoded_message[20..24], [0x80, 0x2F, 0x00, 0x13]);
+    check_sample_integrity(&encoded_message, None);
+
+    let decoded = StunMessageRef::decode(&encoded_message, None).unwrap();
+    assert_eq!(
+        decoded.get_origins(),
+        vec!["https://example.org", "https://app.example.org:8443"]
+    );
+}
@@ -0,0 +1,113 @@
+use super::check_sample_integrity;
+use crate::{
+    check_for_stun_message_header, HeaderEncodeError, MessageEncodeError, StunAttribute,
+    StunMessage, StunMessageClass, StunMessageMethod,
+};
+
+/// Tests that an unknown method is decoded and re-encoded without any loss
+#[test]
+fn unknown_method_round_trip() {
+    // Indication with the 0xABC method and a Software attribute
+    let msg_bytes = vec![
+        0x2A, 0x7C, 0x00, 0x08, 0x21, 0x12, 0xa4, 0x42, 0x1, 0x2, 0x3, 0x4, 0x5, 0x6, 0x7, 0x8,
+        0x9, 0xA, 0xB, 0xC, 0x80, 0x22, 0x00, 0x04, 0x74, 0x65, 0x73, 0x74,
+    ];
+
+    let decoded_msg = StunMessage::decode(&msg_bytes, None).unwrap();
+
+    assert_eq!(
+        decoded_msg.get_header().message_method,
+        StunMessageMethod::Other(0xABC)
+    );
+    assert_eq!(
+        decoded_msg.get_header().message_class,
+        StunMessageClass::Indication
+    );
+
+    check_sample_integrity(&msg_bytes, None);
+}
+
+/// Tests the method bits placement for every message class
+#[test]
+fn method_and_class_encoding() {
+    let classes = [
+        (StunMessageClass::Request, 0x3EEFu16),
+        (StunMessageClass::Indication, 0x3EFF),
+        (StunMessageClass::SuccessResponse, 0x3FEF),
+        (StunMessageClass::ErrorResponse, 0x3FFF),
+    ];
+
+    for (class, type_field) in classes.iter() {
+        let encoded_message = StunMessage::new(StunMessageMethod::Other(0xFFF), *class)
+            .add_attribute(StunAttribute::Software {
+                description: String::from("test"),
+            })
+            .encode(None)
+            .unwrap();
+
+        assert_eq!(encoded_message[0..2], type_field.to_be_bytes());
+
+        let header = check_for_stun_message_header(&encoded_message).unwrap();
+        assert_eq!(header.message_method, StunMessageMethod::Other(0xFFF));
+        assert_eq!(header.message_class, *class);
+    }
+}
+
+/// Tests that the known methods are mapped to their own variants
+#[test]
+fn known_method_conversion() {
+    let methods = [
+        StunMessageMethod::BindingRequest,
+        StunMessageMethod::Allocate,
+        StunMessageMethod::Refresh,
+        StunMessageMethod::Send,
+        StunMessageMethod::Data,
+        StunMessageMethod::CreatePermission,
+        StunMessageMethod::ChannelBind,
+        StunMessageMethod::Connect,
+        StunMessageMethod::ConnectionBind,
+        StunMessageMethod::ConnectionAttempt,
+    ];
+
+    for method in methods.iter() {
+        assert_eq!(StunMessageMethod::from(u16::from(*method)), *method);
+    }
+
+    assert_eq!(
+        StunMessageMethod::from(0x002),
+        StunMessageMethod::Other(0x002)
+    );
+}
+
+/// Tests that out of range and known method values wrapped in `Other` are rejected
+#[test]
+fn invalid_other_method() {
+    for method in [0x1001u16, 0xFFFF, 0x001, 0x003].iter() {
+        let result = StunMessage::new(StunMessageMethod::Other(*method), StunMessageClass::Request)
+            .encode(None);
+
+        assert!(matches!(
+            result,
+            Err(MessageEncodeError::HeaderEncodeFailure(
+                HeaderEncodeError::InvalidMessageMethod(value)
+            )) if value == *method
+        ));
+    }
+
+    // The conversion from the method value normalizes known methods
+    assert_eq!(
+        StunMessageMethod::from(0x001),
+        StunMessageMethod::BindingRequest
+    );
+}
+
+/// Tests that byte sequences with non-zero leading bits are not treated as STUN messages
+#[test]
+fn non_zero_leading_bits() {
+    let bytes = vec![
+        0x40, 0x01, 0x00, 0x00, 0x21, 0x12, 0xa4, 0x42, 0xb7, 0xe7, 0xa7, 0x01, 0xbc, 0x34, 0xd6,
+        0x86, 0xfa, 0x87, 0xdf, 0xae,
+    ];
+
+    assert!(check_for_stun_message_header(&bytes).is_none());
+}
@@ -0,0 +1,229 @@
+use super::check_sample_integrity;
+use crate::{
+    AttributeDecodeError, AttributeEncodeError, IntegrityKeyGenerationError, MessageDecodeError,
+    MessageEncodeError, StunAttribute, StunMessage, StunMessageRef, StunPasswordAlgorithm,
+};
+
+const TRANSACTION_ID: [u8; 12] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+
+fn long_term_request(algorithm: StunPasswordAlgorithm) -> StunMessage {
+    StunMessage::create_request()
+        .add_attribute(StunAttribute::Username {
+            value: String::from("user"),
+        })
+        .add_attribute(StunAttribute::Realm {
+            value: String::from("example.org"),
+        })
+        .add_attribute(StunAttribute::Nonce {
+            value: String::from("f//499k954d6OL34oL9FSTvy64sA"),
+        })
+        .add_attribute(StunAttribute::PasswordAlgorithm {
+            algorithm,
+            parameters: Vec::new(),
+        })
+        .set_transaction_id(TRANSACTION_ID)
+}
+
+/// Both integrity attributes are computed and verified with the SHA-256 derived key
+#[test]
+fn sha256_long_term_credentials() {
+    let message = long_term_request(StunPasswordAlgorithm::Sha256)
+        .add_message_integrity()
+        .add_message_integrity_sha256()
+        .add_fingerprint();
+
+    let encoded = message.encode(Some("pass")).unwrap();
+
+    let decoded = StunMessageRef::decode(&encoded, Some("pass")).unwrap();
+    assert_eq!(decoded.get_message_integrity().unwrap().len(), 20);
+    assert_eq!(decoded.get_message_integrity_sha256().unwrap().len(), 32);
+
+    // The same key can be provided directly
+    let key = StunMessage::calculate_long_term_key(
+        "user",
+        "example.org",
+        "pass",
+        StunPasswordAlgorithm::Sha256,
+    )
+    .unwrap();
+    StunMessage::decode_with_key(&encoded, Some(&key)).unwrap();
+    assert_eq!(message.encode_with_key(Some(&key)).unwrap(), encoded);
+
+    // The MD5 derived key doesn't match
+    let md5_key = StunMessage::calculate_long_term_key(
+        "user",
+        "example.org",
+        "pass",
+        StunPasswordAlgorithm::Md5,
+    )
+    .unwrap();
+    assert!(matches!(
+        StunMessage::decode_with_key(&encoded, Some(&md5_key)),
+        Err(MessageDecodeError::MessageIntegrityFail { .. })
+    ));
+
+    // Re-encoding a decoded message reproduces it
+    check_sample_integrity(&encoded, Some("pass"));
+}
+
+/// Truncated HMAC-SHA256 values are supported in the 16 to 32 bytes range
+#[test]
+fn truncated_integrity() {
+    let message = long_term_request(StunPasswordAlgorithm::Sha256)
+        .add_truncated_message_integrity_sha256(16)
+        .add_fingerprint();
+
+    let encoded = message.encode(Some("pass")).unwrap();
+
+    let decoded = StunMessageRef::decode(&encoded, Some("pass")).unwrap();
+    assert_eq!(decoded.get_message_integrity_sha256().unwrap().len(), 16);
+    check_sample_integrity(&encoded, Some("pass"));
+
+    // Invalid truncation lengths
+    assert!(matches!(
+        long_term_request(StunPasswordAlgorithm::Sha256)
+            .add_truncated_message_integrity_sha256(18)
+            .encode(Some("pass")),
+        Err(MessageEncodeError::AttributeEncodeFailure(
+            AttributeEncodeError::InvalidIntegrityLength(18)
+        ))
+    ));
+
+    let mut invalid = encoded.clone();
+    // Change the MESSAGE-INTEGRITY-SHA256 length from 16 to 12 bytes
+    let attr_pos = encoded.len() - 8 - 20;
+    assert_eq!(&encoded[attr_pos..attr_pos + 4], &[0x00, 0x1C, 0x00, 0x10]);
+    invalid[attr_pos + 3] = 0x0C;
+    assert!(matches!(
+        StunMessage::decode(&invalid, None),
+        Err(MessageDecodeError::AttributeDecodeFailure {
+            source: AttributeDecodeError::InvalidIntegrityLength(12),
+            ..
+        })
+    ));
+}
+
+/// HMAC-SHA1 MESSAGE-INTEGRITY values must not be truncated
+#[test]
+fn truncated_sha1_integrity() {
+    // Binding request with a USERNAME and a single byte MESSAGE-INTEGRITY value
+    let mut bytes = vec![
+        0x00, 0x01, 0x00, 0x10, 0x21, 0x12, 0xA4, 0x42, 0xB7, 0xE7, 0xA7, 0x01, 0xBC, 0x34, 0xD6,
+        0x86, 0xFA, 0x87, 0xDF, 0xAE, 0x00, 0x06, 0x00, 0x04, 0x75, 0x73, 0x65, 0x72, 0x00, 0x08,
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    // None of the possible values may pass the integrity check
+    for value in 0..=u8::MAX {
+        bytes[32] = value;
+
+        assert!(matches!(
+            StunMessageRef::decode(&bytes, Some("pass")),
+            Err(MessageDecodeError::AttributeDecodeFailure {
+                source: AttributeDecodeError::InvalidIntegrityLength(1),
+                ..
+            })
+        ));
+        assert!(matches!(
+            StunMessage::decode(&bytes, Some("pass")),
+            Err(MessageDecodeError::AttributeDecodeFailure {
+                source: AttributeDecodeError::InvalidIntegrityLength(1),
+                ..
+            })
+        ));
+    }
+}
+
+/// Tampering is detected by the SHA-256 integrity check
+#[test]
+fn tampered_message() {
+    let mut encoded = long_term_request(StunPasswordAlgorithm::Sha256)
+        .add_message_integrity_sha256()
+        .encode(Some("pass"))
+        .unwrap();
+
+    // Modify the nonce value
+    encoded[50] ^= 0x01;
+
+    assert!(matches!(
+        StunMessage::decode(&encoded, Some("pass")),
+        Err(MessageDecodeError::MessageIntegrityFail { .. })
+    ));
+}
+
+/// The encoder enforces the MESSAGE-INTEGRITY, MESSAGE-INTEGRITY-SHA256, FINGERPRINT order
+#[test]
+fn encoding_order() {
+    let sha256_before_sha1 = long_term_request(StunPasswordAlgorithm::Sha256)
+        .add_message_integrity_sha256()
+        .add_message_integrity();
+    assert!(matches!(
+        sha256_before_sha1.encode(Some("pass")),
+        Err(MessageEncodeError::AttributeAfterIntegrity())
+    ));
+
+    let duplicate = long_term_request(StunPasswordAlgorithm::Sha256)
+        .add_message_integrity_sha256()
+        .add_message_integrity_sha256();
+    assert!(matches!(
+        duplicate.encode(Some("pass")),
+        Err(MessageEncodeError::AttributeAfterIntegrity())
+    ));
+
+    let fingerprint_first = long_term_request(StunPasswordAlgorithm::Sha256)
+        .add_fingerprint()
+        .add_message_integrity_sha256();
+    assert!(matches!(
+        fingerprint_first.encode(Some("pass")),
+        Err(MessageEncodeError::IncorrectFingerprintAttributePosition { .. })
+    ));
+}
+
+/// The decoder ignores the attributes following the integrity attributes, except FINGERPRINT
+#[test]
+fn decoding_order() {
+    let message = long_term_request(StunPasswordAlgorithm::Sha256)
+        .add_message_integrity_sha256()
+        .encode(Some("pass"))
+        .unwrap();
+
+    // Append a SOFTWARE attribute after MESSAGE-INTEGRITY-SHA256 and fix up the message length
+    let mut bytes = message.clone();
+    bytes.extend_from_slice(&[0x80, 0x22, 0x00, 0x04, 0x74, 0x65, 0x73, 0x74]);
+    bytes[3] += 8;
+
+    let decoded = StunMessageRef::decode(&bytes, Some("pass")).unwrap();
+    assert_eq!(decoded.get_software(), None);
+    assert_eq!(decoded.get_attributes().len(), 5);
+}
+
+/// PASSWORD-ALGORITHMS lists survive a round trip and unsupported algorithms are reported
+#[test]
+fn password_algorithms() {
+    let message = StunMessage::create_error_response()
+        .add_attribute(StunAttribute::PasswordAlgorithms {
+            algorithms: vec![
+                (StunPasswordAlgorithm::Sha256, Vec::new()),
+                (StunPasswordAlgorithm::Md5, Vec::new()),
+                (StunPasswordAlgorithm::Other(0x1234), vec![1, 2, 3]),
+            ],
+        })
+        .add_fingerprint()
+        .set_transaction_id(TRANSACTION_ID);
+
+    let encoded = message.encode(None).unwrap();
+    check_sample_integrity(&encoded, None);
+
+    let decoded = StunMessage::decode(&encoded, None).unwrap();
+    assert_eq!(decoded.get_attributes()[0], message.get_attributes()[0]);
+
+    let unsupported = long_term_request(StunPasswordAlgorithm::Other(0x1234))
+        .add_message_integrity()
+        .encode(Some("pass"));
+    assert!(matches!(
+        unsupported,
+        Err(MessageEncodeError::IntegrityKeyGenerationFailure(
+            IntegrityKeyGenerationError::UnsupportedPasswordAlgorithm(0x1234)
+        ))
+    ));
+}
@@ -0,0 +1,159 @@
+use crypto::hmac::Hmac;
+use crypto::mac::Mac;
+use crypto::sha1::Sha1;
+use std::net::SocketAddr;
+
+use crate::{
+    check_for_stun_message_header, HeaderDecodeError, MessageDecodeError, StunAttribute,
+    StunDialect, StunMessage, StunMessageRef,
+};
+
+const LEGACY_TRANSACTION_ID: [u8; 16] = [
+    0xA1, 0xA2, 0xA3, 0xA4, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C,
+];
+
+/// Classic binding request with the CHANGE-REQUEST and RESPONSE-ADDRESS attributes
+#[test]
+fn binding_request() {
+    let response_addr: SocketAddr = "192.0.2.1:32853".parse().unwrap();
+
+    let message = StunMessage::create_request()
+        .add_attribute(StunAttribute::ResponseAddress {
+            socket_addr: response_addr,
+        })
+        .add_attribute(StunAttribute::ChangeRequest {
+            change_ip: true,
+            change_port: false,
+        })
+        .set_legacy_transaction_id(LEGACY_TRANSACTION_ID);
+
+    let encoded = message.encode(None).unwrap();
+
+    let encoded_match = vec![
+        0x00, 0x01, 0x00, 0x14, 0xA1, 0xA2, 0xA3, 0xA4, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07,
+        0x08, 0x09, 0x0A, 0x0B, 0x0C, // Header
+        0x00, 0x02, 0x00, 0x08, 0x00, 0x01, 0x80, 0x55, // RESPONSE-ADDRESS
+        0xC0, 0x00, 0x02, 0x01, // RESPONSE-ADDRESS IP
+        0x00, 0x03, 0x00, 0x04, 0x00, 0x00, 0x00, 0x04, // CHANGE-REQUEST
+    ];
+    assert_eq!(encoded, encoded_match);
+
+    let decoded = StunMessageRef::decode_legacy(&encoded, None).unwrap();
+    assert_eq!(
+        decoded.get_header().dialect,
+        StunDialect::Rfc3489 {
+            transaction_id_prefix: [0xA1, 0xA2, 0xA3, 0xA4],
+        }
+    );
+    assert_eq!(
+        decoded.get_header().get_legacy_transaction_id(),
+        Some(LEGACY_TRANSACTION_ID)
+    );
+    assert_eq!(decoded.get_response_address(), Some(response_addr));
+    assert_eq!(decoded.get_change_request(), Some((true, false)));
+
+    // Re-encoding keeps the dialect
+    assert_eq!(decoded.into_owned().encode(None).unwrap(), encoded_match);
+}
+
+/// Classic binding response with the MAPPED-ADDRESS, SOURCE-ADDRESS, CHANGED-ADDRESS and REFLECTED-FROM attributes
+#[test]
+fn binding_response() {
+    let mapped: SocketAddr = "192.0.2.1:32853".parse().unwrap();
+    let source: SocketAddr = "198.51.100.1:3478".parse().unwrap();
+    let changed: SocketAddr = "198.51.100.2:3479".parse().unwrap();
+    let reflected: SocketAddr = "203.0.113.7:4000".parse().unwrap();
+
+    let message = StunMessage::create_success_response()
+        .add_attribute(StunAttribute::MappedAddress {
+            socket_addr: mapped,
+        })
+        .add_attribute(StunAttribute::SourceAddress {
+            socket_addr: source,
+        })
+        .add_attribute(StunAttribute::ChangedAddress {
+            socket_addr: changed,
+        })
+        .add_attribute(StunAttribute::ReflectedFrom {
+            socket_addr: reflected,
+        })
+        .set_legacy_transaction_id(LEGACY_TRANSACTION_ID);
+
+    let encoded = message.encode(None).unwrap();
+
+    assert_eq!(&encoded[4..8], &LEGACY_TRANSACTION_ID[..4]);
+    assert_eq!(
+        &encoded[32..44],
+        &[0x00, 0x04, 0x00, 0x08, 0x00, 0x01, 0x0D, 0x96, 0xC6, 0x33, 0x64, 0x01]
+    );
+    assert_eq!(
+        &encoded[44..56],
+        &[0x00, 0x05, 0x00, 0x08, 0x00, 0x01, 0x0D, 0x97, 0xC6, 0x33, 0x64, 0x02]
+    );
+    assert_eq!(
+        &encoded[56..68],
+        &[0x00, 0x0B, 0x00, 0x08, 0x00, 0x01, 0x0F, 0xA0, 0xCB, 0x00, 0x71, 0x07]
+    );
+
+    let decoded = StunMessage::decode_legacy(&encoded, None).unwrap();
+    assert_eq!(decoded.get_attributes(), message.get_attributes());
+
+    let decoded = StunMessageRef::decode_legacy(&encoded, None).unwrap();
+    assert_eq!(decoded.get_mapped_address(), Some(mapped));
+    assert_eq!(decoded.get_source_address(), Some(source));
+    assert_eq!(decoded.get_changed_address(), Some(changed));
+    assert_eq!(decoded.get_reflected_from(), Some(reflected));
+}
+
+/// Classic messages are only accepted by the legacy decoder, which reports the dialect of modern messages too
+#[test]
+fn dialect_detection() {
+    let legacy = StunMessage::create_request()
+        .set_legacy_transaction_id(LEGACY_TRANSACTION_ID)
+        .encode(None)
+        .unwrap();
+
+    assert!(matches!(
+        StunMessage::decode(&legacy, None),
+        Err(MessageDecodeError::HeaderDecodeFailure(
+            HeaderDecodeError::MagicCookieMismatch()
+        ))
+    ));
+    assert!(check_for_stun_message_header(&legacy).is_none());
+
+    let modern = StunMessage::create_request().encode(None).unwrap();
+    let decoded = StunMessageRef::decode_legacy(&modern, None).unwrap();
+
+    assert_eq!(decoded.get_header().dialect, StunDialect::Rfc5389);
+    assert_eq!(decoded.get_header().get_legacy_transaction_id(), None);
+}
+
+/// MESSAGE-INTEGRITY of classic messages is computed over the message padded to a multiple of 64 bytes
+#[test]
+fn legacy_integrity() {
+    let message = StunMessage::create_request()
+        .add_attribute(StunAttribute::Username {
+            value: String::from("legacy-user"),
+        })
+        .add_message_integrity()
+        .set_legacy_transaction_id(LEGACY_TRANSACTION_ID);
+
+    let encoded = message.encode(Some("legacy-pass")).unwrap();
+    let integrity_start = encoded.len() - 24;
+
+    // Compute the expected HMAC independently
+    let mut hmac_input = encoded[..integrity_start].to_vec();
+    hmac_input.resize(64, 0);
+    let mut hmac = Hmac::new(Sha1::new(), b"legacy-pass");
+    hmac.input(&hmac_input);
+
+    assert_eq!(&encoded[integrity_start + 4..], hmac.result().code());
+
+    let decoded = StunMessageRef::decode_legacy(&encoded, Some("legacy-pass")).unwrap();
+    assert_eq!(decoded.get_username(), Some("legacy-user"));
+
+    assert!(matches!(
+        StunMessageRef::decode_legacy(&encoded, Some("wrong-pass")),
+        Err(MessageDecodeError::MessageIntegrityFail { .. })
+    ));
+}
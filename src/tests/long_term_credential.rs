@@ -0,0 +1,320 @@
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+use crate::{
+    LongTermCredentialClient, StunAttribute, StunAttributeRef, StunDialect, StunMessage,
+    StunMessageClass, StunMessageMethod, StunMessageRef, StunPasswordAlgorithm,
+    StunTransactionEvent,
+};
+
+const USERNAME: &str = "user";
+const PASSWORD: &str = "pass";
+const REALM: &str = "example.org";
+
+fn server_address() -> SocketAddr {
+    "198.51.100.1:3478".parse().unwrap()
+}
+
+fn long_term_key() -> Vec<u8> {
+    StunMessage::calculate_long_term_key(USERNAME, REALM, PASSWORD, StunPasswordAlgorithm::Md5)
+        .unwrap()
+}
+
+/// Returns the payload of a Transmit event
+fn expect_transmit(event: Option<StunTransactionEvent>) -> Vec<u8> {
+    match event {
+        Some(StunTransactionEvent::Transmit(payload)) => payload,
+        other => panic!("Unexpected event: {:?}", other),
+    }
+}
+
+/// Creates an error response carrying the REALM and NONCE attributes
+fn challenge(request: &[u8], number: u8, reason: &str, nonce: &str) -> Vec<u8> {
+    let request = StunMessageRef::decode(request, None).unwrap();
+    let header = request.get_header();
+
+    StunMessage::new(header.message_method, StunMessageClass::ErrorResponse)
+        .set_transaction_id(header.transaction_id)
+        .add_attribute(StunAttribute::ErrorCode {
+            class: 4,
+            number,
+            reason: String::from(reason),
+        })
+        .add_attribute(StunAttribute::Realm {
+            value: String::from(REALM),
+        })
+        .add_attribute(StunAttribute::Nonce {
+            value: String::from(nonce),
+        })
+        .encode(None)
+        .unwrap()
+}
+
+/// Verifies an authenticated request and creates an authenticated success response
+fn accept(request: &[u8], nonce: &str) -> Vec<u8> {
+    let request = StunMessageRef::decode(request, Some(PASSWORD)).unwrap();
+    let header = request.get_header();
+
+    assert_eq!(request.get_username(), Some(USERNAME));
+    assert_eq!(request.get_realm(), Some(REALM));
+    assert_eq!(request.get_nonce(), Some(nonce));
+
+    StunMessage::new(header.message_method, StunMessageClass::SuccessResponse)
+        .set_transaction_id(header.transaction_id)
+        .add_message_integrity()
+        .encode_with_key(Some(&long_term_key()))
+        .unwrap()
+}
+
+/// Tests the 401 (Unauthorized) handshake and the cached credentials
+#[test]
+fn unauthorized_handshake() {
+    let now = Instant::now();
+    let mut client = LongTermCredentialClient::new(USERNAME, PASSWORD);
+
+    let allocate = StunMessage::new(StunMessageMethod::Allocate, StunMessageClass::Request)
+        .add_attribute(StunAttribute::RequestedTransport { protocol: 17 })
+        .add_fingerprint();
+    client
+        .start_request(server_address(), allocate.clone(), now)
+        .unwrap();
+
+    // The first attempt is not authenticated and keeps the transaction ID of the request
+    let first_attempt = expect_transmit(client.handle_timeout(now));
+    let decoded = StunMessageRef::decode(&first_attempt, None).unwrap();
+    assert_eq!(
+        decoded.get_header().transaction_id,
+        allocate.get_header().transaction_id
+    );
+    assert!(decoded.get_username().is_none());
+    assert!(decoded.get_message_integrity().is_none());
+
+    // The retry carries the credentials, followed by the fingerprint
+    let unauthorized = challenge(&first_attempt, 1, "Unauthorized", "nonce-1");
+    let second_attempt = expect_transmit(client.handle_input(&unauthorized, now).unwrap());
+    let decoded = StunMessageRef::decode(&second_attempt, Some(PASSWORD)).unwrap();
+    assert_ne!(
+        decoded.get_header().transaction_id,
+        allocate.get_header().transaction_id
+    );
+    assert!(matches!(
+        decoded.get_attributes()[0],
+        StunAttributeRef::Other(StunAttribute::RequestedTransport { protocol: 17 })
+    ));
+    assert!(matches!(
+        decoded.get_attributes().last(),
+        Some(StunAttributeRef::Other(StunAttribute::Fingerprint { .. }))
+    ));
+
+    assert_eq!(client.get_realm(server_address()), Some(REALM));
+    assert_eq!(client.get_nonce(server_address()), Some("nonce-1"));
+
+    // Responses failing the integrity check are ignored
+    let forged = StunMessage::create_success_response()
+        .set_message_method(StunMessageMethod::Allocate)
+        .set_transaction_id(decoded.get_header().transaction_id)
+        .add_message_integrity()
+        .encode_with_key(Some(&[0; 16]))
+        .unwrap();
+    assert!(client.handle_input(&forged, now).unwrap().is_none());
+
+    let success = accept(&second_attempt, "nonce-1");
+    match client.handle_input(&success, now).unwrap() {
+        Some(StunTransactionEvent::Response(response)) => assert_eq!(
+            response.get_header().message_class,
+            StunMessageClass::SuccessResponse
+        ),
+        other => panic!("Unexpected event: {:?}", other),
+    }
+    assert!(client.poll_timeout().is_none());
+
+    // Subsequent requests are authenticated right away
+    client
+        .start_request(server_address(), StunMessage::create_request(), now)
+        .unwrap();
+    let request = expect_transmit(client.handle_timeout(now));
+    let success = accept(&request, "nonce-1");
+    assert!(matches!(
+        client.handle_input(&success, now).unwrap(),
+        Some(StunTransactionEvent::Response(_))
+    ));
+}
+
+/// Tests retrying with a new nonce after a 438 (Stale Nonce) response
+#[test]
+fn stale_nonce() {
+    let now = Instant::now();
+    let mut client = LongTermCredentialClient::new(USERNAME, PASSWORD);
+
+    client
+        .start_request(server_address(), StunMessage::create_request(), now)
+        .unwrap();
+    let first_attempt = expect_transmit(client.handle_timeout(now));
+    let unauthorized = challenge(&first_attempt, 1, "Unauthorized", "nonce-1");
+    let second_attempt = expect_transmit(client.handle_input(&unauthorized, now).unwrap());
+
+    let stale = challenge(&second_attempt, 38, "Stale Nonce", "nonce-2");
+    let third_attempt = expect_transmit(client.handle_input(&stale, now).unwrap());
+    assert_eq!(client.get_nonce(server_address()), Some("nonce-2"));
+
+    // The nonce goes stale once more, which isn't retried again
+    let stale = challenge(&third_attempt, 38, "Stale Nonce", "nonce-3");
+    match client.handle_input(&stale, now).unwrap() {
+        Some(StunTransactionEvent::Response(response)) => {
+            assert_eq!(
+                response.get_header().message_class,
+                StunMessageClass::ErrorResponse
+            );
+        }
+        other => panic!("Unexpected event: {:?}", other),
+    }
+
+    // A cached stale nonce is replaced
+    client
+        .start_request(server_address(), StunMessage::create_request(), now)
+        .unwrap();
+    let first_attempt = expect_transmit(client.handle_timeout(now));
+    let decoded = StunMessageRef::decode(&first_attempt, Some(PASSWORD)).unwrap();
+    assert_eq!(decoded.get_nonce(), Some("nonce-2"));
+
+    let stale = challenge(&first_attempt, 38, "Stale Nonce", "nonce-4");
+    let second_attempt = expect_transmit(client.handle_input(&stale, now).unwrap());
+    let success = accept(&second_attempt, "nonce-4");
+    assert!(matches!(
+        client.handle_input(&success, now).unwrap(),
+        Some(StunTransactionEvent::Response(_))
+    ));
+}
+
+/// Tests that rejected credentials are reported instead of being retried forever
+#[test]
+fn rejected_credentials() {
+    let now = Instant::now();
+    let mut client = LongTermCredentialClient::new(USERNAME, "wrong");
+
+    client
+        .start_request(server_address(), StunMessage::create_request(), now)
+        .unwrap();
+    let first_attempt = expect_transmit(client.handle_timeout(now));
+    let unauthorized = challenge(&first_attempt, 1, "Unauthorized", "nonce-1");
+    let second_attempt = expect_transmit(client.handle_input(&unauthorized, now).unwrap());
+
+    // The server fails to verify the request
+    assert!(StunMessageRef::decode(&second_attempt, Some(PASSWORD)).is_err());
+
+    let unauthorized = challenge(&second_attempt, 1, "Unauthorized", "nonce-2");
+    match client.handle_input(&unauthorized, now).unwrap() {
+        Some(StunTransactionEvent::Response(response)) => {
+            assert_eq!(
+                response.get_header().message_class,
+                StunMessageClass::ErrorResponse
+            );
+        }
+        other => panic!("Unexpected event: {:?}", other),
+    }
+    assert!(client.get_transaction().is_none());
+
+    // Other error responses are returned as is
+    client
+        .start_request(server_address(), StunMessage::create_request(), now)
+        .unwrap();
+    let request = StunMessageRef::decode(&expect_transmit(client.handle_timeout(now)), None)
+        .unwrap()
+        .get_header()
+        .transaction_id;
+    let bad_request = StunMessage::create_error_response()
+        .set_transaction_id(request)
+        .add_attribute(StunAttribute::ErrorCode {
+            class: 4,
+            number: 0,
+            reason: String::from("Bad Request"),
+        })
+        .encode(None)
+        .unwrap();
+    assert!(matches!(
+        client.handle_input(&bad_request, now).unwrap(),
+        Some(StunTransactionEvent::Response(_))
+    ));
+}
+
+/// Tests that the configured RTO is used by every attempt
+#[test]
+fn custom_rto() {
+    let now = Instant::now();
+    let rto = Duration::from_millis(150);
+    let mut client = LongTermCredentialClient::new(USERNAME, PASSWORD).set_rto(rto);
+
+    client
+        .start_request(server_address(), StunMessage::create_request(), now)
+        .unwrap();
+    let first_attempt = expect_transmit(client.handle_timeout(now));
+    assert_eq!(client.poll_timeout(), Some(now + rto));
+
+    let retry_time = now + Duration::from_millis(100);
+    let unauthorized = challenge(&first_attempt, 1, "Unauthorized", "nonce-1");
+    expect_transmit(client.handle_input(&unauthorized, retry_time).unwrap());
+    assert_eq!(client.poll_timeout(), Some(retry_time + rto));
+}
+
+/// Tests that the password is processed via SASLprep
+#[test]
+fn password_saslprep() {
+    let now = Instant::now();
+    // The soft hyphen is mapped to nothing
+    let mut client = LongTermCredentialClient::new(USERNAME, "pa\u{00AD}ss");
+
+    client
+        .start_request(server_address(), StunMessage::create_request(), now)
+        .unwrap();
+    let first_attempt = expect_transmit(client.handle_timeout(now));
+
+    let unauthorized = challenge(&first_attempt, 1, "Unauthorized", "nonce-1");
+    let second_attempt = expect_transmit(client.handle_input(&unauthorized, now).unwrap());
+
+    let success = accept(&second_attempt, "nonce-1");
+    assert!(matches!(
+        client.handle_input(&success, now).unwrap(),
+        Some(StunTransactionEvent::Response(_))
+    ));
+}
+
+/// Tests that the retried requests keep the RFC3489 dialect of the request
+#[test]
+fn legacy_request_dialect() {
+    let now = Instant::now();
+    let mut client = LongTermCredentialClient::new(USERNAME, PASSWORD);
+
+    let request = StunMessage::create_request().set_legacy_transaction_id([7; 16]);
+    client
+        .start_request(server_address(), request, now)
+        .unwrap();
+
+    let first_attempt = expect_transmit(client.handle_timeout(now));
+    let decoded = StunMessageRef::decode_legacy(&first_attempt, None).unwrap();
+    assert_eq!(
+        decoded.get_header().dialect,
+        StunDialect::Rfc3489 {
+            transaction_id_prefix: [7; 4]
+        }
+    );
+
+    // Client transactions only decode responses carrying the magic cookie
+    let challenge = StunMessage::create_error_response()
+        .set_transaction_id(decoded.get_header().transaction_id)
+        .add_error_code(4, 1, "Unauthorized")
+        .add_long_term_credential_challenge(REALM, "nonce")
+        .encode(None)
+        .unwrap();
+
+    let second_attempt = expect_transmit(client.handle_input(&challenge, now).unwrap());
+    let decoded = StunMessageRef::decode_legacy(&second_attempt, Some(PASSWORD)).unwrap();
+    let header = decoded.get_header();
+    assert_eq!(
+        header.dialect,
+        StunDialect::Rfc3489 {
+            transaction_id_prefix: [7; 4]
+        }
+    );
+    assert_ne!(header.transaction_id, [7; 12]);
+    assert_eq!(decoded.get_username(), Some(USERNAME));
+}
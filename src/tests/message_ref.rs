@@ -0,0 +1,93 @@
+use crate::{StunAttribute, StunAttributeRef, StunMessage, StunMessageRef};
+
+/// [Sample Request with Long-Term Authentication](https://tools.ietf.org/html/rfc5769#section-2.4)
+const LTA_REQUEST: [u8; 116] = [
+    0x00, 0x01, 0x00, 0x60, 0x21, 0x12, 0xa4, 0x42, 0x78, 0xad, 0x34, 0x33, 0xc6, 0xad, 0x72, 0xc0,
+    0x29, 0xda, 0x41, 0x2e, 0x00, 0x06, 0x00, 0x12, 0xe3, 0x83, 0x9e, 0xe3, 0x83, 0x88, 0xe3, 0x83,
+    0xaa, 0xe3, 0x83, 0x83, 0xe3, 0x82, 0xaf, 0xe3, 0x82, 0xb9, 0x00, 0x00, 0x00, 0x15, 0x00, 0x1c,
+    0x66, 0x2f, 0x2f, 0x34, 0x39, 0x39, 0x6b, 0x39, 0x35, 0x34, 0x64, 0x36, 0x4f, 0x4c, 0x33, 0x34,
+    0x6f, 0x4c, 0x39, 0x46, 0x53, 0x54, 0x76, 0x79, 0x36, 0x34, 0x73, 0x41, 0x00, 0x14, 0x00, 0x0b,
+    0x65, 0x78, 0x61, 0x6d, 0x70, 0x6c, 0x65, 0x2e, 0x6f, 0x72, 0x67, 0x00, 0x00, 0x08, 0x00, 0x14,
+    0xf6, 0x70, 0x24, 0x65, 0x6d, 0xd6, 0x4a, 0x3e, 0x02, 0xb8, 0xe0, 0x71, 0x2e, 0x85, 0xc9, 0xa2,
+    0x8c, 0xa8, 0x96, 0x66,
+];
+
+/// Tests that the borrowed attributes point into the decoded buffer
+#[test]
+fn borrowed_attributes() {
+    let decoded_msg = StunMessageRef::decode(&LTA_REQUEST, Some("TheMatrIX")).unwrap();
+
+    let username = decoded_msg.get_username().unwrap();
+    assert_eq!(username, "\u{30DE}\u{30C8}\u{30EA}\u{30C3}\u{30AF}\u{30B9}");
+    assert_eq!(username.as_ptr(), LTA_REQUEST[24..].as_ptr());
+
+    assert_eq!(decoded_msg.get_realm(), Some("example.org"));
+    assert_eq!(
+        decoded_msg.get_nonce(),
+        Some("f//499k954d6OL34oL9FSTvy64sA")
+    );
+    assert_eq!(
+        decoded_msg.get_message_integrity().unwrap().as_ptr(),
+        LTA_REQUEST[96..].as_ptr()
+    );
+    assert_eq!(decoded_msg.get_software(), None);
+    assert_eq!(decoded_msg.get_attributes().len(), 4);
+}
+
+/// Tests that the owned conversion matches the owned decoder output
+#[test]
+fn into_owned() {
+    let decoded_ref = StunMessageRef::decode(&LTA_REQUEST, Some("TheMatrIX")).unwrap();
+    let decoded_msg = StunMessage::decode(&LTA_REQUEST, Some("TheMatrIX")).unwrap();
+
+    let owned_msg = decoded_ref.into_owned();
+
+    assert_eq!(owned_msg.get_attributes(), decoded_msg.get_attributes());
+    assert_eq!(
+        owned_msg.encode(Some("TheMatrIX")).unwrap(),
+        LTA_REQUEST.to_vec()
+    );
+}
+
+/// Tests typed accessors of the attributes decoded by value
+#[test]
+fn typed_accessors() {
+    let encoded_message = StunMessage::create_request()
+        .add_attribute(StunAttribute::Priority { value: 0x6e0001ff })
+        .add_attribute(StunAttribute::IceControlling {
+            tie_breaker: 0x932ff9b151263b36,
+        })
+        .add_attribute(StunAttribute::UseCandidate)
+        .add_attribute(StunAttribute::XorMappedAddress {
+            socket_addr: "192.0.2.1:32853".parse().unwrap(),
+        })
+        .add_message_integrity()
+        .add_fingerprint()
+        .encode(Some("pass"))
+        .unwrap();
+
+    let decoded_msg = StunMessageRef::decode(&encoded_message, Some("pass")).unwrap();
+
+    assert_eq!(decoded_msg.get_priority(), Some(0x6e0001ff));
+    assert_eq!(decoded_msg.get_ice_controlling(), Some(0x932ff9b151263b36));
+    assert_eq!(decoded_msg.get_ice_controlled(), None);
+    assert!(decoded_msg.has_use_candidate());
+    assert_eq!(
+        decoded_msg.get_xor_mapped_address(),
+        Some("192.0.2.1:32853".parse().unwrap())
+    );
+    assert!(matches!(
+        decoded_msg.get_attributes().last(),
+        Some(StunAttributeRef::Other(StunAttribute::Fingerprint { .. }))
+    ));
+}
+
+/// Tests that truncated attributes are reported instead of being read past the buffer
+#[test]
+fn truncated_attribute() {
+    let mut truncated = LTA_REQUEST[0..30].to_vec();
+    // Adjust the message length to the truncated size
+    truncated[3] = 10;
+
+    assert!(StunMessageRef::decode(&truncated, None).is_err());
+}
@@ -0,0 +1,115 @@
+use std::net::SocketAddr;
+
+use super::check_sample_integrity;
+use crate::{StunAttribute, StunMessage, StunMessageClass, StunMessageMethod, StunMessageRef};
+
+/// Tests an MS-TURN Allocate request decoding/recoding
+#[test]
+fn ms_turn_allocate_request() {
+    let connection_id = [0xAB; 20];
+
+    let message = StunMessage::new(StunMessageMethod::Allocate, StunMessageClass::Request)
+        .add_attribute(StunAttribute::MsVersion { version: 6 })
+        .add_attribute(StunAttribute::MsSequenceNumber {
+            connection_id,
+            sequence_number: 0x0102_0304,
+        })
+        .add_attribute(StunAttribute::MsServiceQuality {
+            stream_type: 1,
+            service_quality: 0,
+        })
+        .add_attribute(StunAttribute::BandwidthAdmissionControl { message_type: 2 })
+        .add_message_integrity();
+
+    let encoded_message = message.encode(Some("pass")).unwrap();
+
+    assert_eq!(
+        encoded_message[20..32],
+        [
+            0x80, 0x08, 0x00, 0x04, 0x00, 0x00, 0x00, 0x06, // MS-VERSION
+            0x80, 0x50, 0x00, 0x18, // MS-SEQUENCE-NUMBER
+        ]
+    );
+    assert_eq!(encoded_message[32..52], connection_id);
+    assert_eq!(
+        encoded_message[52..72],
+        [
+            0x01, 0x02, 0x03, 0x04, // MS-SEQUENCE-NUMBER sequence number
+            0x80, 0x55, 0x00, 0x04, 0x00, 0x01, 0x00, 0x00, // MS-SERVICE-QUALITY
+            0x80, 0x56, 0x00, 0x04, 0x00, 0x00, 0x00,
+            0x02, // BANDWIDTH-ADMISSION-CONTROL-MESSAGE
+        ]
+    );
+
+    check_sample_integrity(&encoded_message, Some("pass"));
+
+    let decoded = StunMessageRef::decode(&encoded_message, Some("pass")).unwrap();
+    assert_eq!(decoded.get_ms_version(), Some(6));
+    assert_eq!(
+        decoded.get_ms_sequence_number(),
+        Some((connection_id, 0x0102_0304))
+    );
+    assert_eq!(decoded.get_ms_service_quality(), Some((1, 0)));
+    assert_eq!(decoded.get_bandwidth_admission_control(), Some(2));
+}
+
+/// Tests an MS-TURN redirect response decoding/recoding
+#[test]
+fn ms_turn_redirect() {
+    let alternate_server: SocketAddr = "198.51.100.7:443".parse().unwrap();
+
+    let message = StunMessage::new(StunMessageMethod::Allocate, StunMessageClass::ErrorResponse)
+        .add_attribute(StunAttribute::ErrorCode {
+            class: 3,
+            number: 0,
+            reason: String::from("Try Alternate"),
+        })
+        .add_attribute(StunAttribute::AlternateServer {
+            socket_addr: alternate_server,
+        })
+        .add_attribute(StunAttribute::MsAlternateHostName {
+            host_name: String::from("edge.example.com"),
+        });
+
+    let encoded_message = message.encode(None).unwrap();
+
+    check_sample_integrity(&encoded_message, None);
+
+    let decoded = StunMessageRef::decode(&encoded_message, None).unwrap();
+    assert_eq!(
+        decoded.get_ms_alternate_host_name(),
+        Some("edge.example.com")
+    );
+}
+
+/// Tests an MS-ICE2 connectivity check decoding/recoding
+#[test]
+fn ms_ice2_connectivity_check() {
+    let message = StunMessage::create_request()
+        .add_attribute(StunAttribute::Priority { value: 0x6E00_01FF })
+        .add_attribute(StunAttribute::CandidateIdentifier {
+            foundation: [0x0A, 0x0B, 0x0C, 0x0D],
+        })
+        .add_attribute(StunAttribute::MsImplementationVersion { version: 2 })
+        .add_message_integrity()
+        .add_fingerprint();
+
+    let encoded_message = message.encode(Some("ice-pwd")).unwrap();
+
+    assert_eq!(
+        encoded_message[28..44],
+        [
+            0x80, 0x54, 0x00, 0x04, 0x0A, 0x0B, 0x0C, 0x0D, // CANDIDATE-IDENTIFIER
+            0x80, 0x70, 0x00, 0x04, 0x00, 0x00, 0x00, 0x02, // MS-IMPLEMENTATION-VERSION
+        ]
+    );
+
+    check_sample_integrity(&encoded_message, Some("ice-pwd"));
+
+    let decoded = StunMessageRef::decode(&encoded_message, Some("ice-pwd")).unwrap();
+    assert_eq!(
+        decoded.get_candidate_identifier(),
+        Some([0x0A, 0x0B, 0x0C, 0x0D])
+    );
+    assert_eq!(decoded.get_ms_implementation_version(), Some(2));
+}
@@ -1,3 +1,22 @@
+mod access_token;
+mod channel_data;
+mod encode;
+mod extensions;
+mod header;
+mod integrity_sha256;
+mod legacy;
+mod long_term_credential;
+mod message_ref;
+mod microsoft;
+mod nat_behavior;
+mod nat_discovery;
+mod server;
+mod transaction;
+mod turn;
+mod unknown_attributes;
+mod userhash;
+mod webrtc;
+
 use crate::{check_for_stun_message_header, StunAttribute, StunMessage};
 
 /// Utility function to test sample payloads
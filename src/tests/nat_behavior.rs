@@ -0,0 +1,84 @@
+use std::net::SocketAddr;
+
+use super::check_sample_integrity;
+use crate::{StunAttribute, StunMessage, StunMessageRef};
+
+const TRANSACTION_ID: [u8; 12] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+
+/// Binding request with the CHANGE-REQUEST, RESPONSE-PORT and PADDING attributes
+#[test]
+fn binding_request() {
+    let message = StunMessage::create_request()
+        .add_attribute(StunAttribute::ChangeRequest {
+            change_ip: true,
+            change_port: true,
+        })
+        .add_attribute(StunAttribute::ResponsePort { port: 0x1234 })
+        .add_attribute(StunAttribute::Padding {
+            value: vec![0u8; 5],
+        })
+        .set_transaction_id(TRANSACTION_ID);
+
+    let encoded = message.encode(None).unwrap();
+
+    let encoded_match = vec![
+        0x00, 0x01, 0x00, 0x1C, 0x21, 0x12, 0xA4, 0x42, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07,
+        0x08, 0x09, 0x0A, 0x0B, 0x0C, // Header
+        0x00, 0x03, 0x00, 0x04, 0x00, 0x00, 0x00, 0x06, // CHANGE-REQUEST
+        0x00, 0x27, 0x00, 0x04, 0x12, 0x34, 0x00, 0x00, // RESPONSE-PORT
+        0x00, 0x26, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // PADDING
+    ];
+    assert_eq!(encoded, encoded_match);
+
+    let decoded = StunMessageRef::decode(&encoded, None).unwrap();
+    assert_eq!(decoded.get_change_request(), Some((true, true)));
+    assert_eq!(decoded.get_response_port(), Some(0x1234));
+
+    // Only one of the flags
+    let change_port_only = StunMessage::create_request()
+        .add_attribute(StunAttribute::ChangeRequest {
+            change_ip: false,
+            change_port: true,
+        })
+        .encode(None)
+        .unwrap();
+    assert_eq!(
+        &change_port_only[20..],
+        &[0x00, 0x03, 0x00, 0x04, 0x00, 0x00, 0x00, 0x02]
+    );
+}
+
+/// Binding response with the RESPONSE-ORIGIN and OTHER-ADDRESS attributes
+#[test]
+fn binding_response() {
+    let mapped: SocketAddr = "192.0.2.1:32853".parse().unwrap();
+    let origin: SocketAddr = "198.51.100.1:3478".parse().unwrap();
+    let other: SocketAddr = "[2001:db8::2]:3479".parse().unwrap();
+
+    let message = StunMessage::create_success_response()
+        .add_attribute(StunAttribute::XorMappedAddress {
+            socket_addr: mapped,
+        })
+        .add_attribute(StunAttribute::ResponseOrigin {
+            socket_addr: origin,
+        })
+        .add_attribute(StunAttribute::OtherAddress { socket_addr: other })
+        .add_message_integrity()
+        .add_fingerprint()
+        .set_transaction_id(TRANSACTION_ID);
+
+    let encoded = message.encode(Some("pass")).unwrap();
+
+    // RESPONSE-ORIGIN is not XOR-ed
+    assert_eq!(
+        &encoded[32..44],
+        &[0x80, 0x2B, 0x00, 0x08, 0x00, 0x01, 0x0D, 0x96, 198, 51, 100, 1]
+    );
+
+    let decoded = StunMessageRef::decode(&encoded, Some("pass")).unwrap();
+    assert_eq!(decoded.get_xor_mapped_address(), Some(mapped));
+    assert_eq!(decoded.get_response_origin(), Some(origin));
+    assert_eq!(decoded.get_other_address(), Some(other));
+
+    check_sample_integrity(&encoded, Some("pass"));
+}
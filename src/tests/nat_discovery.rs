@@ -0,0 +1,382 @@
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, SocketAddr};
+use std::time::{Duration, Instant};
+
+use crate::{
+    NatBehaviorDiscovery, NatBehaviorReport, NatDiscoveryError, NatFilteringBehavior,
+    NatMappingBehavior, NatTest, StunAttribute, StunMessage, StunMessageRef,
+};
+
+/// Stand-in RFC5780 server listening on two loopback addresses
+struct StandInServer {
+    primary: SocketAddr,
+    alternate: SocketAddr,
+    advertise_other_address: bool,
+    honor_change_request: bool,
+}
+
+impl StandInServer {
+    fn new() -> Self {
+        Self {
+            primary: "127.0.0.1:3478".parse().unwrap(),
+            alternate: "127.0.0.2:3479".parse().unwrap(),
+            advertise_other_address: true,
+            honor_change_request: true,
+        }
+    }
+
+    /// Returns the response and the address it is sent from
+    fn respond(
+        &self,
+        request: &[u8],
+        received_on: SocketAddr,
+        from: SocketAddr,
+    ) -> (SocketAddr, Vec<u8>) {
+        let request = StunMessageRef::decode(request, None).unwrap();
+        let (change_ip, change_port) = match request.get_change_request() {
+            Some(change_request) if self.honor_change_request => change_request,
+            _ => (false, false),
+        };
+
+        let ip = if change_ip == (received_on.ip() == self.primary.ip()) {
+            self.alternate.ip()
+        } else {
+            self.primary.ip()
+        };
+        let port = if change_port == (received_on.port() == self.primary.port()) {
+            self.alternate.port()
+        } else {
+            self.primary.port()
+        };
+        let source = SocketAddr::new(ip, port);
+
+        let mut response = StunMessage::create_success_response()
+            .set_transaction_id(request.get_header().transaction_id)
+            .add_attribute(StunAttribute::XorMappedAddress { socket_addr: from })
+            .add_attribute(StunAttribute::ResponseOrigin {
+                socket_addr: source,
+            });
+
+        if self.advertise_other_address {
+            response = response.add_attribute(StunAttribute::OtherAddress {
+                socket_addr: self.alternate,
+            });
+        }
+
+        (source, response.encode(None).unwrap())
+    }
+}
+
+/// Simulated NAT in front of the client
+struct SimulatedNat {
+    mapping: NatMappingBehavior,
+    filtering: NatFilteringBehavior,
+    public_ip: IpAddr,
+    mappings: HashMap<Option<SocketAddr>, SocketAddr>,
+    contacted: HashSet<SocketAddr>,
+}
+
+impl SimulatedNat {
+    fn new(mapping: NatMappingBehavior, filtering: NatFilteringBehavior) -> Self {
+        Self {
+            mapping,
+            filtering,
+            public_ip: "127.0.0.100".parse().unwrap(),
+            mappings: HashMap::new(),
+            contacted: HashSet::new(),
+        }
+    }
+
+    /// Returns the public transport address for a packet sent to `destination`
+    fn outbound(&mut self, destination: SocketAddr) -> SocketAddr {
+        self.contacted.insert(destination);
+
+        let key = match self.mapping {
+            NatMappingBehavior::EndpointIndependent => None,
+            NatMappingBehavior::AddressDependent => Some(SocketAddr::new(destination.ip(), 0)),
+            NatMappingBehavior::AddressAndPortDependent => Some(destination),
+        };
+
+        let next_port = 40000 + self.mappings.len() as u16;
+        let public_ip = self.public_ip;
+
+        *self
+            .mappings
+            .entry(key)
+            .or_insert_with(|| SocketAddr::new(public_ip, next_port))
+    }
+
+    /// Checks whether a packet from `source` passes through
+    fn inbound(&self, source: SocketAddr) -> bool {
+        match self.filtering {
+            NatFilteringBehavior::EndpointIndependent => true,
+            NatFilteringBehavior::AddressDependent => {
+                self.contacted.iter().any(|addr| addr.ip() == source.ip())
+            }
+            NatFilteringBehavior::AddressAndPortDependent => self.contacted.contains(&source),
+        }
+    }
+}
+
+/// Runs the discovery, optionally through a NAT. An unreachable server is simulated with `server: None`.
+/// With `lossy` set, the first transmission of every test request is lost.
+fn run(
+    server: Option<&StandInServer>,
+    mut nat: Option<SimulatedNat>,
+    lossy: bool,
+) -> Result<NatBehaviorReport, NatDiscoveryError> {
+    let local_address: SocketAddr = "127.0.0.1:50000".parse().unwrap();
+
+    let mut discovery = NatBehaviorDiscovery::new("127.0.0.1:3478".parse().unwrap(), local_address)
+        .set_rto(Duration::from_millis(100))
+        .set_max_transmissions(3);
+    let mut now = Instant::now();
+    let mut previous_payload = Vec::new();
+
+    loop {
+        if let Some(report) = discovery.poll_report() {
+            return report;
+        }
+
+        let transmit = discovery.poll_transmit(now).unwrap();
+
+        // No datagrams are accepted before the request is sent
+        assert!(discovery.poll_transmit(now).is_none());
+
+        let first_transmission = transmit.payload != previous_payload;
+        previous_payload = transmit.payload.clone();
+
+        let response = server
+            .filter(|_| !(lossy && first_transmission))
+            .map(|server| {
+                let from = match &mut nat {
+                    Some(nat) => nat.outbound(transmit.destination),
+                    None => local_address,
+                };
+
+                server.respond(&transmit.payload, transmit.destination, from)
+            });
+
+        match response {
+            Some((source, bytes))
+                if nat.as_ref().map(|nat| nat.inbound(source)).unwrap_or(true)
+                    && discovery.handle_input(&bytes, source) => {}
+            _ => {
+                // Nothing happens before the deadline
+                let deadline = discovery.poll_timeout().unwrap();
+                assert!(deadline > now);
+                discovery.handle_timeout(now);
+                assert!(discovery.poll_transmit(now).is_none());
+
+                now = deadline;
+                discovery.handle_timeout(now);
+            }
+        }
+    }
+}
+
+#[test]
+fn no_nat() {
+    let report = run(Some(&StandInServer::new()), None, false).unwrap();
+
+    assert!(!report.behind_nat);
+    assert_eq!(report.mapped_address, report.local_address);
+    assert_eq!(report.other_address, "127.0.0.2:3479".parse().unwrap());
+    assert_eq!(report.mapping, NatMappingBehavior::EndpointIndependent);
+    assert_eq!(report.filtering, NatFilteringBehavior::EndpointIndependent);
+}
+
+#[test]
+fn nat_behaviors() {
+    let behaviors = [
+        (
+            NatMappingBehavior::EndpointIndependent,
+            NatFilteringBehavior::AddressDependent,
+        ),
+        (
+            NatMappingBehavior::AddressDependent,
+            NatFilteringBehavior::AddressAndPortDependent,
+        ),
+        (
+            NatMappingBehavior::AddressAndPortDependent,
+            NatFilteringBehavior::EndpointIndependent,
+        ),
+    ];
+
+    for (mapping, filtering) in behaviors.iter() {
+        let nat = SimulatedNat::new(*mapping, *filtering);
+        let report = run(Some(&StandInServer::new()), Some(nat), false).unwrap();
+
+        assert!(report.behind_nat);
+        assert_eq!(
+            report.mapped_address.ip(),
+            "127.0.0.100".parse::<IpAddr>().unwrap()
+        );
+        assert_eq!(report.mapping, *mapping);
+        assert_eq!(report.filtering, *filtering);
+    }
+}
+
+/// Lost datagrams are retransmitted, so they don't affect the result
+#[test]
+fn lost_datagrams() {
+    let nat = SimulatedNat::new(
+        NatMappingBehavior::EndpointIndependent,
+        NatFilteringBehavior::EndpointIndependent,
+    );
+    let report = run(Some(&StandInServer::new()), Some(nat), true).unwrap();
+
+    assert_eq!(report.mapping, NatMappingBehavior::EndpointIndependent);
+    assert_eq!(report.filtering, NatFilteringBehavior::EndpointIndependent);
+}
+
+/// Requests are retransmitted with a doubling interval
+#[test]
+fn retransmissions() {
+    let server = StandInServer::new();
+    let local_address: SocketAddr = "127.0.0.1:50000".parse().unwrap();
+    let rto = Duration::from_millis(100);
+    let mut discovery = NatBehaviorDiscovery::new(server.primary, local_address)
+        .set_rto(rto)
+        .set_max_transmissions(3);
+    let start = Instant::now();
+
+    let transmit = discovery.poll_transmit(start).unwrap();
+    assert_eq!(discovery.poll_timeout(), Some(start + rto));
+
+    discovery.handle_timeout(start + rto);
+    assert_eq!(discovery.poll_transmit(start + rto), Some(transmit.clone()));
+    assert_eq!(discovery.poll_timeout(), Some(start + rto * 3));
+
+    discovery.handle_timeout(start + rto * 3);
+    assert_eq!(discovery.poll_transmit(start + rto * 3), Some(transmit));
+
+    // The last transmission waits Rm times the RTO
+    let deadline = discovery.poll_timeout().unwrap();
+    assert_eq!(deadline, start + rto * 3 + rto * 16);
+    discovery.handle_timeout(deadline);
+
+    assert!(matches!(
+        discovery.poll_report(),
+        Some(Err(NatDiscoveryError::Timeout(NatTest::Binding)))
+    ));
+}
+
+/// At least one transmission is done, even if no transmissions are allowed
+#[test]
+fn single_transmission() {
+    let server = StandInServer::new();
+    let local_address: SocketAddr = "127.0.0.1:50000".parse().unwrap();
+    let rto = Duration::from_millis(100);
+    let mut discovery = NatBehaviorDiscovery::new(server.primary, local_address)
+        .set_rto(rto)
+        .set_max_transmissions(0);
+    let start = Instant::now();
+
+    assert!(discovery.poll_transmit(start).is_some());
+    assert!(discovery.poll_transmit(start).is_none());
+
+    let deadline = discovery.poll_timeout().unwrap();
+    assert_eq!(deadline, start + rto * 16);
+    discovery.handle_timeout(deadline);
+
+    assert!(matches!(
+        discovery.poll_report(),
+        Some(Err(NatDiscoveryError::Timeout(NatTest::Binding)))
+    ));
+}
+
+#[test]
+fn unsupported_server() {
+    let mut server = StandInServer::new();
+    server.advertise_other_address = false;
+
+    assert!(matches!(
+        run(Some(&server), None, false),
+        Err(NatDiscoveryError::MissingOtherAddress())
+    ));
+}
+
+/// Responses to the filtering tests sent from the primary server address fail the discovery
+#[test]
+fn change_request_ignored() {
+    let mut server = StandInServer::new();
+    server.honor_change_request = false;
+
+    assert!(matches!(
+        run(Some(&server), None, false),
+        Err(NatDiscoveryError::ChangeRequestNotHonored(
+            NatTest::FilteringChangeAddressAndPort
+        ))
+    ));
+}
+
+/// Responses whose RESPONSE-ORIGIN doesn't match their source address are ignored
+#[test]
+fn response_origin_mismatch() {
+    let server = StandInServer::new();
+    let local_address: SocketAddr = "127.0.0.1:50000".parse().unwrap();
+    let mut discovery = NatBehaviorDiscovery::new(server.primary, local_address);
+    let transmit = discovery.poll_transmit(Instant::now()).unwrap();
+
+    let request = StunMessageRef::decode(&transmit.payload, None).unwrap();
+    let response = StunMessage::create_success_response()
+        .set_transaction_id(request.get_header().transaction_id)
+        .add_attribute(StunAttribute::XorMappedAddress {
+            socket_addr: local_address,
+        })
+        .add_attribute(StunAttribute::ResponseOrigin {
+            socket_addr: server.alternate,
+        })
+        .add_attribute(StunAttribute::OtherAddress {
+            socket_addr: server.alternate,
+        })
+        .encode(None)
+        .unwrap();
+
+    assert!(!discovery.handle_input(&response, server.primary));
+    assert_eq!(discovery.get_current_test(), Some(NatTest::Binding));
+}
+
+#[test]
+fn unreachable_server() {
+    assert!(matches!(
+        run(None, None, false),
+        Err(NatDiscoveryError::Timeout(NatTest::Binding))
+    ));
+}
+
+/// Datagrams that don't match the test in progress are ignored
+#[test]
+fn unrelated_datagrams() {
+    let server = StandInServer::new();
+    let local_address: SocketAddr = "127.0.0.1:50000".parse().unwrap();
+    let mut discovery = NatBehaviorDiscovery::new(server.primary, local_address);
+
+    let transmit = discovery.poll_transmit(Instant::now()).unwrap();
+    assert_eq!(transmit.destination, server.primary);
+    assert_eq!(discovery.get_current_test(), Some(NatTest::Binding));
+
+    // Response to another transaction
+    let other_request = StunMessage::create_request().encode(None).unwrap();
+    let (_, other_response) = server.respond(&other_request, server.primary, local_address);
+    assert!(!discovery.handle_input(&other_response, server.primary));
+    // Not a STUN message
+    assert!(!discovery.handle_input(&[0x40, 0x00, 0x00, 0x00], server.primary));
+
+    assert_eq!(discovery.get_current_test(), Some(NatTest::Binding));
+
+    // Response from an unexpected address
+    let (source, response) = server.respond(&transmit.payload, server.primary, local_address);
+    assert!(!discovery.handle_input(&response, server.alternate));
+
+    assert!(discovery.handle_input(&response, source));
+    assert_eq!(
+        discovery.get_current_test(),
+        Some(NatTest::FilteringChangeAddressAndPort)
+    );
+    assert_eq!(
+        discovery.poll_transmit(Instant::now()).unwrap().destination,
+        server.primary
+    );
+}
@@ -0,0 +1,910 @@
+use std::cell::Cell;
+use std::net::SocketAddr;
+use std::rc::Rc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use super::check_sample_integrity;
+use crate::{
+    CredentialStore, InMemoryCredentialStore, InMemoryNonceSource, LongTermAuthenticator,
+    LongTermCredentialClient, NonceError, NonceSource, StatelessNonce, StunAttribute,
+    StunAttributeRef, StunAuthentication, StunBindingResponder, StunClock, StunDialect,
+    StunMessage, StunMessageClass, StunMessageMethod, StunMessageRef, StunPasswordAlgorithm,
+    StunResponseCache, StunSecurityFeatures, StunTransactionEvent,
+};
+
+fn client_address() -> SocketAddr {
+    "192.0.2.1:32853".parse().unwrap()
+}
+
+/// Tests answering Binding requests
+#[test]
+fn binding_success_response() {
+    let responder = StunBindingResponder::new();
+    let request = StunMessage::create_request();
+    let transaction_id = request.get_header().transaction_id;
+
+    let response = responder
+        .handle_request(&request.encode(None).unwrap(), client_address())
+        .unwrap()
+        .unwrap();
+    check_sample_integrity(&response, None);
+
+    let decoded = StunMessageRef::decode(&response, None).unwrap();
+    let header = decoded.get_header();
+    assert_eq!(header.transaction_id, transaction_id);
+    assert_eq!(header.message_class, StunMessageClass::SuccessResponse);
+    assert_eq!(header.message_method, StunMessageMethod::BindingRequest);
+    assert_eq!(decoded.get_xor_mapped_address(), Some(client_address()));
+    assert_eq!(decoded.get_mapped_address(), None);
+    assert_eq!(decoded.get_attributes().len(), 1);
+
+    // MAPPED-ADDRESS, SOFTWARE and FINGERPRINT
+    let responder = StunBindingResponder::new()
+        .set_mapped_address(true)
+        .set_software("rust-stun-coder");
+    let request = StunMessage::create_request().add_fingerprint();
+
+    let response = responder
+        .handle_request(&request.encode(None).unwrap(), client_address())
+        .unwrap()
+        .unwrap();
+    check_sample_integrity(&response, None);
+
+    let decoded = StunMessageRef::decode(&response, None).unwrap();
+    assert_eq!(decoded.get_xor_mapped_address(), Some(client_address()));
+    assert_eq!(decoded.get_mapped_address(), Some(client_address()));
+    assert_eq!(decoded.get_software(), Some("rust-stun-coder"));
+    assert!(matches!(
+        decoded.get_attributes().last(),
+        Some(StunAttributeRef::Other(StunAttribute::Fingerprint { .. }))
+    ));
+}
+
+/// Tests answering Binding requests containing unknown comprehension-required attributes
+#[test]
+fn unknown_attributes_error_response() {
+    let responder = StunBindingResponder::new();
+    let request = StunMessage::create_request()
+        .add_attribute(StunAttribute::Unknown {
+            attr_type: 0x7F00,
+            value: vec![0x01, 0x02, 0x03, 0x04],
+        })
+        // Comprehension-optional attributes are not reported
+        .add_attribute(StunAttribute::Unknown {
+            attr_type: 0xC0FF,
+            value: vec![0x01],
+        })
+        .add_attribute(StunAttribute::Unknown {
+            attr_type: 0x0042,
+            value: vec![],
+        });
+
+    let response = responder
+        .handle_request(&request.encode(None).unwrap(), client_address())
+        .unwrap()
+        .unwrap();
+    check_sample_integrity(&response, None);
+
+    let decoded = StunMessageRef::decode(&response, None).unwrap();
+    let header = decoded.get_header();
+    assert_eq!(header.transaction_id, request.get_header().transaction_id);
+    assert_eq!(header.message_class, StunMessageClass::ErrorResponse);
+    assert_eq!(header.message_method, StunMessageMethod::BindingRequest);
+    assert_eq!(decoded.get_error_code(), Some((4, 20, "Unknown Attribute")));
+    assert_eq!(
+        decoded.get_unknown_attributes(),
+        Some(&[0x7F00, 0x0042][..])
+    );
+    assert!(decoded.get_xor_mapped_address().is_none());
+}
+
+/// Tests that messages other than Binding requests are ignored
+#[test]
+fn ignored_messages() {
+    let responder = StunBindingResponder::new();
+
+    let indication = StunMessage::create_indication().encode(None).unwrap();
+    assert!(responder
+        .handle_request(&indication, client_address())
+        .unwrap()
+        .is_none());
+
+    let response = StunMessage::create_success_response().encode(None).unwrap();
+    assert!(responder
+        .handle_request(&response, client_address())
+        .unwrap()
+        .is_none());
+
+    let allocate = StunMessage::new(StunMessageMethod::Allocate, StunMessageClass::Request)
+        .encode(None)
+        .unwrap();
+    assert!(responder
+        .handle_request(&allocate, client_address())
+        .unwrap()
+        .is_none());
+
+    // Malformed message
+    let mut truncated = StunMessage::create_request()
+        .add_attribute(StunAttribute::Software {
+            description: String::from("rust-stun-coder"),
+        })
+        .encode(None)
+        .unwrap();
+    truncated.truncate(26);
+    assert!(responder
+        .handle_request(&truncated, client_address())
+        .unwrap()
+        .is_none());
+}
+
+/// Tests answering classic RFC3489 Binding requests
+#[test]
+fn legacy_binding_request() {
+    let transaction_id = [
+        0xb7, 0xe7, 0xa7, 0x01, 0xbc, 0x34, 0xd6, 0x86, 0xfa, 0x87, 0xdf, 0xae, 0x01, 0x02, 0x03,
+        0x04,
+    ];
+    let request = StunMessage::create_request()
+        .set_legacy_transaction_id(transaction_id)
+        .encode(None)
+        .unwrap();
+
+    // Classic requests are not answered by default
+    assert!(StunBindingResponder::new()
+        .handle_request(&request, client_address())
+        .unwrap()
+        .is_none());
+
+    let response = StunBindingResponder::new()
+        .set_legacy_requests(true)
+        .handle_request(&request, client_address())
+        .unwrap()
+        .unwrap();
+
+    let decoded = StunMessageRef::decode_legacy(&response, None).unwrap();
+    let header = decoded.get_header();
+    assert_eq!(
+        header.dialect,
+        StunDialect::Rfc3489 {
+            transaction_id_prefix: [0xb7, 0xe7, 0xa7, 0x01]
+        }
+    );
+    assert_eq!(header.get_legacy_transaction_id(), Some(transaction_id));
+    assert_eq!(decoded.get_mapped_address(), Some(client_address()));
+    assert!(decoded.get_xor_mapped_address().is_none());
+}
+
+/// Manually advanced clock
+#[derive(Clone)]
+struct ManualClock(Rc<Cell<Instant>>);
+
+impl ManualClock {
+    fn advance(&self, duration: Duration) {
+        self.0.set(self.0.get() + duration);
+    }
+}
+
+impl StunClock for ManualClock {
+    fn now(&self) -> Instant {
+        self.0.get()
+    }
+}
+
+/// Tests replaying responses to retransmitted requests
+#[test]
+fn response_cache_replay() {
+    let clock = ManualClock(Rc::new(Cell::new(Instant::now())));
+    let mut cache = StunResponseCache::with_clock(clock.clone());
+    assert!(cache.is_empty());
+
+    let other_client = "192.0.2.2:32853".parse().unwrap();
+    let request = StunMessage::create_request();
+    let transaction_id = request.get_header().transaction_id;
+    let encoded_request = request.encode(None).unwrap();
+
+    let response = StunBindingResponder::new()
+        .handle_request(&encoded_request, client_address())
+        .unwrap()
+        .unwrap();
+    cache.insert_for_request(client_address(), &encoded_request, response.clone());
+    assert_eq!(cache.len(), 1);
+
+    // Retransmission
+    clock.advance(Duration::from_millis(500));
+    assert_eq!(
+        cache.get_for_request(client_address(), &encoded_request),
+        Some(&response[..])
+    );
+    assert_eq!(
+        cache.get(client_address(), &transaction_id),
+        Some(&response[..])
+    );
+
+    // Same transaction ID from another client
+    assert!(cache.get(other_client, &transaction_id).is_none());
+    // Not a STUN message
+    assert!(cache
+        .get_for_request(client_address(), &[0xFF; 20])
+        .is_none());
+
+    // The response expires after 40 seconds
+    clock.advance(Duration::from_millis(39499));
+    assert!(cache.get(client_address(), &transaction_id).is_some());
+    clock.advance(Duration::from_millis(1));
+    assert!(cache.get(client_address(), &transaction_id).is_none());
+
+    cache.purge();
+    assert!(cache.is_empty());
+
+    // Removing a response
+    cache.insert(other_client, transaction_id, response.clone());
+    assert_eq!(cache.remove(other_client, &transaction_id), Some(response));
+    assert!(cache.get(other_client, &transaction_id).is_none());
+}
+
+/// Tests the cache capacity and custom lifetime
+#[test]
+fn response_cache_bounds() {
+    let clock = ManualClock(Rc::new(Cell::new(Instant::now())));
+    let mut cache = StunResponseCache::with_clock(clock.clone())
+        .set_capacity(2)
+        .set_lifetime(Duration::from_secs(5));
+
+    cache.insert(client_address(), [1; 12], vec![1]);
+    clock.advance(Duration::from_secs(1));
+    cache.insert(client_address(), [2; 12], vec![2]);
+    clock.advance(Duration::from_secs(1));
+
+    // The oldest response is evicted
+    cache.insert(client_address(), [3; 12], vec![3]);
+    assert_eq!(cache.len(), 2);
+    assert!(cache.get(client_address(), &[1; 12]).is_none());
+    assert_eq!(cache.get(client_address(), &[2; 12]), Some(&[2u8][..]));
+
+    // Replacing a response refreshes its lifetime without evicting others
+    clock.advance(Duration::from_secs(1));
+    cache.insert(client_address(), [2; 12], vec![4]);
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get(client_address(), &[2; 12]), Some(&[4u8][..]));
+
+    clock.advance(Duration::from_secs(4));
+    cache.purge();
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.get(client_address(), &[2; 12]), Some(&[4u8][..]));
+
+    // Expired responses are purged before evicting live ones
+    clock.advance(Duration::from_secs(1));
+    cache.insert(client_address(), [5; 12], vec![5]);
+    cache.insert(client_address(), [6; 12], vec![6]);
+    assert_eq!(cache.get(client_address(), &[5; 12]), Some(&[5u8][..]));
+    assert_eq!(cache.get(client_address(), &[6; 12]), Some(&[6u8][..]));
+
+    // Nothing is cached without capacity
+    let mut cache = StunResponseCache::with_clock(clock).set_capacity(0);
+    cache.insert(client_address(), [1; 12], vec![1]);
+    assert!(cache.is_empty());
+}
+
+/// Tests replacing and removing responses with a frozen clock
+#[test]
+fn response_cache_replacement() {
+    let clock = ManualClock(Rc::new(Cell::new(Instant::now())));
+    let mut cache = StunResponseCache::with_clock(clock.clone()).set_capacity(1);
+
+    // Replacing the only response many times keeps it cached
+    for value in 0..1000u16 {
+        cache.insert(client_address(), [1; 12], value.to_be_bytes().to_vec());
+        assert_eq!(cache.len(), 1);
+    }
+    assert_eq!(
+        cache.get(client_address(), &[1; 12]),
+        Some(&[0x03u8, 0xE7][..])
+    );
+
+    cache.insert(client_address(), [2; 12], vec![2]);
+    assert_eq!(cache.len(), 1);
+    assert!(cache.get(client_address(), &[1; 12]).is_none());
+    assert_eq!(cache.get(client_address(), &[2; 12]), Some(&[2u8][..]));
+
+    // A replaced response is the newest one, even if the clock doesn't move
+    let mut cache = StunResponseCache::with_clock(clock).set_capacity(2);
+    cache.insert(client_address(), [1; 12], vec![1]);
+    cache.insert(client_address(), [2; 12], vec![2]);
+    cache.insert(client_address(), [1; 12], vec![3]);
+    cache.insert(client_address(), [4; 12], vec![4]);
+    assert!(cache.get(client_address(), &[2; 12]).is_none());
+    assert_eq!(cache.get(client_address(), &[1; 12]), Some(&[3u8][..]));
+
+    // Removed responses don't take any room
+    assert_eq!(cache.remove(client_address(), &[1; 12]), Some(vec![3]));
+    assert!(cache.remove(client_address(), &[1; 12]).is_none());
+    cache.insert(client_address(), [5; 12], vec![5]);
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get(client_address(), &[4; 12]), Some(&[4u8][..]));
+    assert_eq!(cache.get(client_address(), &[5; 12]), Some(&[5u8][..]));
+}
+
+/// Returns the error response of a rejected request
+fn expect_rejected(authentication: StunAuthentication) -> Vec<u8> {
+    match authentication {
+        StunAuthentication::Rejected(response) => response,
+        other => panic!("Unexpected authentication outcome: {:?}", other),
+    }
+}
+
+fn credential_store() -> InMemoryCredentialStore {
+    let mut store = InMemoryCredentialStore::new();
+    store.add_user("user", "example.org", "pass").unwrap();
+
+    store
+}
+
+/// Tests the in-memory credential store
+#[test]
+fn in_memory_credential_store() {
+    let mut store = credential_store();
+
+    let key = StunMessage::calculate_long_term_key(
+        "user",
+        "example.org",
+        "pass",
+        StunPasswordAlgorithm::Md5,
+    )
+    .unwrap();
+    let sha256_key = StunMessage::calculate_long_term_key(
+        "user",
+        "example.org",
+        "pass",
+        StunPasswordAlgorithm::Sha256,
+    )
+    .unwrap();
+    let md5 = StunPasswordAlgorithm::Md5;
+    assert_eq!(store.get_key("user", "example.org", md5), Some(key));
+    assert_eq!(
+        store.get_key("user", "example.org", StunPasswordAlgorithm::Sha256),
+        Some(sha256_key)
+    );
+    assert!(store
+        .get_key("user", "example.org", StunPasswordAlgorithm::Other(3))
+        .is_none());
+    assert!(store.get_key("user", "example.com", md5).is_none());
+    assert!(store.get_key("other", "example.org", md5).is_none());
+
+    store.add_key("other", "example.org", md5, vec![1; 16]);
+    assert_eq!(
+        store.get_key("other", "example.org", md5),
+        Some(vec![1; 16])
+    );
+    assert!(store
+        .get_key("other", "example.org", StunPasswordAlgorithm::Sha256)
+        .is_none());
+
+    // Keys of other password algorithms are kept
+    store.add_key(
+        "other",
+        "example.org",
+        StunPasswordAlgorithm::Sha256,
+        vec![2; 32],
+    );
+    assert_eq!(
+        store.get_key("other", "example.org", md5),
+        Some(vec![1; 16])
+    );
+
+    assert!(store.remove_user("other", "example.org"));
+    assert!(!store.remove_user("other", "example.org"));
+    assert!(store.get_key("other", "example.org", md5).is_none());
+    assert!(store
+        .get_key("other", "example.org", StunPasswordAlgorithm::Sha256)
+        .is_none());
+}
+
+/// Tests that the in-memory nonce source is bounded
+#[test]
+fn in_memory_nonce_source() {
+    let clock = ManualClock(Rc::new(Cell::new(Instant::now())));
+    let mut nonces = InMemoryNonceSource::with_clock(clock.clone())
+        .set_capacity(2)
+        .set_lifetime(Duration::from_secs(10));
+
+    let first = nonces.create_nonce(client_address());
+    clock.advance(Duration::from_secs(1));
+    let second = nonces.create_nonce(client_address());
+    assert_ne!(first, second);
+    assert!(nonces.validate_nonce(&first, client_address()));
+    assert!(!nonces.validate_nonce("unknown", client_address()));
+
+    // The oldest nonce is evicted once the capacity is reached
+    let third = nonces.create_nonce(client_address());
+    assert_eq!(nonces.len(), 2);
+    assert!(!nonces.validate_nonce(&first, client_address()));
+    assert!(nonces.validate_nonce(&second, client_address()));
+
+    // Flooding the source doesn't grow it
+    for _ in 0..1000 {
+        nonces.create_nonce(client_address());
+    }
+    assert_eq!(nonces.len(), 2);
+    assert!(!nonces.validate_nonce(&third, client_address()));
+
+    // Nonces expire
+    let fresh = nonces.create_nonce(client_address());
+    clock.advance(Duration::from_secs(10));
+    assert!(!nonces.validate_nonce(&fresh, client_address()));
+
+    // Expired nonces are purged when a new one is issued
+    nonces.create_nonce(client_address());
+    assert_eq!(nonces.len(), 1);
+}
+
+/// Tests the long-term credential handshake against the client
+#[test]
+fn long_term_authentication() {
+    let server: SocketAddr = "198.51.100.1:3478".parse().unwrap();
+    let now = Instant::now();
+    let mut authenticator = LongTermAuthenticator::new(credential_store(), "example.org");
+    let mut client = LongTermCredentialClient::new("user", "pass");
+
+    let request = StunMessage::create_request().add_fingerprint();
+    client.start_request(server, request, now).unwrap();
+
+    // The unauthenticated request is challenged
+    let first_attempt = match client.handle_timeout(now) {
+        Some(StunTransactionEvent::Transmit(payload)) => payload,
+        other => panic!("Unexpected event: {:?}", other),
+    };
+    let challenge = expect_rejected(
+        authenticator
+            .authenticate(&first_attempt, client_address())
+            .unwrap(),
+    );
+    check_sample_integrity(&challenge, None);
+
+    let decoded = StunMessageRef::decode(&challenge, None).unwrap();
+    assert_eq!(decoded.get_error_code(), Some((4, 1, "Unauthorized")));
+    assert_eq!(decoded.get_realm(), Some("example.org"));
+    assert!(decoded.get_nonce().is_some());
+    assert!(decoded.has_fingerprint());
+
+    // The retry is authenticated
+    let second_attempt = match client.handle_input(&challenge, now).unwrap() {
+        Some(StunTransactionEvent::Transmit(payload)) => payload,
+        other => panic!("Unexpected event: {:?}", other),
+    };
+    let (request, key) = match authenticator
+        .authenticate(&second_attempt, client_address())
+        .unwrap()
+    {
+        StunAuthentication::Authenticated { request, key } => (request, key),
+        other => panic!("Unexpected authentication outcome: {:?}", other),
+    };
+    assert_eq!(request.get_username(), Some("user"));
+
+    // The client accepts the response protected with the key of the user
+    let response =
+        StunMessage::create_response_to(request.get_header(), StunMessageClass::SuccessResponse)
+            .add_message_integrity()
+            .encode_with_key(Some(&key))
+            .unwrap();
+    assert!(matches!(
+        client.handle_input(&response, now).unwrap(),
+        Some(StunTransactionEvent::Response(_))
+    ));
+}
+
+/// Tests that indications and responses are never answered
+#[test]
+fn long_term_authentication_ignored_messages() {
+    let mut authenticator = LongTermAuthenticator::new(credential_store(), "example.org");
+
+    let messages = [
+        StunMessage::create_indication(),
+        StunMessage::create_success_response(),
+        StunMessage::create_error_response(),
+    ];
+
+    for message in messages.iter() {
+        let bytes = message.clone().encode(None).unwrap();
+
+        assert!(matches!(
+            authenticator
+                .authenticate(&bytes, client_address())
+                .unwrap(),
+            StunAuthentication::Ignored
+        ));
+    }
+}
+
+/// Tests rejecting bad credentials and stale nonces
+#[test]
+fn long_term_authentication_rejections() {
+    let clock = ManualClock(Rc::new(Cell::new(Instant::now())));
+    let nonces =
+        InMemoryNonceSource::with_clock(clock.clone()).set_lifetime(Duration::from_secs(60));
+    let mut authenticator =
+        LongTermAuthenticator::with_nonce_source(credential_store(), "example.org", nonces);
+    let nonce = authenticator.create_nonce(client_address());
+
+    let authenticated_request = |username: &str, nonce: &str, password: &str| {
+        StunMessage::create_request()
+            .add_long_term_credential_message_integrity(username, "example.org", nonce)
+            .unwrap()
+            .encode(Some(password))
+            .unwrap()
+    };
+
+    let error_code = |response: &[u8]| {
+        let response = StunMessageRef::decode(response, None).unwrap();
+        assert_eq!(
+            response.get_header().message_class,
+            StunMessageClass::ErrorResponse
+        );
+        response
+            .get_error_code()
+            .map(|(class, number, _)| (class, number))
+    };
+
+    // Wrong password
+    let request = authenticated_request("user", &nonce, "wrong");
+    let response = expect_rejected(
+        authenticator
+            .authenticate(&request, client_address())
+            .unwrap(),
+    );
+    assert_eq!(error_code(&response), Some((4, 1)));
+
+    // MESSAGE-INTEGRITY truncated to a single byte
+    let mut request = authenticated_request("user", &nonce, "pass");
+    let integrity_pos = request.len() - 24;
+    assert_eq!(
+        &request[integrity_pos..integrity_pos + 4],
+        &[0x00, 0x08, 0x00, 0x14]
+    );
+    request[integrity_pos + 3] = 0x01;
+    request.truncate(integrity_pos + 8);
+    let message_len = (request.len() - 20) as u16;
+    request[2..4].copy_from_slice(&message_len.to_be_bytes());
+    let response = expect_rejected(
+        authenticator
+            .authenticate(&request, client_address())
+            .unwrap(),
+    );
+    assert_eq!(error_code(&response), Some((4, 1)));
+
+    // Unknown user
+    let request = authenticated_request("other", &nonce, "pass");
+    let response = expect_rejected(
+        authenticator
+            .authenticate(&request, client_address())
+            .unwrap(),
+    );
+    assert_eq!(error_code(&response), Some((4, 1)));
+
+    // Valid credentials of another realm held by the store
+    authenticator
+        .get_store_mut()
+        .add_user("user", "other.org", "pass")
+        .unwrap();
+    let request = StunMessage::create_request()
+        .add_long_term_credential_message_integrity("user", "other.org", &nonce)
+        .unwrap()
+        .encode(Some("pass"))
+        .unwrap();
+    let response = expect_rejected(
+        authenticator
+            .authenticate(&request, client_address())
+            .unwrap(),
+    );
+    assert_eq!(error_code(&response), Some((4, 1)));
+    assert_eq!(
+        StunMessageRef::decode(&response, None).unwrap().get_realm(),
+        Some("example.org")
+    );
+
+    // Nonce not issued by the server
+    let request = authenticated_request("user", "forged", "pass");
+    let response = expect_rejected(
+        authenticator
+            .authenticate(&request, client_address())
+            .unwrap(),
+    );
+    assert_eq!(error_code(&response), Some((4, 38)));
+
+    // Missing NONCE
+    let request = StunMessage::create_request()
+        .add_attribute(StunAttribute::Username {
+            value: String::from("user"),
+        })
+        .add_message_integrity()
+        .encode(Some("pass"))
+        .unwrap();
+    let response = expect_rejected(
+        authenticator
+            .authenticate(&request, client_address())
+            .unwrap(),
+    );
+    assert_eq!(error_code(&response), Some((4, 0)));
+
+    // Valid credentials
+    let request = authenticated_request("user", &nonce, "pass");
+    assert!(matches!(
+        authenticator
+            .authenticate(&request, client_address())
+            .unwrap(),
+        StunAuthentication::Authenticated { .. }
+    ));
+
+    // Expired nonce. The response carries a fresh nonce.
+    clock.advance(Duration::from_secs(61));
+    let response = expect_rejected(
+        authenticator
+            .authenticate(&request, client_address())
+            .unwrap(),
+    );
+    assert_eq!(error_code(&response), Some((4, 38)));
+
+    let fresh_nonce = StunMessageRef::decode(&response, None)
+        .unwrap()
+        .get_nonce()
+        .map(String::from)
+        .unwrap();
+    assert_ne!(fresh_nonce, nonce);
+
+    let request = authenticated_request("user", &fresh_nonce, "pass");
+    assert!(matches!(
+        authenticator
+            .authenticate(&request, client_address())
+            .unwrap(),
+        StunAuthentication::Authenticated { .. }
+    ));
+}
+
+/// Tests authenticating requests protected by MESSAGE-INTEGRITY-SHA256 only
+#[test]
+fn long_term_authentication_sha256() {
+    let mut authenticator = LongTermAuthenticator::new(credential_store(), "example.org");
+    let nonce = authenticator.create_nonce(client_address());
+
+    let sha256_request = |password_algorithm: Option<StunPasswordAlgorithm>| {
+        let mut request = StunMessage::create_request()
+            .add_attribute(StunAttribute::Username {
+                value: String::from("user"),
+            })
+            .add_attribute(StunAttribute::Realm {
+                value: String::from("example.org"),
+            })
+            .add_attribute(StunAttribute::Nonce {
+                value: nonce.clone(),
+            });
+
+        if let Some(algorithm) = password_algorithm {
+            request = request.add_attribute(StunAttribute::PasswordAlgorithm {
+                algorithm,
+                parameters: Vec::new(),
+            });
+        }
+
+        request.add_message_integrity_sha256()
+    };
+
+    // The key is derived with MD5 when no PASSWORD-ALGORITHM attribute is present
+    for password_algorithm in [None, Some(StunPasswordAlgorithm::Sha256)].iter() {
+        let request = sha256_request(*password_algorithm)
+            .encode(Some("pass"))
+            .unwrap();
+        let key = match authenticator
+            .authenticate(&request, client_address())
+            .unwrap()
+        {
+            StunAuthentication::Authenticated { request, key } => {
+                assert!(request.get_message_integrity().is_none());
+                assert!(request.get_message_integrity_sha256().is_some());
+
+                key
+            }
+            other => panic!("Unexpected authentication outcome: {:?}", other),
+        };
+
+        let expected_key = StunMessage::calculate_long_term_key(
+            "user",
+            "example.org",
+            "pass",
+            password_algorithm.unwrap_or_default(),
+        )
+        .unwrap();
+        assert_eq!(key, expected_key);
+    }
+
+    // Unsupported password algorithms can't be used
+    let request = sha256_request(Some(StunPasswordAlgorithm::Other(3)))
+        .encode_with_key(Some(&[0xAB; 32]))
+        .unwrap();
+    let response = expect_rejected(
+        authenticator
+            .authenticate(&request, client_address())
+            .unwrap(),
+    );
+    assert_eq!(
+        StunMessageRef::decode(&response, None)
+            .unwrap()
+            .get_error_code(),
+        Some((4, 0, "Bad Request"))
+    );
+}
+
+/// Tests encoding the security feature set of the nonce cookie
+#[test]
+fn nonce_cookie_security_features() {
+    let features = [
+        (false, false, "obMatJos2AAAA"),
+        (true, false, "obMatJos2gAAA"),
+        (false, true, "obMatJos2QAAA"),
+        (true, true, "obMatJos2wAAA"),
+    ];
+
+    for (password_algorithms, username_anonymity, encoded) in features.iter() {
+        let features = StunSecurityFeatures {
+            password_algorithms: *password_algorithms,
+            username_anonymity: *username_anonymity,
+        };
+
+        assert_eq!(features.encode(), *encoded);
+        assert_eq!(StunSecurityFeatures::from_nonce(encoded), Some(features));
+    }
+
+    // Reserved bits are ignored
+    assert_eq!(
+        StunSecurityFeatures::from_nonce("obMatJos2////"),
+        Some(StunSecurityFeatures {
+            password_algorithms: true,
+            username_anonymity: true,
+        })
+    );
+
+    assert!(StunSecurityFeatures::from_nonce("obMatJos2gA").is_none());
+    assert!(StunSecurityFeatures::from_nonce("obMatJos2g!AA").is_none());
+}
+
+/// Tests validating stateless nonces
+#[test]
+fn stateless_nonce() {
+    let client: SocketAddr = "[2001:db8::1]:32853".parse().unwrap();
+    let now = UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+    let features = StunSecurityFeatures {
+        password_algorithms: true,
+        username_anonymity: false,
+    };
+    let nonces = StatelessNonce::new(b"secret").set_lifetime(Duration::from_secs(60));
+
+    let nonce = nonces.create_nonce(features, client, now);
+    assert!(nonce.starts_with("obMatJos2gAAA"));
+    assert_eq!(nonce.len(), 13 + 32);
+
+    // The nonce can be validated by another instance sharing the key
+    let other_instance = StatelessNonce::new(b"secret").set_lifetime(Duration::from_secs(60));
+    assert_eq!(
+        other_instance
+            .validate_nonce(&nonce, client, now + Duration::from_secs(60))
+            .unwrap(),
+        features
+    );
+
+    assert!(matches!(
+        nonces.validate_nonce(&nonce, client, now + Duration::from_secs(61)),
+        Err(NonceError::Expired())
+    ));
+    assert!(matches!(
+        StatelessNonce::new(b"other secret").validate_nonce(&nonce, client, now),
+        Err(NonceError::AuthenticationFailure())
+    ));
+    assert!(matches!(
+        nonces.validate_nonce(&nonce, "[2001:db8::1]:32854".parse().unwrap(), now),
+        Err(NonceError::AuthenticationFailure())
+    ));
+
+    // The security features are signed
+    let downgraded = nonce.replacen("obMatJos2gAAA", "obMatJos2AAAA", 1);
+    assert!(matches!(
+        nonces.validate_nonce(&downgraded, client, now),
+        Err(NonceError::AuthenticationFailure())
+    ));
+
+    assert!(matches!(
+        nonces.validate_nonce("0123456789abcdef", client, now),
+        Err(NonceError::MissingCookie())
+    ));
+    assert!(matches!(
+        nonces.validate_nonce(&nonce[..30], client, now),
+        Err(NonceError::InvalidEncoding())
+    ));
+    assert!(matches!(
+        nonces.validate_nonce(&nonce[..29], client, now),
+        Err(NonceError::InvalidEncoding())
+    ));
+
+    // Nonces issued at different times differ
+    assert_ne!(
+        nonce,
+        nonces.create_nonce(features, client, SystemTime::now())
+    );
+
+    // Lifetimes too long to be represented never expire
+    let everlasting = StatelessNonce::new(b"secret").set_lifetime(Duration::MAX);
+    assert_eq!(
+        everlasting
+            .validate_nonce(&nonce, client, now + Duration::from_secs(3600))
+            .unwrap(),
+        features
+    );
+}
+
+/// Tests authenticating requests across server instances sharing the stateless nonce key
+#[test]
+fn stateless_nonce_authentication() {
+    let client = client_address();
+    let mut front_end = LongTermAuthenticator::with_nonce_source(
+        credential_store(),
+        "example.org",
+        StatelessNonce::new(b"secret"),
+    );
+    let mut other_front_end = LongTermAuthenticator::with_nonce_source(
+        credential_store(),
+        "example.org",
+        StatelessNonce::new(b"secret"),
+    );
+
+    // The challenge is issued by one instance
+    let request = StunMessage::create_request().encode(None).unwrap();
+    let challenge = expect_rejected(front_end.authenticate(&request, client).unwrap());
+    let challenge = StunMessageRef::decode(&challenge, None).unwrap();
+    let nonce = challenge.get_nonce().unwrap();
+
+    // The features the authenticator doesn't implement aren't advertised
+    assert_eq!(
+        StunSecurityFeatures::from_nonce(nonce),
+        Some(StunSecurityFeatures::default())
+    );
+    assert!(!challenge.get_attributes().iter().any(|attr| matches!(
+        attr,
+        StunAttributeRef::Other(StunAttribute::PasswordAlgorithms { .. })
+    )));
+
+    // The retried request is authenticated by another one
+    let request = StunMessage::create_request()
+        .add_long_term_credential_message_integrity("user", "example.org", nonce)
+        .unwrap()
+        .encode(Some("pass"))
+        .unwrap();
+    assert!(matches!(
+        other_front_end.authenticate(&request, client).unwrap(),
+        StunAuthentication::Authenticated { .. }
+    ));
+
+    // The nonce is bound to the client transport address
+    let other_client: SocketAddr = "192.0.2.2:32853".parse().unwrap();
+    let response = expect_rejected(
+        other_front_end
+            .authenticate(&request, other_client)
+            .unwrap(),
+    );
+    assert_eq!(
+        StunMessageRef::decode(&response, None)
+            .unwrap()
+            .get_error_code(),
+        Some((4, 38, "Stale Nonce"))
+    );
+
+    // Nonces signed with another key are stale
+    let mut foreign_front_end = LongTermAuthenticator::with_nonce_source(
+        credential_store(),
+        "example.org",
+        StatelessNonce::new(b"other secret"),
+    );
+    let response = expect_rejected(foreign_front_end.authenticate(&request, client).unwrap());
+    assert_eq!(
+        StunMessageRef::decode(&response, None)
+            .unwrap()
+            .get_error_code(),
+        Some((4, 38, "Stale Nonce"))
+    );
+}
@@ -0,0 +1,712 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+use crate::{
+    StunAttribute, StunClientTransaction, StunCorrelatedEvent, StunMessage, StunMessageClass,
+    StunMessageMethod, StunRtoEstimator, StunTransactionCorrelator, StunTransactionEvent,
+    StunTransport,
+};
+
+/// Runs the transaction without ever responding and returns the transmission and timeout offsets in milliseconds
+fn run_without_response(mut transaction: StunClientTransaction, start: Instant) -> (Vec<u64>, u64) {
+    let mut transmissions = Vec::new();
+
+    loop {
+        let deadline = transaction.poll_timeout().unwrap();
+
+        // Nothing happens before the deadline
+        assert!(transaction
+            .handle_timeout(deadline - Duration::from_millis(1))
+            .is_none());
+
+        let offset = (deadline - start).as_millis() as u64;
+        match transaction.handle_timeout(deadline) {
+            Some(StunTransactionEvent::Transmit(_)) => transmissions.push(offset),
+            Some(StunTransactionEvent::Timeout) => {
+                assert!(transaction.is_finished());
+                assert!(transaction.poll_timeout().is_none());
+
+                return (transmissions, offset);
+            }
+            other => panic!("Unexpected event: {:?}", other),
+        }
+    }
+}
+
+/// Tests the RFC5389 retransmission schedule over UDP
+#[test]
+fn unreliable_retransmissions() {
+    let start = Instant::now();
+    let request = StunMessage::create_request();
+
+    let transaction =
+        StunClientTransaction::new(&request, None, StunTransport::Unreliable, start).unwrap();
+    assert_eq!(
+        run_without_response(transaction, start),
+        (vec![0, 500, 1500, 3500, 7500, 15500, 31500], 39500)
+    );
+
+    // Custom RTO, Rc and Rm
+    let transaction = StunClientTransaction::new(&request, None, StunTransport::Unreliable, start)
+        .unwrap()
+        .set_rto(Duration::from_millis(100))
+        .set_max_transmissions(3)
+        .set_last_timeout_multiplier(4);
+    assert_eq!(
+        run_without_response(transaction, start),
+        (vec![0, 100, 300], 700)
+    );
+}
+
+/// Tests that large timer settings don't overflow the deadlines
+#[test]
+fn large_timer_settings() {
+    let start = Instant::now();
+    let request = StunMessage::create_request();
+
+    // Exponential backoff with a huge RTO and Rc
+    let mut transaction =
+        StunClientTransaction::new(&request, None, StunTransport::Unreliable, start)
+            .unwrap()
+            .set_rto(Duration::from_secs(3600))
+            .set_max_transmissions(u32::MAX);
+    let mut now = start;
+    for transmissions in 1..=40 {
+        assert!(matches!(
+            transaction.handle_timeout(now),
+            Some(StunTransactionEvent::Transmit(_))
+        ));
+        assert_eq!(transaction.get_transmissions(), transmissions);
+
+        let deadline = transaction.poll_timeout().unwrap();
+        assert!(deadline > now);
+        now = deadline;
+    }
+
+    // Huge Rm after the last transmission
+    let mut transaction =
+        StunClientTransaction::new(&request, None, StunTransport::Unreliable, start)
+            .unwrap()
+            .set_rto(Duration::MAX)
+            .set_max_transmissions(1)
+            .set_last_timeout_multiplier(u32::MAX);
+    assert!(transaction.handle_timeout(start).is_some());
+    assert!(transaction.poll_timeout().unwrap() > start);
+
+    // Huge Ti
+    let mut transaction =
+        StunClientTransaction::new(&request, None, StunTransport::Reliable, start)
+            .unwrap()
+            .set_reliable_timeout(Duration::MAX);
+    assert!(transaction.handle_timeout(start).is_some());
+    assert!(transaction.poll_timeout().unwrap() > start);
+}
+
+/// Tests the Ti timeout over reliable transports
+#[test]
+fn reliable_timeout() {
+    let start = Instant::now();
+    let request = StunMessage::create_request();
+
+    let transaction =
+        StunClientTransaction::new(&request, None, StunTransport::Reliable, start).unwrap();
+    assert_eq!(run_without_response(transaction, start), (vec![0], 39500));
+
+    let transaction = StunClientTransaction::new(&request, None, StunTransport::Reliable, start)
+        .unwrap()
+        .set_reliable_timeout(Duration::from_secs(5));
+    assert_eq!(run_without_response(transaction, start), (vec![0], 5000));
+}
+
+/// Tests matching the response to the outstanding request
+#[test]
+fn response_matching() {
+    let start = Instant::now();
+    let request = StunMessage::create_request().add_attribute(StunAttribute::Software {
+        description: String::from("rust-stun-coder"),
+    });
+    let transaction_id = request.get_header().transaction_id;
+
+    let mut transaction =
+        StunClientTransaction::new(&request, None, StunTransport::Unreliable, start).unwrap();
+    assert_eq!(transaction.get_transaction_id(), transaction_id);
+
+    let payload = match transaction.handle_timeout(start) {
+        Some(StunTransactionEvent::Transmit(payload)) => payload,
+        other => panic!("Unexpected event: {:?}", other),
+    };
+    assert_eq!(payload, request.encode(None).unwrap());
+
+    // Response to another transaction
+    let other_response = StunMessage::create_success_response().encode(None).unwrap();
+    assert!(transaction.handle_input(&other_response, start).is_none());
+
+    // Response of another method
+    let wrong_method = StunMessage::new(
+        StunMessageMethod::Allocate,
+        StunMessageClass::SuccessResponse,
+    )
+    .set_transaction_id(transaction_id)
+    .encode(None)
+    .unwrap();
+    assert!(transaction.handle_input(&wrong_method, start).is_none());
+
+    // Not a response
+    let indication = StunMessage::create_indication()
+        .set_transaction_id(transaction_id)
+        .encode(None)
+        .unwrap();
+    assert!(transaction.handle_input(&indication, start).is_none());
+
+    // Not a STUN message
+    assert!(transaction.handle_input(&[0xFF; 32], start).is_none());
+    assert!(!transaction.is_finished());
+
+    let mapped_address = "192.0.2.1:32853".parse().unwrap();
+    let response = StunMessage::create_success_response()
+        .set_transaction_id(transaction_id)
+        .add_attribute(StunAttribute::XorMappedAddress {
+            socket_addr: mapped_address,
+        })
+        .encode(None)
+        .unwrap();
+
+    match transaction.handle_input(&response, start) {
+        Some(StunTransactionEvent::Response(response)) => {
+            assert_eq!(
+                response.get_header().message_class,
+                StunMessageClass::SuccessResponse
+            );
+            assert_eq!(response.get_attributes().len(), 1);
+        }
+        other => panic!("Unexpected event: {:?}", other),
+    }
+
+    // The transaction is finished
+    assert!(transaction.is_finished());
+    assert!(transaction.poll_timeout().is_none());
+    assert!(transaction.handle_input(&response, start).is_none());
+    assert!(transaction
+        .handle_timeout(start + Duration::from_secs(60))
+        .is_none());
+}
+
+/// Tests that error responses finish the transaction and responses failing the integrity check are ignored
+#[test]
+fn response_integrity() {
+    let start = Instant::now();
+    let key = b"TEST_PASS";
+    let request = StunMessage::create_request()
+        .add_attribute(StunAttribute::Username {
+            value: String::from("user"),
+        })
+        .add_message_integrity();
+    let transaction_id = request.get_header().transaction_id;
+
+    let mut transaction =
+        StunClientTransaction::new(&request, Some(key), StunTransport::Unreliable, start).unwrap();
+    assert!(transaction.handle_timeout(start).is_some());
+    assert!(transaction
+        .handle_timeout(start + Duration::from_millis(500))
+        .is_some());
+    assert_eq!(transaction.get_transmissions(), 2);
+
+    let response = StunMessage::create_error_response()
+        .set_transaction_id(transaction_id)
+        .add_attribute(StunAttribute::ErrorCode {
+            class: 4,
+            number: 0,
+            reason: String::from("Bad Request"),
+        })
+        .add_message_integrity();
+
+    let forged = response.encode_with_key(Some(b"OTHER_PASS")).unwrap();
+    assert!(transaction.handle_input(&forged, start).is_none());
+
+    let authentic = response.encode_with_key(Some(key)).unwrap();
+    match transaction.handle_input(&authentic, start) {
+        Some(StunTransactionEvent::Response(response)) => {
+            assert_eq!(
+                response.get_header().message_class,
+                StunMessageClass::ErrorResponse
+            );
+        }
+        other => panic!("Unexpected event: {:?}", other),
+    }
+    assert!(transaction.is_finished());
+}
+
+/// Tests that responses to authenticated requests are ignored without an integrity attribute, except for challenges
+#[test]
+fn missing_response_integrity() {
+    let start = Instant::now();
+    let key = b"TEST_PASS";
+    let request = StunMessage::create_request()
+        .add_attribute(StunAttribute::Username {
+            value: String::from("user"),
+        })
+        .add_message_integrity();
+    let transaction_id = request.get_header().transaction_id;
+
+    let mut transaction =
+        StunClientTransaction::new(&request, Some(key), StunTransport::Unreliable, start).unwrap();
+    assert!(transaction.handle_timeout(start).is_some());
+
+    // Forged success response carrying a spoofed mapped address
+    let spoofed = StunMessage::create_success_response()
+        .set_transaction_id(transaction_id)
+        .add_attribute(StunAttribute::XorMappedAddress {
+            socket_addr: "203.0.113.66:6666".parse().unwrap(),
+        })
+        .encode(None)
+        .unwrap();
+    assert!(transaction.handle_input(&spoofed, start).is_none());
+
+    // Forged success response carrying a single byte MESSAGE-INTEGRITY value
+    let mut spoofed = StunMessage::create_success_response()
+        .set_transaction_id(transaction_id)
+        .add_attribute(StunAttribute::XorMappedAddress {
+            socket_addr: "203.0.113.66:6666".parse().unwrap(),
+        })
+        .add_message_integrity()
+        .encode_with_key(Some(b"OTHER_PASS"))
+        .unwrap();
+    let integrity_pos = spoofed.len() - 24;
+    assert_eq!(
+        &spoofed[integrity_pos..integrity_pos + 4],
+        &[0x00, 0x08, 0x00, 0x14]
+    );
+    spoofed[integrity_pos + 3] = 0x01;
+    spoofed.truncate(integrity_pos + 8);
+    let message_len = (spoofed.len() - 20) as u16;
+    spoofed[2..4].copy_from_slice(&message_len.to_be_bytes());
+    for value in 0..=u8::MAX {
+        spoofed[integrity_pos + 4] = value;
+        assert!(transaction.handle_input(&spoofed, start).is_none());
+    }
+    assert!(!transaction.is_finished());
+
+    // Error responses other than the challenges need an integrity attribute too
+    let error_response = |number: u8, reason: &str| {
+        StunMessage::create_error_response()
+            .set_transaction_id(transaction_id)
+            .add_attribute(StunAttribute::ErrorCode {
+                class: 4,
+                number,
+                reason: String::from(reason),
+            })
+            .encode(None)
+            .unwrap()
+    };
+    assert!(transaction
+        .handle_input(&error_response(20, "Unknown Attribute"), start)
+        .is_none());
+    assert!(!transaction.is_finished());
+
+    assert!(matches!(
+        transaction.handle_input(&error_response(1, "Unauthorized"), start),
+        Some(StunTransactionEvent::Response(_))
+    ));
+    assert!(transaction.is_finished());
+
+    // Stale nonce challenges are accepted as well
+    let mut transaction =
+        StunClientTransaction::new(&request, Some(key), StunTransport::Unreliable, start).unwrap();
+    assert!(transaction.handle_timeout(start).is_some());
+    assert!(matches!(
+        transaction.handle_input(&error_response(38, "Stale Nonce"), start),
+        Some(StunTransactionEvent::Response(_))
+    ));
+}
+
+/// Tests the timers of multiple transactions sharing a correlator
+#[test]
+fn correlator_timers() {
+    let start = Instant::now();
+    let mut correlator = StunTransactionCorrelator::new();
+    assert!(correlator.poll_timeout().is_none());
+
+    let udp_request = StunMessage::create_request();
+    let tcp_request = StunMessage::create_request();
+    let late_request = StunMessage::create_request();
+
+    correlator.insert(
+        StunClientTransaction::new(&udp_request, None, StunTransport::Unreliable, start)
+            .unwrap()
+            .set_max_transmissions(2),
+    );
+    correlator.insert(
+        StunClientTransaction::new(&tcp_request, None, StunTransport::Reliable, start).unwrap(),
+    );
+    correlator.insert(
+        StunClientTransaction::new(
+            &late_request,
+            None,
+            StunTransport::Unreliable,
+            start + Duration::from_millis(200),
+        )
+        .unwrap(),
+    );
+    assert_eq!(correlator.len(), 3);
+
+    // The first two requests are sent right away
+    assert_eq!(correlator.poll_timeout(), Some(start));
+    let events = correlator.handle_timeout(start);
+    assert_eq!(events.len(), 2);
+    assert!(events.iter().all(
+        |event| matches!(event.event, StunTransactionEvent::Transmit(_))
+            && event.finished.is_none()
+    ));
+
+    // The third request is sent later
+    let next = start + Duration::from_millis(200);
+    assert_eq!(correlator.poll_timeout(), Some(next));
+    let events = correlator.handle_timeout(next);
+    assert_eq!(events.len(), 1);
+    assert_eq!(
+        events[0].transaction_id,
+        late_request.get_header().transaction_id
+    );
+
+    // Retransmission of the first request
+    let next = start + Duration::from_millis(500);
+    assert_eq!(correlator.poll_timeout(), Some(next));
+    let events = correlator.handle_timeout(next);
+    assert_eq!(events.len(), 1);
+    assert_eq!(
+        events[0].transaction_id,
+        udp_request.get_header().transaction_id
+    );
+
+    // After Rm * RTO the first transaction times out and gets removed
+    let next = start + Duration::from_millis(8500);
+    let events = correlator.handle_timeout(next);
+    assert!(events.iter().any(|event| {
+        event.transaction_id == udp_request.get_header().transaction_id
+            && matches!(event.event, StunTransactionEvent::Timeout)
+            && matches!(&event.finished, Some(transaction) if !transaction.has_response())
+    }));
+    assert!(correlator
+        .get(&udp_request.get_header().transaction_id)
+        .is_none());
+
+    // Cancelling a transaction
+    assert!(correlator
+        .remove(&late_request.get_header().transaction_id)
+        .is_some());
+    assert_eq!(correlator.len(), 1);
+    assert_eq!(
+        correlator.poll_timeout(),
+        Some(start + Duration::from_millis(39500))
+    );
+}
+
+/// Tests routing responses to the outstanding transactions
+#[test]
+fn correlator_routing() {
+    let start = Instant::now();
+    let mut correlator = StunTransactionCorrelator::new();
+
+    let binding_request = StunMessage::create_request();
+    let allocate_request = StunMessage::new(StunMessageMethod::Allocate, StunMessageClass::Request);
+    let binding_id = binding_request.get_header().transaction_id;
+    let allocate_id = allocate_request.get_header().transaction_id;
+
+    for request in [&binding_request, &allocate_request].iter() {
+        correlator.insert(
+            StunClientTransaction::new(request, None, StunTransport::Unreliable, start).unwrap(),
+        );
+    }
+    correlator.handle_timeout(start);
+
+    // Unsolicited response
+    let unsolicited = StunMessage::create_success_response().encode(None).unwrap();
+    assert!(correlator.handle_input(&unsolicited, start).is_none());
+
+    // Method mismatch
+    let wrong_method = StunMessage::create_success_response()
+        .set_transaction_id(allocate_id)
+        .encode(None)
+        .unwrap();
+    assert!(correlator.handle_input(&wrong_method, start).is_none());
+
+    // Class mismatch
+    let request_echo = binding_request.encode(None).unwrap();
+    assert!(correlator.handle_input(&request_echo, start).is_none());
+    assert_eq!(correlator.len(), 2);
+
+    let allocate_response =
+        StunMessage::new(StunMessageMethod::Allocate, StunMessageClass::ErrorResponse)
+            .set_transaction_id(allocate_id)
+            .encode(None)
+            .unwrap();
+    match correlator.handle_input(&allocate_response, start) {
+        Some(StunCorrelatedEvent {
+            transaction_id,
+            event: StunTransactionEvent::Response(response),
+            finished: Some(transaction),
+        }) => {
+            assert_eq!(transaction_id, allocate_id);
+            assert_eq!(transaction.get_transaction_id(), allocate_id);
+            assert_eq!(
+                response.get_header().message_method,
+                StunMessageMethod::Allocate
+            );
+        }
+        Some(other) => panic!("Unexpected event: {:?}", other.event),
+        None => panic!("The response hasn't been routed"),
+    }
+
+    // Duplicate responses are dropped
+    assert!(correlator.handle_input(&allocate_response, start).is_none());
+
+    let binding_response = StunMessage::create_success_response()
+        .set_transaction_id(binding_id)
+        .encode(None)
+        .unwrap();
+    assert_eq!(
+        correlator
+            .handle_input(&binding_response, start)
+            .map(|event| event.transaction_id),
+        Some(binding_id)
+    );
+
+    assert!(correlator.is_empty());
+    assert!(correlator.poll_timeout().is_none());
+}
+
+/// Tests the RFC6298 RTO computation and the per-server cache
+#[test]
+fn rto_estimation() {
+    let start = Instant::now();
+    let server = "192.0.2.1:3478".parse().unwrap();
+    let other_server = "192.0.2.2:3478".parse().unwrap();
+    let mut estimator = StunRtoEstimator::new();
+
+    assert_eq!(estimator.get_rto(server, start), Duration::from_millis(500));
+    assert!(estimator.get_srtt(server, start).is_none());
+
+    // First sample: SRTT = 600, RTTVAR = 300, RTO = 600 + 4 * 300
+    estimator.add_rtt_sample(server, Duration::from_millis(600), start);
+    assert_eq!(
+        estimator.get_rto(server, start),
+        Duration::from_millis(1800)
+    );
+
+    // Second sample: RTTVAR = 3/4 * 300 + 1/4 * 200 = 275, SRTT = 7/8 * 600 + 1/8 * 400 = 575
+    estimator.add_rtt_sample(server, Duration::from_millis(400), start);
+    assert_eq!(
+        estimator.get_srtt(server, start),
+        Some(Duration::from_millis(575))
+    );
+    assert_eq!(
+        estimator.get_rto(server, start),
+        Duration::from_millis(1675)
+    );
+
+    // Other servers are not affected
+    assert_eq!(
+        estimator.get_rto(other_server, start),
+        Duration::from_millis(500)
+    );
+
+    // Timeouts double the RTO up to the upper bound
+    estimator.add_timeout(server, start);
+    assert_eq!(
+        estimator.get_rto(server, start),
+        Duration::from_millis(3350)
+    );
+    estimator.add_timeout(other_server, start);
+    assert_eq!(
+        estimator.get_rto(other_server, start),
+        Duration::from_secs(1)
+    );
+
+    // Small RTTs are bounded by the minimum RTO
+    estimator.add_rtt_sample(other_server, Duration::from_millis(10), start);
+    assert_eq!(
+        estimator.get_rto(other_server, start),
+        Duration::from_millis(500)
+    );
+
+    // Estimates are discarded after 10 minutes
+    let later = start + Duration::from_secs(600);
+    assert_eq!(
+        estimator.get_rto(server, later),
+        Duration::from_millis(3350)
+    );
+    let stale = start + Duration::from_secs(601);
+    assert_eq!(estimator.get_rto(server, stale), Duration::from_millis(500));
+
+    // A stale estimate is restarted by the next sample
+    estimator.add_rtt_sample(server, Duration::from_millis(100), stale);
+    assert_eq!(
+        estimator.get_srtt(server, stale),
+        Some(Duration::from_millis(100))
+    );
+
+    // Custom bounds
+    let mut estimator = StunRtoEstimator::new()
+        .set_initial_rto(Duration::from_secs(1))
+        .set_min_rto(Duration::from_millis(50))
+        .set_max_rto(Duration::from_secs(2))
+        .set_cache_lifetime(Duration::from_secs(30));
+    assert_eq!(estimator.get_rto(server, start), Duration::from_secs(1));
+    estimator.add_rtt_sample(server, Duration::from_millis(20), start);
+    assert_eq!(estimator.get_rto(server, start), Duration::from_millis(60));
+    estimator.add_rtt_sample(server, Duration::from_secs(3), start);
+    assert_eq!(estimator.get_rto(server, start), Duration::from_secs(2));
+
+    estimator.purge(start + Duration::from_secs(31));
+    assert_eq!(
+        estimator.get_rto(server, start + Duration::from_secs(31)),
+        Duration::from_secs(1)
+    );
+}
+
+/// Tests feeding the RTO estimator from finished transactions
+#[test]
+fn rto_from_transactions() {
+    let start = Instant::now();
+    let server = "192.0.2.1:3478".parse().unwrap();
+    let mut estimator = StunRtoEstimator::new();
+
+    let respond = |transaction: &mut StunClientTransaction, now: Instant| {
+        let response = StunMessage::create_success_response()
+            .set_transaction_id(transaction.get_transaction_id())
+            .encode(None)
+            .unwrap();
+        assert!(transaction.handle_input(&response, now).is_some());
+    };
+
+    // Response to the first transmission
+    let request = StunMessage::create_request();
+    let mut transaction =
+        StunClientTransaction::new(&request, None, StunTransport::Unreliable, start)
+            .unwrap()
+            .set_rto(estimator.get_rto(server, start));
+    transaction.handle_timeout(start);
+
+    // Unfinished transactions are ignored
+    estimator.update(server, &transaction, start);
+    assert!(estimator.get_srtt(server, start).is_none());
+
+    let now = start + Duration::from_millis(300);
+    respond(&mut transaction, now);
+    assert_eq!(
+        transaction.get_rtt_sample(),
+        Some(Duration::from_millis(300))
+    );
+    // The sample is measured up to the arrival of the response, not up to the update
+    estimator.update(server, &transaction, now + Duration::from_millis(50));
+    assert_eq!(
+        estimator.get_srtt(server, now),
+        Some(Duration::from_millis(300))
+    );
+    assert_eq!(estimator.get_rto(server, now), Duration::from_millis(900));
+
+    // Responses to retransmitted requests don't provide a sample
+    let mut transaction =
+        StunClientTransaction::new(&request, None, StunTransport::Unreliable, now)
+            .unwrap()
+            .set_rto(estimator.get_rto(server, now));
+    transaction.handle_timeout(now);
+    transaction.handle_timeout(now + Duration::from_millis(900));
+    let later = now + Duration::from_millis(1000);
+    respond(&mut transaction, later);
+
+    assert!(transaction.get_rtt_sample().is_none());
+    estimator.update(server, &transaction, later);
+    assert_eq!(estimator.get_rto(server, later), Duration::from_millis(900));
+
+    // Timed out transactions back off the RTO
+    let mut transaction =
+        StunClientTransaction::new(&request, None, StunTransport::Reliable, later).unwrap();
+    transaction.handle_timeout(later);
+    let timeout = later + StunClientTransaction::DEFAULT_RELIABLE_TIMEOUT;
+    assert!(matches!(
+        transaction.handle_timeout(timeout),
+        Some(StunTransactionEvent::Timeout)
+    ));
+    assert!(!transaction.has_response());
+
+    estimator.update(server, &transaction, timeout);
+    assert_eq!(
+        estimator.get_rto(server, timeout),
+        Duration::from_millis(1800)
+    );
+}
+
+/// Tests feeding the RTO estimator from the transactions finished by a correlator
+#[test]
+fn rto_from_correlator() {
+    let start = Instant::now();
+    let mut estimator = StunRtoEstimator::new();
+    let mut correlator = StunTransactionCorrelator::new();
+    let mut servers = HashMap::new();
+
+    let answering_server: SocketAddr = "192.0.2.1:3478".parse().unwrap();
+    let silent_server: SocketAddr = "192.0.2.2:3478".parse().unwrap();
+
+    for server in [answering_server, silent_server].iter() {
+        let request = StunMessage::create_request();
+        let transaction =
+            StunClientTransaction::new(&request, None, StunTransport::Unreliable, start)
+                .unwrap()
+                .set_rto(estimator.get_rto(*server, start))
+                .set_max_transmissions(1);
+
+        servers.insert(transaction.get_transaction_id(), *server);
+        correlator.insert(transaction);
+    }
+
+    let events = correlator.handle_timeout(start);
+    assert_eq!(events.len(), 2);
+
+    // The response of the answering server provides an RTT sample
+    let transaction_id = events
+        .iter()
+        .map(|event| event.transaction_id)
+        .find(|transaction_id| servers[transaction_id] == answering_server)
+        .unwrap();
+    let response = StunMessage::create_success_response()
+        .set_transaction_id(transaction_id)
+        .encode(None)
+        .unwrap();
+
+    let now = start + Duration::from_millis(200);
+    let event = correlator.handle_input(&response, now).unwrap();
+    let transaction = event.finished.unwrap();
+    estimator.update(servers[&event.transaction_id], &transaction, now);
+
+    assert_eq!(
+        estimator.get_srtt(answering_server, now),
+        Some(Duration::from_millis(200))
+    );
+    assert_eq!(
+        estimator.get_rto(answering_server, now),
+        Duration::from_millis(600)
+    );
+
+    // The timeout of the request to the silent server backs off its RTO
+    let deadline = correlator.poll_timeout().unwrap();
+    let events = correlator.handle_timeout(deadline);
+    assert_eq!(events.len(), 1);
+
+    for event in events {
+        assert!(matches!(event.event, StunTransactionEvent::Timeout));
+        estimator.update(
+            servers[&event.transaction_id],
+            &event.finished.unwrap(),
+            deadline,
+        );
+    }
+
+    assert!(correlator.is_empty());
+    assert!(estimator.get_srtt(silent_server, deadline).is_none());
+    assert_eq!(
+        estimator.get_rto(silent_server, deadline),
+        StunRtoEstimator::DEFAULT_INITIAL_RTO * 2
+    );
+}
@@ -0,0 +1,326 @@
+use std::net::SocketAddr;
+
+use super::check_sample_integrity;
+use crate::{
+    StunAddressFamily, StunAttribute, StunMessage, StunMessageClass, StunMessageMethod,
+    StunMessageRef,
+};
+
+/// Tests an Allocate request with all the TURN request attributes decoding/recoding
+#[test]
+fn allocate_request() {
+    let transaction_id = [
+        0xb7, 0xe7, 0xa7, 0x01, 0xbc, 0x34, 0xd6, 0x86, 0xfa, 0x87, 0xdf, 0xae,
+    ];
+
+    let message = StunMessage::new(StunMessageMethod::Allocate, StunMessageClass::Request)
+        .set_transaction_id(transaction_id)
+        .add_attribute(StunAttribute::RequestedTransport { protocol: 17 })
+        .add_attribute(StunAttribute::Lifetime { seconds: 600 })
+        .add_attribute(StunAttribute::EvenPort { reserve_next: true })
+        .add_attribute(StunAttribute::DontFragment)
+        .add_long_term_credential_message_integrity("user", "realm", "nonce")
+        .unwrap()
+        .add_fingerprint();
+
+    let encoded_message = message.encode(Some("pass")).unwrap();
+
+    // Prerecorded binary representation of the attributes preceding USERNAME
+    let encoded_attrs = vec![
+        0x00, 0x19, 0x00, 0x04, 0x11, 0x00, 0x00, 0x00, 0x00, 0x0D, 0x00, 0x04, 0x00, 0x00, 0x02,
+        0x58, 0x00, 0x18, 0x00, 0x01, 0x80, 0x00, 0x00, 0x00, 0x00, 0x1A, 0x00, 0x00,
+    ];
+
+    assert_eq!(encoded_message[0..2], [0x00, 0x03]);
+    assert_eq!(encoded_message[20..48], encoded_attrs[..]);
+
+    check_sample_integrity(&encoded_message, Some("pass"));
+}
+
+/// Tests an Allocate success response decoding/recoding
+#[test]
+fn allocate_response() {
+    let relayed_addr: SocketAddr = "192.0.2.1:32853".parse().unwrap();
+    let mapped_addr: SocketAddr = "[2001:db8:1234:5678:11:2233:4455:6677]:32853"
+        .parse()
+        .unwrap();
+
+    let message = StunMessage::new(
+        StunMessageMethod::Allocate,
+        StunMessageClass::SuccessResponse,
+    )
+    .add_attribute(StunAttribute::XorRelayedAddress {
+        socket_addr: relayed_addr,
+    })
+    .add_attribute(StunAttribute::XorMappedAddress {
+        socket_addr: mapped_addr,
+    })
+    .add_attribute(StunAttribute::Lifetime { seconds: 1200 })
+    .add_attribute(StunAttribute::ReservationToken {
+        token: 0x0102_0304_0506_0708,
+    })
+    .add_message_integrity()
+    .add_fingerprint();
+
+    let encoded_message = message.encode(Some("pass")).unwrap();
+
+    // XOR-RELAYED-ADDRESS is encoded the same way as the XOR-MAPPED-ADDRESS in RFC5769 samples
+    assert_eq!(
+        encoded_message[20..32],
+        [0x00, 0x16, 0x00, 0x08, 0x00, 0x01, 0xa1, 0x47, 0xe1, 0x12, 0xa6, 0x43]
+    );
+
+    check_sample_integrity(&encoded_message, Some("pass"));
+
+    let decoded_msg = StunMessage::decode(&encoded_message, Some("pass")).unwrap();
+    let attrs = decoded_msg.get_attributes();
+
+    assert!(
+        matches!(attrs[0], StunAttribute::XorRelayedAddress { socket_addr } if socket_addr == relayed_addr)
+    );
+    assert!(
+        matches!(attrs[1], StunAttribute::XorMappedAddress { socket_addr } if socket_addr == mapped_addr)
+    );
+    assert!(matches!(
+        attrs[3],
+        StunAttribute::ReservationToken {
+            token: 0x0102_0304_0506_0708
+        }
+    ));
+}
+
+/// Tests a ChannelBind request decoding/recoding
+#[test]
+fn channel_bind_request() {
+    let peer_addr: SocketAddr = "203.0.113.5:49152".parse().unwrap();
+
+    let message = StunMessage::new(StunMessageMethod::ChannelBind, StunMessageClass::Request)
+        .add_attribute(StunAttribute::ChannelNumber { number: 0x4001 })
+        .add_attribute(StunAttribute::XorPeerAddress {
+            socket_addr: peer_addr,
+        })
+        .add_message_integrity();
+
+    let encoded_message = message.encode(Some("pass")).unwrap();
+
+    assert_eq!(encoded_message[0..2], [0x00, 0x09]);
+    assert_eq!(
+        encoded_message[20..28],
+        [0x00, 0x0C, 0x00, 0x04, 0x40, 0x01, 0x00, 0x00]
+    );
+
+    check_sample_integrity(&encoded_message, Some("pass"));
+}
+
+/// Tests Send and Data indications carrying application data decoding/recoding
+#[test]
+fn send_and_data_indications() {
+    let peer_addr: SocketAddr = "[2001:db8::1]:3478".parse().unwrap();
+
+    for method in [StunMessageMethod::Send, StunMessageMethod::Data].iter() {
+        let message = StunMessage::new(*method, StunMessageClass::Indication)
+            .add_attribute(StunAttribute::XorPeerAddress {
+                socket_addr: peer_addr,
+            })
+            .add_attribute(StunAttribute::Data {
+                value: vec![0xde, 0xad, 0xbe, 0xef, 0x01],
+            })
+            .add_fingerprint();
+
+        let encoded_message = message.encode(None).unwrap();
+
+        check_sample_integrity(&encoded_message, None);
+
+        let decoded_msg = StunMessage::decode(&encoded_message, None).unwrap();
+
+        assert_eq!(decoded_msg.get_header().message_method, *method);
+        assert!(matches!(
+            &decoded_msg.get_attributes()[1],
+            StunAttribute::Data { value } if value == &[0xde, 0xad, 0xbe, 0xef, 0x01]
+        ));
+    }
+}
+
+/// Tests the TURN-TCP Connect transaction decoding/recoding
+#[test]
+fn connect_transaction() {
+    let peer_addr: SocketAddr = "203.0.113.5:443".parse().unwrap();
+
+    let request = StunMessage::new(StunMessageMethod::Connect, StunMessageClass::Request)
+        .add_attribute(StunAttribute::XorPeerAddress {
+            socket_addr: peer_addr,
+        })
+        .add_message_integrity();
+
+    let encoded_request = request.encode(Some("pass")).unwrap();
+
+    assert_eq!(encoded_request[0..2], [0x00, 0x0A]);
+    check_sample_integrity(&encoded_request, Some("pass"));
+
+    let response = StunMessage::new(
+        StunMessageMethod::Connect,
+        StunMessageClass::SuccessResponse,
+    )
+    .add_attribute(StunAttribute::ConnectionId { value: 0x1234_5678 })
+    .add_message_integrity();
+
+    let encoded_response = response.encode(Some("pass")).unwrap();
+
+    assert_eq!(encoded_response[0..2], [0x01, 0x0A]);
+    assert_eq!(
+        encoded_response[20..28],
+        [0x00, 0x2A, 0x00, 0x04, 0x12, 0x34, 0x56, 0x78]
+    );
+    check_sample_integrity(&encoded_response, Some("pass"));
+
+    let decoded = StunMessageRef::decode(&encoded_response, Some("pass")).unwrap();
+    assert_eq!(
+        decoded.get_header().message_method,
+        StunMessageMethod::Connect
+    );
+    assert_eq!(decoded.get_connection_id(), Some(0x1234_5678));
+}
+
+/// Tests the TURN-TCP ConnectionBind request and ConnectionAttempt indication decoding/recoding
+#[test]
+fn connection_bind_and_attempt() {
+    let peer_addr: SocketAddr = "[2001:db8::5]:8080".parse().unwrap();
+
+    let attempt = StunMessage::new(
+        StunMessageMethod::ConnectionAttempt,
+        StunMessageClass::Indication,
+    )
+    .add_attribute(StunAttribute::ConnectionId { value: 42 })
+    .add_attribute(StunAttribute::XorPeerAddress {
+        socket_addr: peer_addr,
+    });
+
+    let encoded_attempt = attempt.encode(None).unwrap();
+
+    assert_eq!(encoded_attempt[0..2], [0x00, 0x1C]);
+    check_sample_integrity(&encoded_attempt, None);
+
+    let decoded_attempt = StunMessageRef::decode(&encoded_attempt, None).unwrap();
+    let connection_id = decoded_attempt.get_connection_id().unwrap();
+
+    let bind = StunMessage::new(StunMessageMethod::ConnectionBind, StunMessageClass::Request)
+        .add_attribute(StunAttribute::ConnectionId {
+            value: connection_id,
+        })
+        .add_long_term_credential_message_integrity("user", "realm", "nonce")
+        .unwrap()
+        .add_fingerprint();
+
+    let encoded_bind = bind.encode(Some("pass")).unwrap();
+
+    assert_eq!(encoded_bind[0..2], [0x00, 0x0B]);
+    assert_eq!(
+        encoded_bind[20..28],
+        [0x00, 0x2A, 0x00, 0x04, 0x00, 0x00, 0x00, 0x2A]
+    );
+    check_sample_integrity(&encoded_bind, Some("pass"));
+}
+
+/// Tests a dual-stack Allocate request decoding/recoding
+#[test]
+fn dual_stack_allocate_request() {
+    let message = StunMessage::new(StunMessageMethod::Allocate, StunMessageClass::Request)
+        .add_attribute(StunAttribute::RequestedTransport { protocol: 17 })
+        .add_attribute(StunAttribute::RequestedAddressFamily {
+            family: StunAddressFamily::Ipv4,
+        })
+        .add_attribute(StunAttribute::AdditionalAddressFamily {
+            family: StunAddressFamily::Ipv6,
+        })
+        .add_message_integrity();
+
+    let encoded_message = message.encode(Some("pass")).unwrap();
+
+    assert_eq!(
+        encoded_message[28..44],
+        [
+            0x00, 0x17, 0x00, 0x04, 0x01, 0x00, 0x00, 0x00, // REQUESTED-ADDRESS-FAMILY
+            0x80, 0x00, 0x00, 0x04, 0x02, 0x00, 0x00, 0x00, // ADDITIONAL-ADDRESS-FAMILY
+        ]
+    );
+
+    check_sample_integrity(&encoded_message, Some("pass"));
+
+    let decoded = StunMessageRef::decode(&encoded_message, Some("pass")).unwrap();
+    assert_eq!(
+        decoded.get_requested_address_family(),
+        Some(StunAddressFamily::Ipv4)
+    );
+    assert_eq!(
+        decoded.get_additional_address_family(),
+        Some(StunAddressFamily::Ipv6)
+    );
+}
+
+/// Tests dual-stack Allocate success responses decoding/recoding
+#[test]
+fn dual_stack_allocate_response() {
+    let relayed_v4: SocketAddr = "192.0.2.1:49152".parse().unwrap();
+    let relayed_v6: SocketAddr = "[2001:db8::1]:49153".parse().unwrap();
+
+    // Both address families have been allocated
+    let message = StunMessage::new(
+        StunMessageMethod::Allocate,
+        StunMessageClass::SuccessResponse,
+    )
+    .add_attribute(StunAttribute::XorRelayedAddress {
+        socket_addr: relayed_v4,
+    })
+    .add_attribute(StunAttribute::XorRelayedAddress {
+        socket_addr: relayed_v6,
+    })
+    .add_attribute(StunAttribute::Lifetime { seconds: 600 })
+    .add_message_integrity();
+
+    let encoded_message = message.encode(Some("pass")).unwrap();
+    check_sample_integrity(&encoded_message, Some("pass"));
+
+    let decoded = StunMessageRef::decode(&encoded_message, Some("pass")).unwrap();
+    assert_eq!(decoded.get_xor_relayed_address(), Some(relayed_v4));
+    assert_eq!(
+        decoded.get_xor_relayed_addresses(),
+        vec![relayed_v4, relayed_v6]
+    );
+
+    // Only the IPv4 address has been allocated
+    let message = StunMessage::new(
+        StunMessageMethod::Allocate,
+        StunMessageClass::SuccessResponse,
+    )
+    .add_attribute(StunAttribute::XorRelayedAddress {
+        socket_addr: relayed_v4,
+    })
+    .add_attribute(StunAttribute::AddressErrorCode {
+        family: StunAddressFamily::Ipv6,
+        class: 4,
+        number: 40,
+        reason: String::from("Address Family not Supported"),
+    })
+    .add_message_integrity();
+
+    let encoded_message = message.encode(Some("pass")).unwrap();
+
+    assert_eq!(
+        encoded_message[32..40],
+        [0x80, 0x01, 0x00, 0x20, 0x02, 0x00, 0x04, 0x28]
+    );
+
+    check_sample_integrity(&encoded_message, Some("pass"));
+
+    let decoded = StunMessageRef::decode(&encoded_message, Some("pass")).unwrap();
+    assert_eq!(decoded.get_xor_relayed_addresses(), vec![relayed_v4]);
+    assert_eq!(
+        decoded.get_address_error_code(),
+        Some((
+            StunAddressFamily::Ipv6,
+            4,
+            40,
+            "Address Family not Supported"
+        ))
+    );
+}
@@ -0,0 +1,89 @@
+use super::check_sample_integrity;
+use crate::{AttributeDecodeError, MessageDecodeError, StunAttribute, StunMessage};
+
+/// Tests that comprehension-optional attributes of unknown types survive decoding/recoding
+#[test]
+fn unknown_attribute_round_trip() {
+    let transaction_id = [
+        1u8, 2u8, 3u8, 4u8, 5u8, 6u8, 7u8, 8u8, 9u8, 10u8, 11u8, 12u8,
+    ];
+
+    let message = StunMessage::create_request()
+        .set_transaction_id(transaction_id)
+        .add_attribute(StunAttribute::Software {
+            description: String::from("test"),
+        })
+        .add_attribute(StunAttribute::Unknown {
+            attr_type: 0xC0FF,
+            value: vec![0x01, 0x02, 0x03, 0x04, 0x05],
+        })
+        .add_message_integrity()
+        .add_fingerprint();
+
+    let encoded_message = message.encode(Some("TEST_PASS")).unwrap();
+
+    // The unknown attribute is written as is and padded
+    assert_eq!(
+        encoded_message[28..40],
+        [0xC0, 0xFF, 0x00, 0x05, 0x01, 0x02, 0x03, 0x04, 0x05, 0x00, 0x00, 0x00]
+    );
+
+    // Message integrity and fingerprint still verify after decoding and re-encoding
+    check_sample_integrity(&encoded_message, Some("TEST_PASS"));
+
+    let decoded_msg = StunMessage::decode(&encoded_message, Some("TEST_PASS")).unwrap();
+
+    assert!(matches!(
+        &decoded_msg.get_attributes()[1],
+        StunAttribute::Unknown { attr_type: 0xC0FF, value } if value == &[0x01, 0x02, 0x03, 0x04, 0x05]
+    ));
+}
+
+/// Tests that comprehension-required attributes of unknown types fail decoding
+#[test]
+fn unknown_comprehension_required_attribute() {
+    let encoded_message = StunMessage::create_request()
+        .add_attribute(StunAttribute::Unknown {
+            attr_type: 0x7F00,
+            value: vec![0x01, 0x02, 0x03, 0x04],
+        })
+        .encode(None)
+        .unwrap();
+
+    let result = StunMessage::decode(&encoded_message, None);
+
+    assert!(matches!(
+        result,
+        Err(MessageDecodeError::AttributeDecodeFailure {
+            source: AttributeDecodeError::UnrecognizedAttributeType { attr_type: 0x7F00 },
+            ..
+        })
+    ));
+}
+
+/// Tests that the value of unknown attributes round-trips exactly, while their padding is normalised to zero
+#[test]
+fn unknown_attribute_non_zero_padding() {
+    let mut encoded_message = StunMessage::create_request()
+        .add_attribute(StunAttribute::Unknown {
+            attr_type: 0xC0FF,
+            value: vec![0x01, 0x02, 0x03, 0x04, 0x05],
+        })
+        .encode(None)
+        .unwrap();
+
+    // Padding bytes may take any value, as per RFC5389 Section 15
+    encoded_message[29..32].copy_from_slice(&[0xAA, 0xBB, 0xCC]);
+
+    let decoded_msg = StunMessage::decode(&encoded_message, None).unwrap();
+
+    assert!(matches!(
+        &decoded_msg.get_attributes()[0],
+        StunAttribute::Unknown { attr_type: 0xC0FF, value } if value == &[0x01, 0x02, 0x03, 0x04, 0x05]
+    ));
+
+    let reencoded_message = decoded_msg.encode(None).unwrap();
+
+    assert_eq!(reencoded_message[..29], encoded_message[..29]);
+    assert_eq!(reencoded_message[29..32], [0x00, 0x00, 0x00]);
+}
@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+
+use crate::{
+    MessageDecodeError, StunAttribute, StunMessage, StunMessageRef, StunPasswordAlgorithm,
+};
+
+/// USERHASH value of the [Sample Request with Long-Term Authentication with MESSAGE-INTEGRITY-SHA256 and USERHASH](https://tools.ietf.org/html/rfc8489#appendix-B.1)
+#[test]
+fn rfc8489_userhash() {
+    let userhash = StunMessage::calculate_userhash(
+        "\u{30DE}\u{30C8}\u{30EA}\u{30C3}\u{30AF}\u{30B9}",
+        "example.org",
+    )
+    .unwrap();
+
+    assert_eq!(
+        userhash,
+        [
+            0x4a, 0x3c, 0xf3, 0x8f, 0xef, 0x69, 0x92, 0xbd, 0xa9, 0x52, 0xc6, 0x78, 0x04, 0x17,
+            0xda, 0x0f, 0x24, 0x81, 0x94, 0x15, 0x56, 0x9e, 0x60, 0xb2, 0x05, 0xc4, 0x6e, 0x41,
+            0x40, 0x7f, 0x17, 0x04,
+        ]
+    );
+}
+
+fn anonymous_request(username: &str, key: &[u8]) -> Vec<u8> {
+    StunMessage::create_request()
+        .add_userhash(username, "example.org")
+        .unwrap()
+        .add_attribute(StunAttribute::Realm {
+            value: String::from("example.org"),
+        })
+        .add_attribute(StunAttribute::Nonce {
+            value: String::from("obMatJos2AAACf//499k954d6OL34oL9FSTvy64sA"),
+        })
+        .add_attribute(StunAttribute::PasswordAlgorithm {
+            algorithm: StunPasswordAlgorithm::Sha256,
+            parameters: Vec::new(),
+        })
+        .add_message_integrity_sha256()
+        .add_fingerprint()
+        .encode_with_key(Some(key))
+        .unwrap()
+}
+
+/// A server finds the credential by the USERHASH value
+#[test]
+fn credential_lookup() {
+    let mut users = HashMap::new();
+    for (username, password) in [("alice", "alice-pass"), ("bob", "bob-pass")].iter() {
+        let userhash = StunMessage::calculate_userhash(username, "example.org").unwrap();
+        let key = StunMessage::calculate_long_term_key(
+            username,
+            "example.org",
+            password,
+            StunPasswordAlgorithm::Sha256,
+        )
+        .unwrap();
+
+        users.insert(userhash, key);
+    }
+
+    let request = anonymous_request(
+        "bob",
+        &users[&StunMessage::calculate_userhash("bob", "example.org").unwrap()],
+    );
+
+    let lookup = |msg: &StunMessageRef| {
+        msg.get_userhash()
+            .and_then(|hash| users.get(&hash).cloned())
+    };
+
+    let decoded = StunMessageRef::decode_with_key_lookup(&request, lookup).unwrap();
+    assert_eq!(decoded.get_username(), None);
+    assert_eq!(decoded.get_realm(), Some("example.org"));
+
+    // Decoding with a password is impossible without a username
+    assert!(StunMessage::decode(&request, Some("bob-pass")).is_err());
+
+    // Unknown user
+    let unknown_key = StunMessage::calculate_long_term_key(
+        "eve",
+        "example.org",
+        "eve-pass",
+        StunPasswordAlgorithm::Sha256,
+    )
+    .unwrap();
+    let unknown_request = anonymous_request("eve", &unknown_key);
+    assert!(matches!(
+        StunMessage::decode_with_key_lookup(&unknown_request, lookup),
+        Err(MessageDecodeError::CredentialNotFound())
+    ));
+
+    // Wrong password
+    let wrong_key = StunMessage::calculate_long_term_key(
+        "alice",
+        "example.org",
+        "wrong-pass",
+        StunPasswordAlgorithm::Sha256,
+    )
+    .unwrap();
+    let forged_request = anonymous_request("alice", &wrong_key);
+    assert!(matches!(
+        StunMessage::decode_with_key_lookup(&forged_request, lookup),
+        Err(MessageDecodeError::MessageIntegrityFail { .. })
+    ));
+}
+
+/// The lookup function isn't needed for messages without integrity attributes
+#[test]
+fn no_integrity() {
+    let request = StunMessage::create_request()
+        .add_userhash("alice", "example.org")
+        .unwrap()
+        .encode(None)
+        .unwrap();
+
+    let decoded =
+        StunMessage::decode_with_key_lookup(&request, |_| panic!("Unexpected lookup")).unwrap();
+    assert_eq!(decoded.get_attributes().len(), 1);
+
+    // USERHASH values have a fixed length
+    let mut truncated = request.clone();
+    truncated[3] -= 4;
+    truncated[23] -= 4;
+    truncated.truncate(request.len() - 4);
+    assert!(StunMessage::decode(&truncated, None).is_err());
+}
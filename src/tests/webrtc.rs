@@ -0,0 +1,98 @@
+use super::check_sample_integrity;
+use crate::{StunAttribute, StunMessage, StunMessageRef};
+
+/// Tests an ICE connectivity check carrying WebRTC vendor attributes decoding/recoding
+#[test]
+fn connectivity_check() {
+    let message = StunMessage::create_request()
+        .add_attribute(StunAttribute::Username {
+            value: String::from("remote:local"),
+        })
+        .add_attribute(StunAttribute::GoogNetworkInfo {
+            network_id: 3,
+            network_cost: 10,
+        })
+        .add_attribute(StunAttribute::GoogMiscInfo {
+            values: vec![1, 2, 3],
+        })
+        .add_attribute(StunAttribute::IceControlling {
+            tie_breaker: 0x0102_0304_0506_0708,
+        })
+        .add_attribute(StunAttribute::UseCandidate)
+        .add_attribute(StunAttribute::Nomination { value: 7 })
+        .add_attribute(StunAttribute::Priority { value: 0x6E7F_1EFF })
+        .add_message_integrity()
+        .add_fingerprint();
+
+    let encoded_message = message.encode(Some("ice-pwd")).unwrap();
+
+    assert_eq!(
+        encoded_message[36..60],
+        [
+            0xC0, 0x57, 0x00, 0x04, 0x00, 0x03, 0x00, 0x0A, // GOOG-NETWORK-INFO
+            0xC0, 0x59, 0x00, 0x06, 0x00, 0x01, 0x00, 0x02, // GOOG-MISC-INFO
+            0x00, 0x03, 0x00, 0x00, // GOOG-MISC-INFO values and padding
+            0x80, 0x2A, 0x00, 0x08, // ICE-CONTROLLING
+        ]
+    );
+    assert_eq!(
+        encoded_message[72..80],
+        [0xC0, 0x01, 0x00, 0x04, 0x00, 0x00, 0x00, 0x07] // NOMINATION
+    );
+
+    check_sample_integrity(&encoded_message, Some("ice-pwd"));
+
+    let decoded = StunMessageRef::decode(&encoded_message, Some("ice-pwd")).unwrap();
+    assert_eq!(decoded.get_goog_network_info(), Some((3, 10)));
+    assert_eq!(decoded.get_goog_misc_info(), Some(&[1u16, 2, 3][..]));
+    assert_eq!(decoded.get_nomination(), Some(7));
+    assert!(decoded.has_use_candidate());
+}
+
+/// Tests the GOOG-DELTA, GOOG-DELTA-ACK and GOOG-LAST-ICE-CHECK-RECEIVED attributes decoding/recoding
+#[test]
+fn delta_exchange() {
+    let request = StunMessage::create_request()
+        .add_attribute(StunAttribute::GoogDelta {
+            value: vec![0x0A, 0x0B, 0x0C],
+        })
+        .add_message_integrity()
+        .add_fingerprint();
+
+    let encoded_request = request.encode(Some("ice-pwd")).unwrap();
+
+    assert_eq!(
+        encoded_request[20..28],
+        [0xC0, 0x5C, 0x00, 0x03, 0x0A, 0x0B, 0x0C, 0x00]
+    );
+    check_sample_integrity(&encoded_request, Some("ice-pwd"));
+
+    let decoded_request = StunMessageRef::decode(&encoded_request, Some("ice-pwd")).unwrap();
+    assert_eq!(
+        decoded_request.get_goog_delta(),
+        Some(&[0x0A, 0x0B, 0x0C][..])
+    );
+
+    let transaction_id = decoded_request.get_header().transaction_id;
+    let response = StunMessage::create_success_response()
+        .set_transaction_id(transaction_id)
+        .add_attribute(StunAttribute::GoogDeltaAck { value: 42 })
+        .add_attribute(StunAttribute::GoogLastIceCheckReceived {
+            value: transaction_id.to_vec(),
+        })
+        .add_message_integrity()
+        .add_fingerprint();
+
+    let encoded_response = response.encode(Some("ice-pwd")).unwrap();
+
+    assert_eq!(encoded_response[20..24], [0xC0, 0x5D, 0x00, 0x08]);
+    assert_eq!(encoded_response[32..36], [0xC0, 0x58, 0x00, 0x0C]);
+    check_sample_integrity(&encoded_response, Some("ice-pwd"));
+
+    let decoded_response = StunMessageRef::decode(&encoded_response, Some("ice-pwd")).unwrap();
+    assert_eq!(decoded_response.get_goog_delta_ack(), Some(42));
+    assert_eq!(
+        decoded_response.get_goog_last_ice_check_received(),
+        Some(&transaction_id[..])
+    );
+}
@@ -0,0 +1,311 @@
+use std::time::{Duration, Instant};
+
+use crate::definitions::StunTransactionId;
+use crate::{MessageEncodeError, StunMessage, StunMessageClass, StunMessageMethod, StunMessageRef};
+
+/// Transport protocol the transaction is run over, as it determines the retransmission behavior
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum StunTransport {
+    /// Unreliable transport, such as UDP. The request is retransmitted until a response is received.
+    Unreliable,
+    /// Reliable transport, such as TCP or TLS-over-TCP. The request is sent only once.
+    Reliable,
+}
+
+/// Event produced by a [`StunClientTransaction`]
+#[derive(Debug, Clone)]
+pub enum StunTransactionEvent {
+    /// Encoded request that has to be sent to the server
+    Transmit(Vec<u8>),
+    /// No response has been received in time. The transaction is finished.
+    Timeout,
+    /// Success or error response to the request. The transaction is finished.
+    Response(StunMessage),
+}
+
+/// Sans-IO [RFC5389](https://tools.ietf.org/html/rfc5389#section-7.2) client transaction
+///
+/// Tracks a single outstanding request by its transaction ID. It doesn't perform any IO by itself. Instead, the caller:
+///
+/// * calls `handle_timeout` once the instant returned by `poll_timeout` is reached,
+/// * feeds every message received from the server into `handle_input`,
+/// * sends the payload of every `Transmit` event to the server.
+///
+/// The first transmission is scheduled at the creation time of the transaction.
+///
+/// Over unreliable transports the request is retransmitted with an exponentially increasing interval,
+/// starting at RTO and doubling after each transmission. Up to Rc transmissions are done in total.
+/// If no response is received within Rm times the RTO after the last transmission, the transaction times out.
+/// With the default values the request is sent at 0 ms, 500 ms, 1500 ms, 3500 ms, 7500 ms, 15500 ms and 31500 ms,
+/// and the transaction times out at 39500 ms.
+///
+/// Over reliable transports the request is sent once and the transaction times out after Ti.
+///
+/// ```no_run
+/// use std::net::UdpSocket;
+/// use std::time::Instant;
+/// use stun_coder::{StunClientTransaction, StunMessage, StunTransactionEvent, StunTransport};
+///
+/// let socket = UdpSocket::bind("0.0.0.0:0").unwrap();
+/// socket.connect("stun.l.google.com:19302").unwrap();
+///
+/// let request = StunMessage::create_request();
+/// let mut transaction =
+///     StunClientTransaction::new(&request, None, StunTransport::Unreliable, Instant::now()).unwrap();
+/// let mut buf = [0u8; 1500];
+///
+/// let response = loop {
+///     let deadline = transaction.poll_timeout().unwrap();
+///     socket
+///         .set_read_timeout(Some(deadline.saturating_duration_since(Instant::now()).max(std::time::Duration::from_millis(1))))
+///         .unwrap();
+///
+///     let event = match socket.recv(&mut buf) {
+///         Ok(len) => transaction.handle_input(&buf[..len], Instant::now()),
+///         Err(_) => transaction.handle_timeout(Instant::now()),
+///     };
+///
+///     match event {
+///         Some(StunTransactionEvent::Transmit(payload)) => {
+///             socket.send(&payload).unwrap();
+///         }
+///         Some(StunTransactionEvent::Response(response)) => break Some(response),
+///         Some(StunTransactionEvent::Timeout) => break None,
+///         None => {}
+///     }
+/// };
+///
+/// println!("{:?}", response);
+/// ```
+pub struct StunClientTransaction {
+    /// Transaction ID of the request
+    transaction_id: StunTransactionId,
+    /// Method of the request
+    method: StunMessageMethod,
+    /// Encoded request
+    request: Vec<u8>,
+    /// HMAC key used to verify the response integrity
+    integrity_key: Option<Vec<u8>>,
+    /// Transport the request is sent over
+    transport: StunTransport,
+    /// Initial retransmission timeout
+    rto: Duration,
+    /// Maximum amount of transmissions (Rc)
+    max_transmissions: u32,
+    /// Multiplier of the RTO used to wait for a response after the last transmission (Rm)
+    last_timeout_multiplier: u32,
+    /// Transaction timeout over reliable transports (Ti)
+    reliable_timeout: Duration,
+    /// Amount of transmissions done so far
+    transmissions: u32,
+    /// Instant of the first transmission
+    first_transmission: Option<Instant>,
+    /// Instant the response has been received at
+    response_time: Option<Instant>,
+    /// Instant of the next transmission or of the transaction timeout. Unset once the transaction is finished.
+    deadline: Option<Instant>,
+}
+
+impl StunClientTransaction {
+    /// Default initial retransmission timeout
+    pub const DEFAULT_RTO: Duration = Duration::from_millis(500);
+    /// Default maximum amount of transmissions (Rc)
+    pub const DEFAULT_MAX_TRANSMISSIONS: u32 = 7;
+    /// Default multiplier of the RTO used to wait for a response after the last transmission (Rm)
+    pub const DEFAULT_LAST_TIMEOUT_MULTIPLIER: u32 = 16;
+    /// Default transaction timeout over reliable transports (Ti)
+    pub const DEFAULT_RELIABLE_TIMEOUT: Duration = Duration::from_millis(39500);
+    /// Upper bound of the time waited after a transmission
+    const MAX_WAIT: Duration = Duration::from_secs(u32::MAX as u64);
+
+    /// Creates a new client transaction and schedules the first transmission of the request at `now`
+    ///
+    /// Arguments:
+    ///
+    /// * `request`: Request to send. Its transaction ID identifies the transaction.
+    /// * `integrity_key`: Optionally set HMAC key used for the request integrity generation and the response integrity verification.
+    ///   For short-term credentials it is the password. See [`calculate_long_term_key`](StunMessage::calculate_long_term_key) for long-term credentials.
+    /// * `transport`: Transport the request is sent over
+    /// * `now`: Current time
+    pub fn new(
+        request: &StunMessage,
+        integrity_key: Option<&[u8]>,
+        transport: StunTransport,
+        now: Instant,
+    ) -> Result<Self, MessageEncodeError> {
+        let header = request.get_header();
+
+        Ok(Self {
+            transaction_id: header.transaction_id,
+            method: header.message_method,
+            request: request.encode_with_key(integrity_key)?,
+            integrity_key: integrity_key.map(|key| key.to_vec()),
+            transport,
+            rto: Self::DEFAULT_RTO,
+            max_transmissions: Self::DEFAULT_MAX_TRANSMISSIONS,
+            last_timeout_multiplier: Self::DEFAULT_LAST_TIMEOUT_MULTIPLIER,
+            reliable_timeout: Self::DEFAULT_RELIABLE_TIMEOUT,
+            transmissions: 0,
+            first_transmission: None,
+            response_time: None,
+            deadline: Some(now),
+        })
+    }
+
+    /// Sets the initial retransmission timeout
+    pub fn set_rto(mut self, rto: Duration) -> Self {
+        self.rto = rto;
+
+        self
+    }
+
+    /// Sets the maximum amount of transmissions (Rc). At least one transmission is always done.
+    pub fn set_max_transmissions(mut self, max_transmissions: u32) -> Self {
+        self.max_transmissions = max_transmissions.max(1);
+
+        self
+    }
+
+    /// Sets the multiplier of the RTO used to wait for a response after the last transmission (Rm)
+    pub fn set_last_timeout_multiplier(mut self, multiplier: u32) -> Self {
+        self.last_timeout_multiplier = multiplier;
+
+        self
+    }
+
+    /// Sets the transaction timeout over reliable transports (Ti)
+    pub fn set_reliable_timeout(mut self, timeout: Duration) -> Self {
+        self.reliable_timeout = timeout;
+
+        self
+    }
+
+    /// Returns the transaction ID of the request
+    pub fn get_transaction_id(&self) -> StunTransactionId {
+        self.transaction_id
+    }
+
+    /// Returns the amount of times the request has been sent so far
+    pub fn get_transmissions(&self) -> u32 {
+        self.transmissions
+    }
+
+    /// Checks whether a response to the request has been received
+    pub fn has_response(&self) -> bool {
+        self.response_time.is_some()
+    }
+
+    /// Returns the round-trip time measured by the transaction
+    ///
+    /// As per [Karn's algorithm](https://tools.ietf.org/html/rfc6298#section-3), the RTT is only measured
+    /// if a response has been received and the request hasn't been retransmitted,
+    /// as it is ambiguous which transmission the response belongs to otherwise.
+    /// The RTT spans from the first transmission to the arrival of the response passed to `handle_input`.
+    pub fn get_rtt_sample(&self) -> Option<Duration> {
+        match (self.first_transmission, self.response_time) {
+            (Some(first_transmission), Some(response_time)) if self.transmissions == 1 => {
+                Some(response_time.saturating_duration_since(first_transmission))
+            }
+            _ => None,
+        }
+    }
+
+    /// Checks whether a response has been received or the transaction has timed out
+    pub fn is_finished(&self) -> bool {
+        self.deadline.is_none()
+    }
+
+    /// Returns the instant `handle_timeout` has to be called at
+    pub fn poll_timeout(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Handles the expiration of the transaction timer
+    ///
+    /// Returns the request to (re)transmit, or the timeout of the transaction.
+    /// Returns `None` if the timer hasn't expired yet or the transaction is finished.
+    ///
+    /// Arguments:
+    ///
+    /// * `now`: Current time
+    pub fn handle_timeout(&mut self, now: Instant) -> Option<StunTransactionEvent> {
+        match self.deadline {
+            Some(deadline) if deadline <= now => {}
+            _ => return None,
+        }
+
+        if self.transmissions >= self.max_transmissions
+            || (self.transport == StunTransport::Reliable && self.transmissions > 0)
+        {
+            self.deadline = None;
+
+            return Some(StunTransactionEvent::Timeout);
+        }
+
+        self.transmissions += 1;
+        self.first_transmission.get_or_insert(now);
+
+        let wait = match self.transport {
+            StunTransport::Reliable => Some(self.reliable_timeout),
+            // The last transmission waits Rm times the initial RTO
+            StunTransport::Unreliable if self.transmissions == self.max_transmissions => {
+                self.rto.checked_mul(self.last_timeout_multiplier)
+            }
+            StunTransport::Unreliable => 2u32
+                .checked_pow(self.transmissions - 1)
+                .and_then(|multiplier| self.rto.checked_mul(multiplier)),
+        };
+        // Large RTO, Rc or Rm values must not overflow the deadline
+        let wait = wait.map_or(Self::MAX_WAIT, |wait| wait.min(Self::MAX_WAIT));
+        self.deadline = Some(now.checked_add(wait).unwrap_or(now));
+
+        Some(StunTransactionEvent::Transmit(self.request.clone()))
+    }
+
+    /// Handles a message received from the server
+    ///
+    /// Returns the response if the message is a success or error response to the request.
+    /// Other messages, including responses that fail the integrity check, are ignored and `None` is returned.
+    /// When an integrity key is set, responses without an integrity attribute are ignored as well,
+    /// except for the 400 (Bad Request), 401 (Unauthorized) and 438 (Stale Nonce) error responses.
+    ///
+    /// Arguments:
+    ///
+    /// * `bytes`: Received message
+    /// * `now`: Time the message has been received at
+    pub fn handle_input(&mut self, bytes: &[u8], now: Instant) -> Option<StunTransactionEvent> {
+        if self.is_finished() {
+            return None;
+        }
+
+        let response =
+            StunMessageRef::decode_with_key(bytes, self.integrity_key.as_deref()).ok()?;
+        let header = response.get_header();
+
+        if header.transaction_id != self.transaction_id || header.message_method != self.method {
+            return None;
+        }
+
+        // Responses to authenticated requests have to be authenticated as well, as per RFC5389 Section 10.1.2.
+        // Only the errors challenging the credentials (400, 401 and 438) are processed without an integrity attribute.
+        let has_integrity = response.get_message_integrity().is_some()
+            || response.get_message_integrity_sha256().is_some();
+        let is_challenge = matches!(
+            response.get_error_code(),
+            Some((4, 0, _)) | Some((4, 1, _)) | Some((4, 38, _))
+        );
+        if self.integrity_key.is_some() && !has_integrity && !is_challenge {
+            return None;
+        }
+
+        match header.message_class {
+            StunMessageClass::SuccessResponse | StunMessageClass::ErrorResponse => {
+                self.deadline = None;
+                self.response_time = Some(now);
+
+                Some(StunTransactionEvent::Response(response.into_owned()))
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+use std::time::Instant;
+
+use super::client::{StunClientTransaction, StunTransactionEvent};
+use crate::definitions::StunTransactionId;
+use crate::{check_for_stun_message_header, StunMessageClass};
+
+/// Event of a transaction sharing a [`StunTransactionCorrelator`]
+pub struct StunCorrelatedEvent {
+    /// Transaction ID of the transaction that produced the event
+    pub transaction_id: StunTransactionId,
+    /// Event produced by the transaction
+    pub event: StunTransactionEvent,
+    /// Transaction removed from the table, if the event finished it.
+    /// It can be fed to a [`StunRtoEstimator`](crate::StunRtoEstimator).
+    pub finished: Option<StunClientTransaction>,
+}
+
+/// Table of outstanding client transactions sharing a single socket
+///
+/// Incoming responses are routed to the transaction their transaction ID belongs to.
+/// Responses that don't match an outstanding request by transaction ID, method or class are dropped,
+/// as well as unsolicited responses. Transactions are removed from the table once they are finished,
+/// and handed back along with the event that finished them.
+///
+/// The events are reported along with the transaction ID of the transaction that produced them,
+/// so the caller can associate each transaction with a destination or any other context of its own.
+///
+/// ```
+/// # use std::time::Instant;
+/// # use stun_coder::{StunClientTransaction, StunCorrelatedEvent, StunMessage, StunTransactionCorrelator, StunTransactionEvent, StunTransport};
+/// let now = Instant::now();
+/// let mut correlator = StunTransactionCorrelator::new();
+///
+/// for _ in 0..3 {
+///     let request = StunMessage::create_request();
+///     let transaction =
+///         StunClientTransaction::new(&request, None, StunTransport::Unreliable, now).unwrap();
+///     correlator.insert(transaction);
+/// }
+///
+/// // All the requests are due now
+/// assert_eq!(correlator.poll_timeout(), Some(now));
+///
+/// let events = correlator.handle_timeout(now);
+/// assert_eq!(events.len(), 3);
+///
+/// // A response to the first request finishes its transaction
+/// let transaction_id = events[0].transaction_id;
+/// let response = StunMessage::create_success_response()
+///     .set_transaction_id(transaction_id)
+///     .encode(None)
+///     .unwrap();
+///
+/// match correlator.handle_input(&response, now) {
+///     Some(StunCorrelatedEvent {
+///         transaction_id: id,
+///         event: StunTransactionEvent::Response(_),
+///         finished: Some(transaction),
+///     }) => {
+///         assert_eq!(id, transaction_id);
+///         assert!(transaction.has_response());
+///     }
+///     _ => unreachable!(),
+/// }
+/// assert_eq!(correlator.len(), 2);
+/// ```
+#[derive(Default)]
+pub struct StunTransactionCorrelator {
+    /// Outstanding transactions
+    transactions: HashMap<StunTransactionId, StunClientTransaction>,
+}
+
+impl StunTransactionCorrelator {
+    /// Creates an empty correlator
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a transaction to the table
+    ///
+    /// Returns the transaction previously registered with the same transaction ID, if any.
+    pub fn insert(&mut self, transaction: StunClientTransaction) -> Option<StunClientTransaction> {
+        self.transactions
+            .insert(transaction.get_transaction_id(), transaction)
+    }
+
+    /// Removes a transaction from the table, cancelling it
+    pub fn remove(&mut self, transaction_id: &StunTransactionId) -> Option<StunClientTransaction> {
+        self.transactions.remove(transaction_id)
+    }
+
+    /// Returns the outstanding transaction with the provided transaction ID
+    pub fn get(&self, transaction_id: &StunTransactionId) -> Option<&StunClientTransaction> {
+        self.transactions.get(transaction_id)
+    }
+
+    /// Returns the amount of outstanding transactions
+    pub fn len(&self) -> usize {
+        self.transactions.len()
+    }
+
+    /// Checks whether there are no outstanding transactions
+    pub fn is_empty(&self) -> bool {
+        self.transactions.is_empty()
+    }
+
+    /// Returns the earliest instant `handle_timeout` has to be called at
+    pub fn poll_timeout(&self) -> Option<Instant> {
+        self.transactions
+            .values()
+            .filter_map(|transaction| transaction.poll_timeout())
+            .min()
+    }
+
+    /// Handles the expiration of the transaction timers
+    ///
+    /// Returns the requests to (re)transmit and the transactions that have timed out.
+    /// Timed out transactions are removed from the table and returned along with their timeout event.
+    ///
+    /// Arguments:
+    ///
+    /// * `now`: Current time
+    pub fn handle_timeout(&mut self, now: Instant) -> Vec<StunCorrelatedEvent> {
+        let transaction_ids: Vec<StunTransactionId> = self.transactions.keys().copied().collect();
+        let mut events = Vec::new();
+
+        for transaction_id in transaction_ids {
+            let transaction = match self.transactions.get_mut(&transaction_id) {
+                Some(transaction) => transaction,
+                None => continue,
+            };
+            let event = transaction.handle_timeout(now);
+
+            let finished = if transaction.is_finished() {
+                self.transactions.remove(&transaction_id)
+            } else {
+                None
+            };
+
+            if let Some(event) = event {
+                events.push(StunCorrelatedEvent {
+                    transaction_id,
+                    event,
+                    finished,
+                });
+            }
+        }
+
+        events
+    }
+
+    /// Handles a message received on the shared socket
+    ///
+    /// Returns the response along with its transaction ID if the message is a response to an outstanding request.
+    /// The transaction is removed from the table and returned as well. Other messages are dropped and `None` is returned.
+    ///
+    /// Arguments:
+    ///
+    /// * `bytes`: Received message
+    /// * `now`: Time the message has been received at
+    pub fn handle_input(&mut self, bytes: &[u8], now: Instant) -> Option<StunCorrelatedEvent> {
+        let header = check_for_stun_message_header(bytes)?;
+
+        // Requests and indications are never routed to client transactions
+        match header.message_class {
+            StunMessageClass::SuccessResponse | StunMessageClass::ErrorResponse => {}
+            _ => return None,
+        }
+
+        let transaction = self.transactions.get_mut(&header.transaction_id)?;
+        let event = transaction.handle_input(bytes, now)?;
+
+        Some(StunCorrelatedEvent {
+            transaction_id: header.transaction_id,
+            event,
+            finished: self.transactions.remove(&header.transaction_id),
+        })
+    }
+}
@@ -0,0 +1,359 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+use stringprep::saslprep;
+
+use super::client::{StunClientTransaction, StunTransactionEvent, StunTransport};
+use super::errors::LongTermCredentialError;
+use crate::definitions::StunTransactionId;
+use crate::{StunAttribute, StunMessage, StunPasswordAlgorithm};
+
+/// Realm and nonce most recently received from a server
+#[derive(Debug, Clone)]
+struct ServerChallenge {
+    realm: String,
+    nonce: String,
+}
+
+/// Request in progress
+struct PendingRequest {
+    /// Server the request is sent to
+    server: SocketAddr,
+    /// Request without the credential attributes
+    request: StunMessage,
+    /// Transaction of the current attempt
+    transaction: StunClientTransaction,
+    /// Set once the request has been retried after a 401 (Unauthorized) response
+    challenged: bool,
+    /// Set once the request has been retried after a 438 (Stale Nonce) response
+    stale_nonce_retried: bool,
+}
+
+/// Sans-IO client side of the [RFC5389 long-term credential mechanism](https://tools.ietf.org/html/rfc5389#section-10.2)
+///
+/// Runs one request at a time on top of a [`StunClientTransaction`]:
+///
+/// * The request is first sent without credentials, unless the realm and nonce of the server are already known.
+/// * A 401 (Unauthorized) response carrying the REALM and NONCE attributes makes the client retry the request
+///   with the USERNAME, REALM, NONCE and MESSAGE-INTEGRITY attributes.
+/// * A 438 (Stale Nonce) response makes the client retry the request with the new nonce.
+///
+/// Each retry is a new transaction with a new transaction ID, and is done at most once per request.
+/// The realm and nonce are cached per server, so subsequent requests to the same server are authenticated right away.
+/// The integrity of the responses to authenticated requests is verified with the long-term key.
+///
+/// The events of the underlying transactions are passed through to the caller, which:
+///
+/// * calls `handle_timeout` once the instant returned by `poll_timeout` is reached,
+/// * feeds every message received from the server into `handle_input`,
+/// * sends the payload of every `Transmit` event to the server.
+///
+/// The final response, or the 401/438 error response if the retry fails as well, is returned via a `Response` event.
+///
+/// ```no_run
+/// use std::net::UdpSocket;
+/// use std::time::Instant;
+/// use stun_coder::{
+///     LongTermCredentialClient, StunAttribute, StunMessage, StunMessageClass, StunMessageMethod,
+///     StunTransactionEvent,
+/// };
+///
+/// let server = "198.51.100.1:3478".parse().unwrap();
+/// let socket = UdpSocket::bind("0.0.0.0:0").unwrap();
+/// socket.connect(server).unwrap();
+///
+/// let mut client = LongTermCredentialClient::new("user", "pass");
+/// let allocate = StunMessage::new(StunMessageMethod::Allocate, StunMessageClass::Request)
+///     .add_attribute(StunAttribute::RequestedTransport { protocol: 17 });
+/// client.start_request(server, allocate, Instant::now()).unwrap();
+///
+/// let mut buf = [0u8; 1500];
+/// let response = loop {
+///     let deadline = client.poll_timeout().unwrap();
+///     socket
+///         .set_read_timeout(Some(deadline.saturating_duration_since(Instant::now()).max(std::time::Duration::from_millis(1))))
+///         .unwrap();
+///
+///     let event = match socket.recv(&mut buf) {
+///         Ok(len) => client.handle_input(&buf[..len], Instant::now()).unwrap(),
+///         Err(_) => client.handle_timeout(Instant::now()),
+///     };
+///
+///     match event {
+///         Some(StunTransactionEvent::Transmit(payload)) => {
+///             socket.send(&payload).unwrap();
+///         }
+///         Some(StunTransactionEvent::Response(response)) => break Some(response),
+///         Some(StunTransactionEvent::Timeout) => break None,
+///         None => {}
+///     }
+/// };
+///
+/// println!("{:?}", response);
+/// ```
+pub struct LongTermCredentialClient {
+    /// Username of the long-term credential
+    username: String,
+    /// Password of the long-term credential
+    password: String,
+    /// Transport the requests are sent over
+    transport: StunTransport,
+    /// Initial retransmission timeout of the transactions
+    rto: Duration,
+    /// Realm and nonce cached per server
+    challenges: HashMap<SocketAddr, ServerChallenge>,
+    /// Request in progress
+    pending: Option<PendingRequest>,
+}
+
+impl LongTermCredentialClient {
+    /// Creates a new client
+    ///
+    /// Arguments:
+    ///
+    /// * `username`: Username of the long-term credential
+    /// * `password`: Password of the long-term credential. Processed via SASLprep.
+    pub fn new(username: &str, password: &str) -> Self {
+        Self {
+            username: String::from(username),
+            password: String::from(password),
+            transport: StunTransport::Unreliable,
+            rto: StunClientTransaction::DEFAULT_RTO,
+            challenges: HashMap::new(),
+            pending: None,
+        }
+    }
+
+    /// Sets the transport the requests are sent over
+    pub fn set_transport(mut self, transport: StunTransport) -> Self {
+        self.transport = transport;
+
+        self
+    }
+
+    /// Sets the initial retransmission timeout of the transactions, for instance the one estimated by a [`StunRtoEstimator`](crate::StunRtoEstimator)
+    pub fn set_rto(mut self, rto: Duration) -> Self {
+        self.rto = rto;
+
+        self
+    }
+
+    /// Returns the realm cached for the server
+    pub fn get_realm(&self, server: SocketAddr) -> Option<&str> {
+        self.challenges
+            .get(&server)
+            .map(|challenge| challenge.realm.as_str())
+    }
+
+    /// Returns the nonce cached for the server
+    pub fn get_nonce(&self, server: SocketAddr) -> Option<&str> {
+        self.challenges
+            .get(&server)
+            .map(|challenge| challenge.nonce.as_str())
+    }
+
+    /// Returns the transaction of the current attempt of the request in progress
+    pub fn get_transaction(&self) -> Option<&StunClientTransaction> {
+        self.pending.as_ref().map(|pending| &pending.transaction)
+    }
+
+    /// Starts a request, replacing the request in progress if any
+    ///
+    /// The first transmission is scheduled at `now`.
+    ///
+    /// Arguments:
+    ///
+    /// * `server`: Server the request is sent to
+    /// * `request`: Request to send. It must not contain integrity attributes.
+    ///   If it contains the FINGERPRINT attribute, the attribute is moved after the credential attributes.
+    /// * `now`: Current time
+    pub fn start_request(
+        &mut self,
+        server: SocketAddr,
+        request: StunMessage,
+        now: Instant,
+    ) -> Result<(), LongTermCredentialError> {
+        let transaction_id = request.get_header().transaction_id;
+        let transaction = self.create_transaction(server, &request, transaction_id, now)?;
+
+        self.pending = Some(PendingRequest {
+            server,
+            request,
+            transaction,
+            challenged: false,
+            stale_nonce_retried: false,
+        });
+
+        Ok(())
+    }
+
+    /// Returns the instant `handle_timeout` has to be called at
+    pub fn poll_timeout(&self) -> Option<Instant> {
+        self.pending
+            .as_ref()
+            .and_then(|pending| pending.transaction.poll_timeout())
+    }
+
+    /// Handles the expiration of the transaction timer
+    ///
+    /// Returns the request to (re)transmit, or the timeout of the request.
+    ///
+    /// Arguments:
+    ///
+    /// * `now`: Current time
+    pub fn handle_timeout(&mut self, now: Instant) -> Option<StunTransactionEvent> {
+        let event = self.pending.as_mut()?.transaction.handle_timeout(now);
+
+        if let Some(StunTransactionEvent::Timeout) = event {
+            self.pending = None;
+        }
+
+        event
+    }
+
+    /// Handles a message received from the server
+    ///
+    /// Returns the authenticated request to transmit after a 401 or 438 error response, or the final response.
+    /// Messages that are not responses to the current attempt are ignored and `None` is returned.
+    ///
+    /// Arguments:
+    ///
+    /// * `bytes`: Received message
+    /// * `now`: Current time, used to schedule the retried request
+    pub fn handle_input(
+        &mut self,
+        bytes: &[u8],
+        now: Instant,
+    ) -> Result<Option<StunTransactionEvent>, LongTermCredentialError> {
+        let pending = match self.pending.as_mut() {
+            Some(pending) => pending,
+            None => return Ok(None),
+        };
+
+        let response = match pending.transaction.handle_input(bytes, now) {
+            Some(StunTransactionEvent::Response(response)) => response,
+            event => return Ok(event),
+        };
+
+        let attributes = response.get_attributes();
+        let error_code = attributes.iter().find_map(|attr| match attr {
+            StunAttribute::ErrorCode { class, number, .. } => Some((*class, *number)),
+            _ => None,
+        });
+        let realm = attributes.iter().find_map(|attr| match attr {
+            StunAttribute::Realm { value } => Some(value.as_str()),
+            _ => None,
+        });
+        let nonce = attributes.iter().find_map(|attr| match attr {
+            StunAttribute::Nonce { value } => Some(value.as_str()),
+            _ => None,
+        });
+
+        let server = pending.server;
+        let challenge = match (error_code, realm, nonce) {
+            // Unauthorized
+            (Some((4, 1)), Some(realm), Some(nonce)) if !pending.challenged => {
+                pending.challenged = true;
+
+                Some(ServerChallenge {
+                    realm: String::from(realm),
+                    nonce: String::from(nonce),
+                })
+            }
+            // Stale Nonce. The REALM attribute is optional, the cached one is used if missing.
+            (Some((4, 38)), realm, Some(nonce)) if !pending.stale_nonce_retried => {
+                pending.stale_nonce_retried = true;
+
+                realm
+                    .map(String::from)
+                    .or_else(|| self.get_realm(server).map(String::from))
+                    .map(|realm| ServerChallenge {
+                        realm,
+                        nonce: String::from(nonce),
+                    })
+            }
+            _ => None,
+        };
+
+        let challenge = match challenge {
+            Some(challenge) => challenge,
+            None => {
+                self.pending = None;
+
+                return Ok(Some(StunTransactionEvent::Response(response)));
+            }
+        };
+
+        self.challenges.insert(server, challenge);
+
+        // Retry the request with a new transaction
+        let request = match &self.pending {
+            Some(pending) => &pending.request,
+            None => return Ok(None),
+        };
+        let mut transaction =
+            match self.create_transaction(server, request, crate::generate_transaction_id(), now) {
+                Ok(transaction) => transaction,
+                Err(err) => {
+                    self.pending = None;
+
+                    return Err(err);
+                }
+            };
+        let event = transaction.handle_timeout(now);
+
+        if let Some(pending) = self.pending.as_mut() {
+            pending.transaction = transaction;
+        }
+
+        Ok(event)
+    }
+
+    // Creates a transaction for the request, adding the credentials if the realm and nonce of the server are known
+    fn create_transaction(
+        &self,
+        server: SocketAddr,
+        request: &StunMessage,
+        transaction_id: StunTransactionId,
+        now: Instant,
+    ) -> Result<StunClientTransaction, LongTermCredentialError> {
+        // Only the transaction ID changes, the dialect of the request is kept
+        let mut message =
+            StunMessage::with_header(*request.get_header()).set_transaction_id(transaction_id);
+
+        let mut has_fingerprint = false;
+        for attr in request.get_attributes() {
+            match attr {
+                StunAttribute::Fingerprint { .. } => has_fingerprint = true,
+                attr => message = message.add_attribute(attr.clone()),
+            }
+        }
+
+        let mut integrity_key = None;
+        if let Some(challenge) = self.challenges.get(&server) {
+            message = message.add_long_term_credential_message_integrity(
+                &self.username,
+                &challenge.realm,
+                &challenge.nonce,
+            )?;
+
+            // The password is processed via SASLprep while calculating the key
+            integrity_key = Some(StunMessage::calculate_long_term_key(
+                &saslprep(&self.username)?,
+                &saslprep(&challenge.realm)?,
+                &self.password,
+                StunPasswordAlgorithm::Md5,
+            )?);
+        }
+
+        if has_fingerprint {
+            message = message.add_fingerprint();
+        }
+
+        Ok(
+            StunClientTransaction::new(&message, integrity_key.as_deref(), self.transport, now)?
+                .set_rto(self.rto),
+        )
+    }
+}
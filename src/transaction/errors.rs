@@ -0,0 +1,17 @@
+use thiserror::Error;
+
+use crate::{IntegrityKeyGenerationError, MessageEncodeError};
+
+/// Long-term credential client errors.
+#[derive(Error, Debug)]
+pub enum LongTermCredentialError {
+    /// Failed to process the username or the realm via SASLprep.
+    #[error("Failed to process the credentials via SASLprep.")]
+    SASLPrepFailure(#[from] stringprep::Error),
+    /// Failed to compute the long-term credential key.
+    #[error("Failed to compute the long-term credential key.")]
+    KeyGenerationFailure(#[from] IntegrityKeyGenerationError),
+    /// Failed to encode the request.
+    #[error("Failed to encode the request.")]
+    RequestEncodeFailure(#[from] MessageEncodeError),
+}
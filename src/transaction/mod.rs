@@ -0,0 +1,11 @@
+mod client;
+mod correlator;
+mod credential;
+mod errors;
+mod rto;
+
+pub use client::{StunClientTransaction, StunTransactionEvent, StunTransport};
+pub use correlator::{StunCorrelatedEvent, StunTransactionCorrelator};
+pub use credential::LongTermCredentialClient;
+pub use errors::LongTermCredentialError;
+pub use rto::StunRtoEstimator;
@@ -0,0 +1,275 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+use super::client::StunClientTransaction;
+
+/// Smoothed RTT state of a single server
+struct RtoEntry {
+    /// Smoothed round-trip time (SRTT). Unset until the first RTT sample is taken.
+    srtt: Option<Duration>,
+    /// Round-trip time variation (RTTVAR)
+    rttvar: Duration,
+    /// Current retransmission timeout
+    rto: Duration,
+    /// Last time the entry has been updated at
+    updated_at: Instant,
+}
+
+/// Per-server RTO estimator, as per [RFC5389 Section 7.2.1](https://tools.ietf.org/html/rfc5389#section-7.2.1)
+///
+/// The retransmission timeout is computed from the measured round-trip times following [RFC6298](https://tools.ietf.org/html/rfc6298#section-2):
+///
+///```text
+///   first sample R:  SRTT <- R, RTTVAR <- R/2
+///   next samples R': RTTVAR <- (1 - 1/4) * RTTVAR + 1/4 * |SRTT - R'|
+///                    SRTT <- (1 - 1/8) * SRTT + 1/8 * R'
+///   RTO <- SRTT + 4 * RTTVAR
+///```
+///
+/// The RTO is clamped between the configured minimum and maximum values and doubled on each transaction timeout.
+/// Each server is identified by its transport address. The estimate of a server is discarded
+/// once it hasn't been updated for the cache lifetime, 10 minutes by default, and the initial RTO is used instead.
+///
+/// ```
+/// # use std::time::{Duration, Instant};
+/// # use stun_coder::{StunClientTransaction, StunMessage, StunRtoEstimator, StunTransactionEvent, StunTransport};
+/// let server = "192.0.2.1:3478".parse().unwrap();
+/// let mut estimator = StunRtoEstimator::new();
+/// let start = Instant::now();
+///
+/// let request = StunMessage::create_request();
+/// let mut transaction = StunClientTransaction::new(&request, None, StunTransport::Unreliable, start)
+///     .unwrap()
+///     .set_rto(estimator.get_rto(server, start));
+///
+/// transaction.handle_timeout(start);
+///
+/// // The response arrives after 800 ms over a satellite link
+/// let response = StunMessage::create_success_response()
+///     .set_transaction_id(transaction.get_transaction_id())
+///     .encode(None)
+///     .unwrap();
+/// let now = start + Duration::from_millis(800);
+/// transaction.handle_input(&response, now);
+///
+/// estimator.update(server, &transaction, now);
+///
+/// // RTO = 800 ms + 4 * 400 ms
+/// assert_eq!(estimator.get_rto(server, now), Duration::from_millis(2400));
+/// ```
+pub struct StunRtoEstimator {
+    /// RTO used for servers without an estimate
+    initial_rto: Duration,
+    /// Lower bound of the RTO
+    min_rto: Duration,
+    /// Upper bound of the RTO
+    max_rto: Duration,
+    /// Time after which an estimate that hasn't been updated is discarded
+    cache_lifetime: Duration,
+    /// Estimates keyed by server transport address
+    servers: HashMap<SocketAddr, RtoEntry>,
+}
+
+impl Default for StunRtoEstimator {
+    /// Creates an estimator with the default settings
+    fn default() -> Self {
+        Self {
+            initial_rto: Self::DEFAULT_INITIAL_RTO,
+            min_rto: Self::DEFAULT_MIN_RTO,
+            max_rto: Self::DEFAULT_MAX_RTO,
+            cache_lifetime: Self::DEFAULT_CACHE_LIFETIME,
+            servers: HashMap::new(),
+        }
+    }
+}
+
+impl StunRtoEstimator {
+    /// Default RTO used for servers without an estimate
+    pub const DEFAULT_INITIAL_RTO: Duration = StunClientTransaction::DEFAULT_RTO;
+    /// Default lower bound of the RTO
+    pub const DEFAULT_MIN_RTO: Duration = StunClientTransaction::DEFAULT_RTO;
+    /// Default upper bound of the RTO
+    pub const DEFAULT_MAX_RTO: Duration = Duration::from_secs(60);
+    /// Default time after which an estimate that hasn't been updated is discarded
+    pub const DEFAULT_CACHE_LIFETIME: Duration = Duration::from_secs(600);
+
+    /// Creates an estimator with the default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the RTO used for servers without an estimate
+    pub fn set_initial_rto(mut self, rto: Duration) -> Self {
+        self.initial_rto = rto;
+
+        self
+    }
+
+    /// Sets the lower bound of the RTO. Low values speed up the retransmissions on fast networks.
+    pub fn set_min_rto(mut self, rto: Duration) -> Self {
+        self.min_rto = rto;
+
+        self
+    }
+
+    /// Sets the upper bound of the RTO
+    pub fn set_max_rto(mut self, rto: Duration) -> Self {
+        self.max_rto = rto;
+
+        self
+    }
+
+    /// Sets the time after which an estimate that hasn't been updated is discarded
+    pub fn set_cache_lifetime(mut self, lifetime: Duration) -> Self {
+        self.cache_lifetime = lifetime;
+
+        self
+    }
+
+    /// Returns the RTO to start a new transaction to the server with
+    ///
+    /// Arguments:
+    ///
+    /// * `server`: Server transport address
+    /// * `now`: Current time
+    pub fn get_rto(&self, server: SocketAddr, now: Instant) -> Duration {
+        match self.servers.get(&server) {
+            Some(entry) if !self.is_stale(entry, now) => entry.rto,
+            _ => self.initial_rto,
+        }
+    }
+
+    /// Returns the smoothed round-trip time of the server, if it has been measured
+    ///
+    /// Arguments:
+    ///
+    /// * `server`: Server transport address
+    /// * `now`: Current time
+    pub fn get_srtt(&self, server: SocketAddr, now: Instant) -> Option<Duration> {
+        self.servers
+            .get(&server)
+            .filter(|entry| !self.is_stale(entry, now))
+            .and_then(|entry| entry.srtt)
+    }
+
+    /// Updates the estimate of the server with a measured round-trip time
+    ///
+    /// Arguments:
+    ///
+    /// * `server`: Server transport address
+    /// * `rtt`: Measured round-trip time. Must not be taken from retransmitted requests.
+    /// * `now`: Current time
+    pub fn add_rtt_sample(&mut self, server: SocketAddr, rtt: Duration, now: Instant) {
+        let (srtt, rttvar) = match self
+            .get_fresh_entry(server, now)
+            .and_then(|entry| entry.srtt.map(|srtt| (srtt, entry.rttvar)))
+        {
+            Some((srtt, rttvar)) => {
+                // `Duration::abs_diff` requires Rust 1.81
+                let deviation = srtt.max(rtt) - srtt.min(rtt);
+
+                ((srtt * 7 + rtt) / 8, (rttvar * 3 + deviation) / 4)
+            }
+            None => (rtt, rtt / 2),
+        };
+
+        let rto = self.clamp(srtt + rttvar * 4);
+
+        self.servers.insert(
+            server,
+            RtoEntry {
+                srtt: Some(srtt),
+                rttvar,
+                rto,
+                updated_at: now,
+            },
+        );
+    }
+
+    /// Backs off the RTO of the server after a transaction timeout by doubling it
+    ///
+    /// Arguments:
+    ///
+    /// * `server`: Server transport address
+    /// * `now`: Current time
+    pub fn add_timeout(&mut self, server: SocketAddr, now: Instant) {
+        let rto = self.clamp(self.get_rto(server, now) * 2);
+
+        match self.get_fresh_entry(server, now) {
+            Some(entry) => {
+                entry.rto = rto;
+                entry.updated_at = now;
+            }
+            None => {
+                self.servers.insert(
+                    server,
+                    RtoEntry {
+                        srtt: None,
+                        rttvar: Duration::from_secs(0),
+                        rto,
+                        updated_at: now,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Updates the estimate of the server with the outcome of a finished transaction
+    ///
+    /// A response to a request that hasn't been retransmitted provides an RTT sample.
+    /// A timeout backs off the RTO. Other transactions are ignored.
+    ///
+    /// Arguments:
+    ///
+    /// * `server`: Transport address the request has been sent to
+    /// * `transaction`: Finished transaction
+    /// * `now`: Time the transaction has finished at
+    pub fn update(
+        &mut self,
+        server: SocketAddr,
+        transaction: &StunClientTransaction,
+        now: Instant,
+    ) {
+        if !transaction.is_finished() {
+            return;
+        }
+
+        if let Some(rtt) = transaction.get_rtt_sample() {
+            self.add_rtt_sample(server, rtt, now);
+        } else if !transaction.has_response() {
+            self.add_timeout(server, now);
+        }
+    }
+
+    /// Discards the stale estimates
+    ///
+    /// Arguments:
+    ///
+    /// * `now`: Current time
+    pub fn purge(&mut self, now: Instant) {
+        let cache_lifetime = self.cache_lifetime;
+
+        self.servers
+            .retain(|_, entry| now.saturating_duration_since(entry.updated_at) <= cache_lifetime);
+    }
+
+    // Returns the estimate of the server unless it is stale
+    fn get_fresh_entry(&mut self, server: SocketAddr, now: Instant) -> Option<&mut RtoEntry> {
+        let cache_lifetime = self.cache_lifetime;
+
+        self.servers
+            .get_mut(&server)
+            .filter(|entry| now.saturating_duration_since(entry.updated_at) <= cache_lifetime)
+    }
+
+    // Checks whether the estimate hasn't been updated for the cache lifetime
+    fn is_stale(&self, entry: &RtoEntry, now: Instant) -> bool {
+        now.saturating_duration_since(entry.updated_at) > self.cache_lifetime
+    }
+
+    // Keeps the RTO within the configured bounds
+    fn clamp(&self, rto: Duration) -> Duration {
+        rto.max(self.min_rto).min(self.max_rto)
+    }
+}
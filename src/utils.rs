@@ -1,3 +1,4 @@
+use crate::channel_data::ChannelData;
 use crate::definitions::StunTransactionId;
 use crate::header::StunHeader;
 use rand::Rng;
@@ -10,7 +11,7 @@ use std::io::Cursor;
 /// Arguments:
 /// * `data_bytes`: Streaming data bytes that need to be checked. Note that only the first 20 bytes are processed.
 pub fn check_for_stun_message_header(data_bytes: &[u8]) -> Option<StunHeader> {
-    StunHeader::decode(&mut Cursor::new(data_bytes)).ok()
+    StunHeader::decode(&mut Cursor::new(data_bytes), false).ok()
 }
 
 /// Generates a random Transaction Id to be used in a StunMessage
@@ -22,3 +23,17 @@ pub fn generate_transaction_id() -> StunTransactionId {
 
     buf
 }
+
+/// Checks for a TURN ChannelData message header and returns its channel number
+///
+/// This function is intended to be used alongside `check_for_stun_message_header` in order to separate
+/// ChannelData messages from STUN messages received over a TURN client-server transport.
+/// The first two bits of a STUN message are always 0b00, while the channel numbers start with 0b01.
+///
+/// Arguments:
+/// * `data_bytes`: Streaming data bytes that need to be checked. Note that only the first 4 bytes are processed.
+pub fn check_for_channel_data_header(data_bytes: &[u8]) -> Option<u16> {
+    ChannelData::decode_header(data_bytes)
+        .ok()
+        .map(|(channel_number, _)| channel_number)
+}